@@ -0,0 +1,71 @@
+//! Benchmarks of parsing, editing and writing a save the size of one late in the game (see
+//! `fixtures::large_synthetic_save`), and of looking up properties by path with and without
+//! `PathIndex`.
+//!
+//! ```text
+//! $ cargo bench -p drg-save-core --features fixtures
+//! ```
+
+use std::path::Path;
+
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+use drg_save_core::editors::blue_number::{BlueNumberOptions, reset_blue_number};
+use drg_save_core::fixtures::large_synthetic_save;
+use drg_save_core::index::PathIndex;
+use drg_save_core::walk::property_at;
+
+const PATHS: &[&str] = &[
+    "CharacterSaves[2].RetiredCharacterLevels",
+    "Credits",
+    "Padding.ItemSaves[250].Value5",
+    "Padding.ItemSaves[499].Value9",
+];
+
+fn parse(c: &mut Criterion) {
+    let mut buf = vec![];
+    large_synthetic_save().write(&mut buf).unwrap();
+    c.bench_function("parse", |b| {
+        b.iter(|| drg_save_core::parse_save(black_box(&buf), Path::new("bench.sav")).unwrap())
+    });
+}
+
+fn edit(c: &mut Criterion) {
+    let options = BlueNumberOptions::default();
+    c.bench_function("edit blue-number", |b| {
+        b.iter_batched(
+            large_synthetic_save,
+            |mut save| reset_blue_number(&mut save, &options).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn write(c: &mut Criterion) {
+    let save = large_synthetic_save();
+    c.bench_function("write verified", |b| {
+        b.iter(|| drg_save_core::serialize_save_verified(black_box(&save)).unwrap())
+    });
+}
+
+fn lookup(c: &mut Criterion) {
+    let save = large_synthetic_save();
+    let props = &save.root.properties;
+    c.bench_function("lookup walk", |b| {
+        b.iter(|| {
+            for path in PATHS {
+                black_box(property_at(props, path).unwrap());
+            }
+        })
+    });
+    let mut index = PathIndex::new();
+    c.bench_function("lookup index", |b| {
+        b.iter(|| {
+            for path in PATHS {
+                black_box(index.get(props, path).unwrap());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, parse, edit, write, lookup);
+criterion_main!(benches);
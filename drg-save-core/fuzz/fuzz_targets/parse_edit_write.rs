@@ -0,0 +1,39 @@
+//! Feeds arbitrary bytes through parse → edit → write with the edits in [`EDITS`], which cover every
+//! editor, like `drg-save edit` does. Errors are fine; panics and written saves that don't parse
+//! back to what was written are not.
+//!
+//! ```text
+//! $ cd drg-save-core && cargo +nightly fuzz run parse_edit_write
+//! ```
+
+#![no_main]
+
+use std::path::Path;
+
+use clap::Command;
+use drg_save_core::editors::editors;
+use drg_save_core::expected_schema::check_preconditions;
+use drg_save_core::fixtures::{fixture_guid_db, with_guid_db, EDITS};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| with_guid_db(fixture_guid_db(), || parse_edit_write(data)));
+
+fn parse_edit_write(data: &[u8]) {
+    for (name, args) in EDITS {
+        let editor = editors().into_iter().find(|editor| editor.name() == *name).unwrap();
+        let Ok(mut save) = drg_save_core::parse_save(data, Path::new("fuzz.sav")) else {
+            return;
+        };
+        let matches = Command::new(*name)
+            .args(editor.args())
+            .try_get_matches_from(std::iter::once(*name).chain(args.iter().copied()))
+            .unwrap();
+        if check_preconditions(&save, name).is_err() || editor.apply(&mut save, &matches).is_err() {
+            continue;
+        }
+        if let Ok(written) = drg_save_core::serialize_save_verified(&save) {
+            let reparsed = drg_save_core::parse_save(&written, Path::new("written.sav")).unwrap();
+            assert_eq!(drg_save_core::find_difference(&save, &reparsed), None);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Strip or randomize account-identifying data from a save so it can be shared.
+//!
+//! Nothing here knows the exact layout of every DRG save version, so identifying data is detected
+//! heuristically: properties whose name looks account-related, and any string that looks like a
+//! Steam64 ID regardless of where it is stored. Everything else, including the structure of the
+//! save, is preserved.
+
+use uesave::{Properties, Property, PropertyValue, Save, StructValue, ValueArray, ValueVec};
+use uuid::Uuid;
+
+use crate::walk::walk_properties_mut;
+
+/// Property name fragments (compared case-insensitively) that mark a property as identifying.
+const IDENTIFYING_NAME_FRAGMENTS: &[&str] = &[
+    "steam",
+    "playerid",
+    "playerguid",
+    "playername",
+    "userid",
+    "username",
+    "accountid",
+    "charactername",
+    "nickname",
+    "anonymousid",
+];
+
+/// What a Steam64 ID string is replaced with. Keeps the length so the save size doesn't give the
+/// original away either.
+const ANONYMOUS_STEAM_ID: &str = "00000000000000000";
+const ANONYMOUS_NAME: &str = "Anonymous";
+
+/// Anonymize `save` in place, returning the paths of all properties that were changed.
+pub fn anonymize(save: &mut Save) -> Vec<String> {
+    anonymize_properties(&mut save.root.properties)
+}
+
+fn anonymize_properties(props: &mut Properties) -> Vec<String> {
+    let mut changed = vec![];
+    walk_properties_mut(props, &mut |path, prop| {
+        let name = path.rsplit('.').next().unwrap_or(path);
+        if anonymize_property(is_identifying_name(name), prop) {
+            changed.push(path.to_string());
+        }
+    });
+    changed
+}
+
+pub(crate) fn is_identifying_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    IDENTIFYING_NAME_FRAGMENTS.iter().any(|fragment| name.contains(fragment))
+}
+
+/// Whether `s` looks like a Steam64 ID.
+pub fn is_steam_id(s: &str) -> bool {
+    s.len() == 17 && s.starts_with("7656119") && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn anonymize_string(identifying: bool, s: &mut String) -> bool {
+    let replacement = if is_steam_id(s) {
+        ANONYMOUS_STEAM_ID
+    } else if identifying && !s.is_empty() {
+        ANONYMOUS_NAME
+    } else {
+        return false;
+    };
+    if s == replacement {
+        return false;
+    }
+    *s = replacement.to_string();
+    true
+}
+
+fn anonymize_property(identifying: bool, prop: &mut Property) -> bool {
+    match prop {
+        Property::Str { value, .. } | Property::Name { value, .. } => {
+            anonymize_string(identifying, value)
+        }
+        Property::Int64 { value, .. } if identifying && *value != 0 => {
+            *value = 0;
+            true
+        }
+        Property::UInt64 { value, .. } if identifying && *value != 0 => {
+            *value = 0;
+            true
+        }
+        Property::Struct { value: StructValue::Guid(guid), .. } if identifying => {
+            *guid = Uuid::new_v4();
+            true
+        }
+        Property::Array {
+            value: ValueArray::Base(ValueVec::Str(values) | ValueVec::Name(values)),
+            ..
+        } => {
+            let mut changed = false;
+            for s in values {
+                changed |= anonymize_string(identifying, s);
+            }
+            changed
+        }
+        // Struct values are visited by the walk, but keys and scalar values aren't.
+        Property::Map { value, .. } => {
+            let mut changed = false;
+            for entry in value {
+                changed |= match &mut entry.key {
+                    PropertyValue::Struct(StructValue::Struct(props)) => {
+                        !anonymize_properties(props).is_empty()
+                    }
+                    key => anonymize_value(identifying, key),
+                };
+                changed |= anonymize_value(identifying, &mut entry.value);
+            }
+            changed
+        }
+        _ => false,
+    }
+}
+
+fn anonymize_value(identifying: bool, value: &mut PropertyValue) -> bool {
+    match value {
+        PropertyValue::Str(s) | PropertyValue::Name(s) => anonymize_string(identifying, s),
+        PropertyValue::Int64(value) if identifying && *value != 0 => {
+            *value = 0;
+            true
+        }
+        PropertyValue::Struct(StructValue::Guid(guid)) if identifying => {
+            *guid = Uuid::new_v4();
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::{MapEntry, PropertyKey, PropertyType};
+
+    use super::*;
+    use crate::fixtures::sample_save;
+
+    const STEAM_ID: &str = "76561198000000042";
+
+    #[test]
+    fn anonymizes_map_keys_and_values() {
+        let mut save = sample_save();
+        let map = |entries: Vec<MapEntry>| Property::Map {
+            id: None,
+            key_type: PropertyType::StrProperty,
+            value_type: PropertyType::StrProperty,
+            value: entries,
+        };
+        let str_entry = |key: &str, value: &str| MapEntry {
+            key: PropertyValue::Str(key.to_string()),
+            value: PropertyValue::Str(value.to_string()),
+        };
+        let props = &mut save.root.properties.0;
+        props.insert(PropertyKey::from("PlayerNames"), map(vec![str_entry(STEAM_ID, "Karl")]));
+        props.insert(PropertyKey::from("LastTeam"), map(vec![str_entry("Host", STEAM_ID)]));
+        let anonymous_id = "2370242411279145901181671422494311252531404095205";
+
+        let changed = anonymize(&mut save);
+        assert_eq!(changed, ["AnonymousID", "PlayerNames", "LastTeam"]);
+        let json = serde_json::to_string(&save.root).unwrap();
+        for identifying in [STEAM_ID, "Karl", anonymous_id] {
+            assert!(!json.contains(identifying), "`{identifying}` is still in the save");
+        }
+        // Other strings in maps are kept.
+        assert!(json.contains("Host"));
+    }
+
+    #[test]
+    fn leaves_anonymized_saves_unchanged() {
+        let mut save = sample_save();
+        anonymize(&mut save);
+        assert!(anonymize(&mut save).is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+//! Blue level (player rank) math.
+//!
+//! Each class save slot contributes `25 * TimesRetired + RetiredCharacterLevels` red levels, and
+//! the blue level is the sum over all class save slots (including the hidden one) divided by 3.
+//! Both can be negative. Values too large for an `i32` are rejected rather than wrapped.
+
+use uesave::{Property, StructValue};
+
+use crate::error::{DrgSaveError, Result};
+use crate::util::property;
+
+/// Red levels a single promotion is worth.
+pub const RED_LEVELS_PER_PROMOTION: i32 = 25;
+
+/// Red levels contributed to the blue level by a single class save slot.
+pub fn red_levels(class_save: &StructValue) -> Result<i32> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let int = |name: &str| match property(props, name) {
+        Some(Property::Int { value, .. }) => Ok(*value),
+        Some(prop) => Err(DrgSaveError::type_mismatch(name, "Int", prop)),
+        None => Err(DrgSaveError::missing(name)),
+    };
+    red_levels_for(int("TimesRetired")?, int("RetiredCharacterLevels")?)
+}
+
+/// Red levels contributed by a class save slot with `times_retired` promotions and
+/// `retired_levels` retired character levels.
+pub fn red_levels_for(times_retired: i32, retired_levels: i32) -> Result<i32> {
+    RED_LEVELS_PER_PROMOTION
+        .checked_mul(times_retired)
+        .and_then(|red_levels| red_levels.checked_add(retired_levels))
+        .ok_or_else(|| {
+            DrgSaveError::InvalidInput(format!(
+                "{times_retired} promotions and {retired_levels} retired levels are too many red \
+                 levels"
+            ))
+        })
+}
+
+/// Blue level resulting from `class_saves`. Like the game this uses integer division, so partial
+/// blue levels are truncated towards zero.
+pub fn blue_level(class_saves: &[StructValue]) -> Result<i32> {
+    let mut total_red_levels: i32 = 0;
+    for class_save in class_saves {
+        total_red_levels =
+            total_red_levels.checked_add(red_levels(class_save)?).ok_or_else(|| {
+                DrgSaveError::InvalidInput("the class saves have too many red levels".to_string())
+            })?;
+    }
+    Ok(blue_level_for(total_red_levels))
+}
+
+/// Blue level resulting from `total_red_levels` over all class save slots.
+pub fn blue_level_for(total_red_levels: i32) -> i32 {
+    total_red_levels / 3
+}
+
+/// Total red levels over all class save slots needed to end up at `target_blue_level`.
+pub fn red_levels_needed_for(target_blue_level: i32) -> Result<i32> {
+    target_blue_level.checked_mul(3).ok_or_else(|| {
+        DrgSaveError::InvalidInput(format!("blue level {target_blue_level} is out of range"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::{Properties, PropertyKey};
+
+    use super::*;
+
+    fn class_save(times_retired: i32, retired_levels: i32) -> StructValue {
+        let mut props = Properties::default();
+        props.0.insert(
+            PropertyKey::from("TimesRetired"),
+            Property::Int { id: None, value: times_retired },
+        );
+        props.0.insert(
+            PropertyKey::from("RetiredCharacterLevels"),
+            Property::Int { id: None, value: retired_levels },
+        );
+        StructValue::Struct(props)
+    }
+
+    #[test]
+    fn red_levels_of_class() {
+        assert_eq!(red_levels(&class_save(0, 0)).unwrap(), 0);
+        assert_eq!(red_levels(&class_save(1, 25)).unwrap(), 50);
+        assert_eq!(red_levels(&class_save(0, -10)).unwrap(), -10);
+    }
+
+    #[test]
+    fn red_levels_missing_property() {
+        let mut props = Properties::default();
+        props.0.insert(PropertyKey::from("TimesRetired"), Property::Int { id: None, value: 1 });
+        assert!(red_levels(&StructValue::Struct(props)).is_err());
+        assert!(red_levels(&StructValue::Guid(Default::default())).is_err());
+    }
+
+    #[test]
+    fn blue_level_sums_all_classes() {
+        let class_saves =
+            [class_save(1, 25), class_save(1, 25), class_save(0, 2), class_save(0, 0)];
+        assert_eq!(blue_level(&class_saves).unwrap(), 34);
+        assert_eq!(blue_level(&[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn blue_level_truncates_towards_zero() {
+        assert_eq!(blue_level(&[class_save(0, 2)]).unwrap(), 0);
+        assert_eq!(blue_level(&[class_save(0, 5)]).unwrap(), 1);
+        assert_eq!(blue_level(&[class_save(0, -2)]).unwrap(), 0);
+        assert_eq!(blue_level(&[class_save(0, -5)]).unwrap(), -1);
+    }
+
+    #[test]
+    fn negative_blue_level() {
+        // What blue-number-resetter produces: 4 active classes at 1 promotion + 25 retired levels,
+        // with the hidden class making up the difference.
+        let target = -69;
+        let hidden = red_levels_needed_for(target).unwrap() - 4 * 50;
+        let mut class_saves: Vec<_> = (0..4).map(|_| class_save(1, 25)).collect();
+        class_saves.push(class_save(0, hidden));
+        assert_eq!(blue_level(&class_saves).unwrap(), target);
+    }
+
+    #[test]
+    fn red_levels_needed_round_trips() {
+        for target in [-69, -1, 0, 1, 100] {
+            assert_eq!(
+                blue_level(&[class_save(0, red_levels_needed_for(target).unwrap())]).unwrap(),
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn overflow_is_invalid_input() {
+        assert!(matches!(red_levels_for(i32::MAX, 1), Err(DrgSaveError::InvalidInput(_))));
+        assert_eq!(red_levels_for(0, i32::MIN).unwrap(), i32::MIN);
+        assert!(matches!(red_levels_for(-1, i32::MIN), Err(DrgSaveError::InvalidInput(_))));
+        assert!(matches!(red_levels_needed_for(i32::MAX), Err(DrgSaveError::InvalidInput(_))));
+        assert!(matches!(
+            blue_level(&[class_save(0, i32::MAX), class_save(0, 1)]),
+            Err(DrgSaveError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! Access to the per-class `CharacterSave` structs.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use uesave::{Property, PropertyType, Save, StructType, StructValue, ValueArray};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::{GuidDb, GuidKind};
+use crate::util::{clone_via_serde, int_mut, property, property_mut};
+
+/// `SavegameID` of the unused class slot. It is not shown in-game but still contributes to the
+/// blue level.
+pub const HIDDEN_CLASS_SAVEGAME_ID: Uuid = uuid::uuid!("d6d5686e-4547-e66f-46c5-ce8e28b16827");
+
+/// Number of class save slots: 4 active classes and 1 hidden class.
+pub const N_CLASS_SAVES: usize = 5;
+
+/// Find the `CharacterSaves` array, checking that it has the expected shape.
+pub fn class_saves(save: &Save) -> Result<&Vec<StructValue>> {
+    let (array_type, value) = match property(&save.root.properties, "CharacterSaves") {
+        Some(Property::Array { array_type, value, .. }) => (array_type, value),
+        Some(prop) => return Err(DrgSaveError::type_mismatch("CharacterSaves", "Array", prop)),
+        None => return Err(DrgSaveError::missing("CharacterSaves")),
+    };
+    check_array_type(array_type)?;
+    check_class_saves_shape(value)
+}
+
+/// Mutable counterpart of [`class_saves`].
+pub fn class_saves_mut(save: &mut Save) -> Result<&mut Vec<StructValue>> {
+    let (array_type, value) = match property_mut(&mut save.root.properties, "CharacterSaves") {
+        Some(Property::Array { array_type, value, .. }) => (array_type, value),
+        Some(prop) => return Err(DrgSaveError::type_mismatch("CharacterSaves", "Array", prop)),
+        None => return Err(DrgSaveError::missing("CharacterSaves")),
+    };
+    check_array_type(array_type)?;
+    check_class_saves_shape(&*value)?;
+    let ValueArray::Struct { value: class_saves, .. } = value else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    Ok(class_saves)
+}
+
+fn check_array_type(array_type: &PropertyType) -> Result<()> {
+    if *array_type != PropertyType::StructProperty {
+        return Err(DrgSaveError::UnexpectedShape(format!(
+            "unexpected `CharacterSaves` array type {array_type:?}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_class_saves_shape(value: &ValueArray) -> Result<&Vec<StructValue>> {
+    let shape_error = |message: &str| Err(DrgSaveError::UnexpectedShape(message.to_string()));
+    // Single struct property inside the array
+    let ValueArray::Struct { _type, name, struct_type, value: class_saves, .. } = value else {
+        return shape_error("unexpected length for character saves array");
+    };
+    if _type != "CharacterSaves" {
+        return shape_error("unexpected value array `_type`");
+    }
+    if name != "StructProperty" {
+        return shape_error("unexpected value array `name`");
+    }
+    if *struct_type != StructType::Struct(Some("CharacterSave".to_string())) {
+        return shape_error("unexpected value array `struct_type`");
+    }
+    if class_saves.len() != N_CLASS_SAVES {
+        return shape_error(&format!("expected {N_CLASS_SAVES} class save slots"));
+    }
+    Ok(class_saves)
+}
+
+/// The `SavegameID` identifying a class save slot, if it has one.
+pub fn savegame_id(class_save: &StructValue) -> Option<Uuid> {
+    let StructValue::Struct(props) = class_save else {
+        return None;
+    };
+    let Some(Property::Struct {
+        value: StructValue::Guid(id), struct_type: StructType::Guid, ..
+    }) = property(props, "SavegameID")
+    else {
+        return None;
+    };
+    Some(*id)
+}
+
+/// Find the index of the class save slot with the given `SavegameID`.
+pub fn find_class_save(class_saves: &[StructValue], id: Uuid) -> Result<usize> {
+    class_saves.iter().position(|class_save| savegame_id(class_save) == Some(id)).ok_or_else(|| {
+        DrgSaveError::InvalidInput(format!("no class save slot with `SavegameID` {id}"))
+    })
+}
+
+/// Overwrite the class save slot at index `to` with a copy of the one at `from`, keeping the
+/// `SavegameID` of `to` so the game still recognizes it as the same class.
+pub fn clone_class_save(class_saves: &mut [StructValue], from: usize, to: usize) -> Result<()> {
+    if from == to {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "cannot clone class save slot {from} onto itself"
+        )));
+    }
+    let Some(target_id) = class_saves.get(to).and_then(savegame_id) else {
+        return Err(DrgSaveError::missing(format!("CharacterSaves[{to}].SavegameID")));
+    };
+    let Some(source) = class_saves.get(from) else {
+        return Err(DrgSaveError::InvalidInput(format!("class save slot {from} does not exist")));
+    };
+
+    let mut cloned = clone_via_serde(source)?;
+    let StructValue::Struct(props) = &mut cloned else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let Some(Property::Struct { value: StructValue::Guid(id), .. }) =
+        property_mut(props, "SavegameID")
+    else {
+        return Err(DrgSaveError::missing(format!("CharacterSaves[{from}].SavegameID")));
+    };
+    *id = target_id;
+
+    class_saves[to] = cloned;
+    Ok(())
+}
+
+/// Reset the progression of `class_save` to that of a freshly created class: level 1 and zero
+/// promotions. The loadout is left alone.
+pub fn reset_class_save(class_save: &mut StructValue) -> Result<()> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    *int_mut(props, "XP")? = 0;
+    *int_mut(props, "TimesRetired")? = 0;
+    *int_mut(props, "RetiredCharacterLevels")? = 0;
+    Ok(())
+}
+
+/// A class save slot, given either by its index in `CharacterSaves` or by its class name in the
+/// [`GuidDb`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassSelector {
+    Slot(usize),
+    Name(String),
+}
+
+impl FromStr for ClassSelector {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse() {
+            Ok(slot) => Self::Slot(slot),
+            Err(_) => Self::Name(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ClassSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Slot(slot) => write!(f, "class save slot {slot}"),
+            Self::Name(name) => write!(f, "class `{name}`"),
+        }
+    }
+}
+
+/// Find the index of the class save slot `selector` refers to.
+pub fn resolve_class(
+    class_saves: &[StructValue],
+    selector: &ClassSelector,
+    db: &GuidDb,
+) -> Result<usize> {
+    match selector {
+        ClassSelector::Slot(slot) => {
+            if *slot >= class_saves.len() {
+                return Err(DrgSaveError::InvalidInput(format!(
+                    "class save slot must be less than {}",
+                    class_saves.len()
+                )));
+            }
+            Ok(*slot)
+        }
+        ClassSelector::Name(name) => {
+            let Some(id) = db.find(GuidKind::Class, name) else {
+                return Err(DrgSaveError::InvalidInput(format!("unknown class `{name}`")));
+            };
+            find_class_save(class_saves, id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blue_level::red_levels;
+    use crate::cosmetics::{equipped, CosmeticSlot};
+    use crate::fixtures::{synthetic_save, BEARDS, ENGINEER, GUNNER, VICTORY_MOVE};
+
+    fn xp(class_save: &StructValue) -> i32 {
+        let StructValue::Struct(props) = class_save else { unreachable!() };
+        match property(props, "XP") {
+            Some(Property::Int { value, .. }) => *value,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn clones_progression_and_cosmetics_keeping_the_savegame_id() {
+        let mut save = synthetic_save();
+        let class_saves = class_saves_mut(&mut save).unwrap();
+        // The Gunner onto the Engineer.
+        clone_class_save(class_saves, 2, 1).unwrap();
+
+        let (engineer, gunner) = (&class_saves[1], &class_saves[2]);
+        assert_eq!(savegame_id(engineer), Some(ENGINEER));
+        assert_eq!(savegame_id(gunner), Some(GUNNER));
+        assert_eq!(xp(engineer), 315_000);
+        assert_eq!(red_levels(engineer).unwrap(), red_levels(gunner).unwrap());
+        for (loadout, beard) in [BEARDS[2], BEARDS[1]].into_iter().enumerate() {
+            assert_eq!(equipped(engineer, CosmeticSlot::Beard, loadout).unwrap(), beard);
+        }
+        assert_eq!(equipped(engineer, CosmeticSlot::VictoryMove, 0).unwrap(), VICTORY_MOVE);
+    }
+
+    #[test]
+    fn refuses_to_clone_onto_the_same_slot_or_missing_slots() {
+        let mut save = synthetic_save();
+        let class_saves = class_saves_mut(&mut save).unwrap();
+        assert!(clone_class_save(class_saves, 1, 1).is_err());
+        assert!(clone_class_save(class_saves, 9, 1).is_err());
+        assert!(clone_class_save(class_saves, 1, 9).is_err());
+    }
+}
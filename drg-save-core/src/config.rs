@@ -0,0 +1,202 @@
+//! User configuration in `config.toml` in the `drg-save` config directory (e.g.
+//! `~/.config/drg-save/config.toml`). Every setting is optional, and command line flags override
+//! it.
+//!
+//! ```toml
+//! save-path = "/path/to/SaveGames/76561198000000000_Player.sav"
+//! backup-dir = "/path/to/backups"
+//! backup-retention = 10
+//! output = "json"
+//! encrypt-snapshots = true
+//!
+//! [editors.blue-number]
+//! target = -69
+//!
+//! # Selected with `--profile alt`, overriding the settings above.
+//! [profiles.alt]
+//! save-path = "/path/to/SaveGames/76561198000000001_Player.sav"
+//! backup-dir = "/path/to/alt-backups"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+use crate::io::BackupOptions;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Save file used when a command is not given one.
+    pub save_path: Option<PathBuf>,
+    /// Directory to write backups to instead of next to the save.
+    pub backup_dir: Option<PathBuf>,
+    /// Number of backups to keep per save.
+    pub backup_retention: Option<usize>,
+    /// Whether to back up saves before writing them. Defaults to true.
+    pub backup: Option<bool>,
+    /// Whether to encrypt new snapshots with a passphrase. Defaults to false.
+    pub encrypt_snapshots: Option<bool>,
+    /// Default output format (`text` or `json`).
+    pub output: Option<String>,
+    /// Default arguments per editor, by editor name and argument name.
+    pub editors: BTreeMap<String, BTreeMap<String, toml::Value>>,
+    /// Named sets of save and backup locations, e.g. for several accounts.
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Save and backup locations used instead of the top-level ones when the profile is selected.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    pub save_path: Option<PathBuf>,
+    pub backup_dir: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("drg-save").join("config.toml"))
+    }
+
+    /// Load the config file, or the default config if there is none.
+    pub fn load() -> Result<Self> {
+        match Self::path().filter(|path| path.exists()) {
+            Some(path) => toml::from_str(&fs::read_to_string(&path)?)
+                .with_context(|| format!("failed to parse config file `{}`", path.display())),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Apply the settings of the profile called `name` over the top-level ones.
+    pub fn select_profile(&mut self, name: &str) -> Result<()> {
+        let profile = self
+            .profiles
+            .get(name)
+            .with_context(|| format!("no profile `{name}` in the config file"))?;
+        if profile.save_path.is_some() {
+            self.save_path = profile.save_path.clone();
+        }
+        if profile.backup_dir.is_some() {
+            self.backup_dir = profile.backup_dir.clone();
+        }
+        Ok(())
+    }
+
+    pub fn backup_options(&self) -> BackupOptions {
+        BackupOptions {
+            dir: self.backup_dir.clone(),
+            retention: self.backup_retention,
+            disabled: self.backup == Some(false),
+        }
+    }
+
+    /// Default arguments of the editor called `name` that aren't among `given`, its arguments from
+    /// the command line, so those replace the defaults instead of adding to them. A `true` default
+    /// becomes a `--<arg>` flag (`false` leaves it out), an array one `--<arg> <value>` pair per
+    /// element, and anything else a `--<arg> <value>` pair.
+    pub fn editor_args(&self, name: &str, given: &[String]) -> Vec<String> {
+        let Some(args) = self.editors.get(name) else {
+            return vec![];
+        };
+        let is_given = |arg: &str| {
+            given.iter().any(|given| {
+                given
+                    .strip_prefix("--")
+                    .and_then(|given| given.strip_prefix(arg))
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('='))
+            })
+        };
+        let mut defaults = vec![];
+        for (arg, value) in args.iter().filter(|(arg, _)| !is_given(arg)) {
+            match value {
+                toml::Value::Boolean(true) => defaults.push(format!("--{arg}")),
+                toml::Value::Boolean(false) => {}
+                toml::Value::Array(values) => {
+                    for value in values {
+                        defaults.extend([format!("--{arg}"), value_arg(value)]);
+                    }
+                }
+                value => defaults.extend([format!("--{arg}"), value_arg(value)]),
+            }
+        }
+        defaults
+    }
+}
+
+fn value_arg(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Add the profile `name` to the config file, replacing a profile of the same name. The rest of
+/// the file, including comments, is left as it is.
+pub fn add_profile(name: &str, profile: &Profile) -> Result<()> {
+    edit_config_file(|doc| {
+        let profiles = doc
+            .entry("profiles")
+            .or_insert_with(|| {
+                let mut table = toml_edit::Table::new();
+                table.set_implicit(true);
+                toml_edit::Item::Table(table)
+            })
+            .as_table_mut()
+            .context("`profiles` in the config file is not a table")?;
+        let table = toml_edit::ser::to_document(profile)?.as_table().clone();
+        profiles.insert(name, toml_edit::Item::Table(table));
+        Ok(())
+    })
+}
+
+/// Remove the profile `name` from the config file. Returns whether there was one.
+pub fn remove_profile(name: &str) -> Result<bool> {
+    let mut removed = false;
+    edit_config_file(|doc| {
+        if let Some(profiles) = doc.get_mut("profiles").and_then(|item| item.as_table_mut()) {
+            removed = profiles.remove(name).is_some();
+        }
+        Ok(())
+    })?;
+    Ok(removed)
+}
+
+fn edit_config_file(edit: impl FnOnce(&mut toml_edit::DocumentMut) -> Result<()>) -> Result<()> {
+    let path = Config::path().context("no config directory for this platform")?;
+    let mut doc = toml_edit::DocumentMut::new();
+    if path.exists() {
+        doc = fs::read_to_string(&path)?
+            .parse()
+            .with_context(|| format!("failed to parse config file `{}`", path.display()))?;
+    }
+    edit(&mut doc)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, doc.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_arguments_replace_editor_defaults() {
+        let config: Config = toml::from_str(
+            "[editors.blue-number]\ntarget = -69\nlegit = true\ndry-run = false\n\
+             skip = [\"a\", \"b\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.editor_args("blue-number", &[]),
+            ["--legit", "--skip", "a", "--skip", "b", "--target", "-69"]
+        );
+        let given = ["--skip=c".to_string(), "--target".to_string(), "1".to_string()];
+        assert_eq!(config.editor_args("blue-number", &given), ["--legit"]);
+        assert!(config.editor_args("other", &[]).is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+//! The victory move and cosmetic slots a class has equipped, each stored as a GUID in its
+//! `CharacterSave`.
+//!
+//! As in the sample save uesave is tested with (`drg-save-test.sav`), the victory move is
+//! `VictoryPose.EquippedVictoryPose`, while the other slots are `Equipped...ItemID` fields of the
+//! vanity loadouts in `Vanity.Loadouts`, of which `SelectedLoadout` is the one in use.
+//!
+//! Items from DLC the account doesn't own, e.g. after merging saves or importing a loadout, show
+//! up oddly in game. [`unowned_dlc_cosmetics`] finds them by the DLC recorded in the GUID database,
+//! and [`default_cosmetic`] finds what the class had equipped before.
+
+use std::fmt;
+use std::str::FromStr;
+
+use uesave::{Property, StructValue, ValueArray};
+use uuid::Uuid;
+
+use crate::classes::savegame_id;
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::{GuidDb, GuidKind};
+use crate::util::property;
+use crate::walk::{property_at, property_at_mut};
+
+/// Path in a `CharacterSave` of the vanity loadouts.
+pub const VANITY_LOADOUTS: &str = "Vanity.Loadouts";
+/// Name of the `CharacterSave` property holding the index of the loadout in use.
+pub const SELECTED_LOADOUT: &str = "SelectedLoadout";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CosmeticSlot {
+    VictoryMove,
+    Helmet,
+    Beard,
+    Moustache,
+    Sideburns,
+    Eyebrows,
+    BeardColor,
+    SkinColor,
+    Armor,
+    Paintjob,
+}
+
+impl CosmeticSlot {
+    pub const ALL: [Self; 10] = [
+        Self::VictoryMove,
+        Self::Helmet,
+        Self::Beard,
+        Self::Moustache,
+        Self::Sideburns,
+        Self::Eyebrows,
+        Self::BeardColor,
+        Self::SkinColor,
+        Self::Armor,
+        Self::Paintjob,
+    ];
+
+    /// Path in a `CharacterSave` of the property holding the GUID equipped in this slot in vanity
+    /// loadout `loadout`. The victory move is the same for every loadout.
+    pub fn path(self, loadout: usize) -> String {
+        let field = match self {
+            Self::VictoryMove => return "VictoryPose.EquippedVictoryPose".to_string(),
+            Self::Helmet => "EquippedHeadItemID",
+            Self::Beard => "EquippedBeardItemID",
+            Self::Moustache => "EquippedMoustacheItemID",
+            Self::Sideburns => "EquippedSideburnsItemID",
+            Self::Eyebrows => "EquippedEyebrowsItemID",
+            Self::BeardColor => "EquippedBeardColorItemID",
+            Self::SkinColor => "EquippedSkinColorItemID",
+            Self::Armor => "EquippedArmorItemID",
+            Self::Paintjob => "EquippedArmorMaterialID",
+        };
+        format!("{VANITY_LOADOUTS}[{loadout}].{field}")
+    }
+
+    /// Kind of the [`GuidDb`] entries that can be equipped in this slot.
+    pub fn guid_kind(self) -> GuidKind {
+        match self {
+            Self::VictoryMove => GuidKind::VictoryMove,
+            _ => GuidKind::Cosmetic,
+        }
+    }
+}
+
+impl FromStr for CosmeticSlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|slot| slot.to_string() == s).ok_or_else(|| {
+            let slots: Vec<_> = Self::ALL.iter().map(|slot| format!("`{slot}`")).collect();
+            format!("expected one of {}, got `{s}`", slots.join(", "))
+        })
+    }
+}
+
+impl fmt::Display for CosmeticSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::VictoryMove => "victory-move",
+            Self::Helmet => "helmet",
+            Self::Beard => "beard",
+            Self::Moustache => "moustache",
+            Self::Sideburns => "sideburns",
+            Self::Eyebrows => "eyebrows",
+            Self::BeardColor => "beard-color",
+            Self::SkinColor => "skin-color",
+            Self::Armor => "armor",
+            Self::Paintjob => "paintjob",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The index of the loadout `class_save` has in use.
+pub fn selected_loadout(class_save: &StructValue) -> Result<usize> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    match property(props, SELECTED_LOADOUT) {
+        Some(Property::Int { value, .. }) => usize::try_from(*value).map_err(|_| {
+            DrgSaveError::InvalidInput(format!("`{SELECTED_LOADOUT}` is negative: {value}"))
+        }),
+        Some(prop) => Err(DrgSaveError::type_mismatch(SELECTED_LOADOUT, "Int", prop)),
+        None => Err(DrgSaveError::missing(SELECTED_LOADOUT)),
+    }
+}
+
+/// The number of vanity loadouts of `class_save`, 0 if it has none.
+pub fn vanity_loadouts(class_save: &StructValue) -> usize {
+    let StructValue::Struct(props) = class_save else {
+        return 0;
+    };
+    match property_at(props, VANITY_LOADOUTS) {
+        Some(Property::Array { value: ValueArray::Struct { value, .. }, .. }) => value.len(),
+        _ => 0,
+    }
+}
+
+/// The GUID equipped in `slot` of vanity loadout `loadout` of `class_save`.
+pub fn equipped(class_save: &StructValue, slot: CosmeticSlot, loadout: usize) -> Result<Uuid> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let path = slot.path(loadout);
+    match property_at(props, &path) {
+        Some(Property::Struct { value: StructValue::Guid(guid), .. }) => Ok(*guid),
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, "Guid Struct", prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
+
+/// Equip `guid` in `slot` of vanity loadout `loadout` of `class_save`, returning the GUID
+/// equipped before.
+pub fn equip(
+    class_save: &mut StructValue,
+    slot: CosmeticSlot,
+    loadout: usize,
+    guid: Uuid,
+) -> Result<Uuid> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let path = slot.path(loadout);
+    match property_at_mut(props, &path) {
+        Some(Property::Struct { value: StructValue::Guid(equipped), .. }) => {
+            Ok(std::mem::replace(equipped, guid))
+        }
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, "Guid Struct", prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
+
+/// Find the GUID of the item called `name` (or given as a GUID) that can be equipped in `slot`.
+pub fn resolve_cosmetic(slot: CosmeticSlot, name: &str, db: &GuidDb) -> Result<Uuid> {
+    if let Ok(guid) = name.parse() {
+        return Ok(guid);
+    }
+    db.require(slot.guid_kind())?;
+    db.find(slot.guid_kind(), name)
+        .ok_or_else(|| DrgSaveError::InvalidInput(format!("unknown {slot} `{name}`")))
+}
+
+/// The item the class of `class_save` has equipped in `slot` from the start, according to the
+/// GUID database.
+pub fn default_cosmetic(class_save: &StructValue, slot: CosmeticSlot, db: &GuidDb) -> Result<Uuid> {
+    db.require(slot.guid_kind())?;
+    let class = savegame_id(class_save).and_then(|id| db.name(id));
+    db.entries
+        .iter()
+        .find(|entry| {
+            entry.default
+                && entry.kind == slot.guid_kind()
+                && (slot == CosmeticSlot::VictoryMove
+                    || entry.slot.as_deref() == Some(slot.to_string().as_str()))
+                && entry.class.as_deref().zip(class).is_some_and(|(a, b)| a.eq_ignore_ascii_case(b))
+        })
+        .map(|entry| entry.guid)
+        .ok_or_else(|| {
+            DrgSaveError::InvalidInput(format!(
+                "the GUID database has no default {slot} of {}, pass `--substitute {slot}=<item>`",
+                class.unwrap_or("this class")
+            ))
+        })
+}
+
+/// An equipped item from a DLC the account doesn't own.
+#[derive(Debug, Clone)]
+pub struct DlcCosmetic {
+    /// Index of the class save slot in `CharacterSaves`.
+    pub class_slot: usize,
+    pub slot: CosmeticSlot,
+    /// Index of the vanity loadout, 0 for the victory move.
+    pub loadout: usize,
+    pub guid: Uuid,
+    pub dlc: String,
+}
+
+/// The items equipped in any vanity loadout of any class that the GUID database lists as coming
+/// with a DLC not in `owned_dlc` (compared ignoring case). Slots a class doesn't have are skipped.
+pub fn unowned_dlc_cosmetics(
+    class_saves: &[StructValue],
+    db: &GuidDb,
+    owned_dlc: &[String],
+) -> Vec<DlcCosmetic> {
+    let mut unowned = vec![];
+    for (class_slot, class_save) in class_saves.iter().enumerate() {
+        for slot in CosmeticSlot::ALL {
+            let loadouts = match slot {
+                CosmeticSlot::VictoryMove => 1,
+                _ => vanity_loadouts(class_save),
+            };
+            for loadout in 0..loadouts {
+                let Ok(guid) = equipped(class_save, slot, loadout) else {
+                    continue;
+                };
+                let Some(dlc) = db.dlc(guid) else {
+                    continue;
+                };
+                if !owned_dlc.iter().any(|owned| owned.eq_ignore_ascii_case(dlc)) {
+                    unowned.push(DlcCosmetic {
+                        class_slot,
+                        slot,
+                        loadout,
+                        guid,
+                        dlc: dlc.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    unowned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::class_saves;
+    use crate::fixtures::{fixture_guid_db, synthetic_save, with_guid_db, BEARDS, VICTORY_MOVE};
+
+    #[test]
+    fn reads_the_selected_vanity_loadout() {
+        let save = synthetic_save();
+        let driller = &class_saves(&save).unwrap()[0];
+        assert_eq!(selected_loadout(driller).unwrap(), 0);
+        assert_eq!(vanity_loadouts(driller), 2);
+        assert_eq!(equipped(driller, CosmeticSlot::Beard, 0).unwrap(), BEARDS[0]);
+        assert_eq!(equipped(driller, CosmeticSlot::Beard, 1).unwrap(), BEARDS[2]);
+        assert_eq!(equipped(driller, CosmeticSlot::VictoryMove, 0).unwrap(), VICTORY_MOVE);
+        // The hidden class has no cosmetics.
+        let hidden = &class_saves(&save).unwrap()[4];
+        assert_eq!(vanity_loadouts(hidden), 0);
+        assert!(equipped(hidden, CosmeticSlot::Beard, 0).is_err());
+    }
+
+    #[test]
+    fn finds_unowned_dlc_items_in_every_loadout() {
+        let save = synthetic_save();
+        let class_saves = class_saves(&save).unwrap();
+        let db = fixture_guid_db();
+        let unowned = unowned_dlc_cosmetics(class_saves, &db, &[]);
+        let found: Vec<_> =
+            unowned.iter().map(|item| (item.class_slot, item.slot, item.loadout)).collect();
+        assert_eq!(found, [(0, CosmeticSlot::Beard, 1), (2, CosmeticSlot::Beard, 0)]);
+        assert!(unowned_dlc_cosmetics(class_saves, &db, &["test pack".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn default_cosmetic_is_the_classes_own() {
+        let save = synthetic_save();
+        let class_saves = class_saves(&save).unwrap();
+        // With the embedded dataset naming the classes.
+        let db = with_guid_db(fixture_guid_db(), GuidDb::load).unwrap();
+        assert_eq!(default_cosmetic(&class_saves[0], CosmeticSlot::Beard, &db).unwrap(), BEARDS[0]);
+        assert_eq!(default_cosmetic(&class_saves[2], CosmeticSlot::Beard, &db).unwrap(), BEARDS[1]);
+        assert!(default_cosmetic(&class_saves[0], CosmeticSlot::Helmet, &db).is_err());
+        assert!(matches!(
+            default_cosmetic(&class_saves[0], CosmeticSlot::Beard, &GuidDb::default()),
+            Err(DrgSaveError::MissingGuidData { .. })
+        ));
+    }
+}
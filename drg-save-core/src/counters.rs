@@ -0,0 +1,262 @@
+//! Milestone tiers and the lifetime statistics (missions completed, minerals mined, ...), as the
+//! sample save uesave is tested with (`drg-save-test.sav`) keeps them:
+//!
+//! - `Milestones.ClaimedKPIRewards`: one `MilestoneCounter` per milestone, its GUID (`KPIGuid`)
+//!   and the tier claimed (`Tier`, an `Int`).
+//! - `MissionStatsSave.Counters`: one `MissionStatCounter` per statistic and class, the
+//!   statistic's GUID (`MissionStatID`), the class's `SavegameID` (`PlayerClassID`) and the count
+//!   (`Value`, a `Float`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use uesave::{Properties, Property, StructValue, ValueArray};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::{GuidDb, GuidKind};
+use crate::util::{property, property_mut};
+use crate::walk::{property_at, property_at_mut};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterKind {
+    Milestones,
+    Statistics,
+}
+
+impl CounterKind {
+    /// Path of the array holding the counters of this kind.
+    pub fn array_path(self) -> &'static str {
+        match self {
+            Self::Milestones => "Milestones.ClaimedKPIRewards",
+            Self::Statistics => "MissionStatsSave.Counters",
+        }
+    }
+
+    /// Name of the GUID property of a counter.
+    fn guid_field(self) -> &'static str {
+        match self {
+            Self::Milestones => "KPIGuid",
+            Self::Statistics => "MissionStatID",
+        }
+    }
+
+    /// Name of the number property of a counter.
+    fn value_field(self) -> &'static str {
+        match self {
+            Self::Milestones => "Tier",
+            Self::Statistics => "Value",
+        }
+    }
+
+    pub fn guid_kind(self) -> GuidKind {
+        match self {
+            Self::Milestones => GuidKind::Milestone,
+            Self::Statistics => GuidKind::Statistic,
+        }
+    }
+}
+
+impl fmt::Display for CounterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Milestones => write!(f, "milestone"),
+            Self::Statistics => write!(f, "statistic"),
+        }
+    }
+}
+
+/// Name of the class GUID property of a statistic.
+const CLASS_FIELD: &str = "PlayerClassID";
+
+#[derive(Debug, Serialize)]
+pub struct Counter {
+    pub guid: Uuid,
+    pub name: Option<String>,
+    /// The `SavegameID` of the class a statistic counts for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<Uuid>,
+    pub value: f64,
+}
+
+fn guid_field(props: &Properties, name: &str) -> Option<Uuid> {
+    match property(props, name) {
+        Some(Property::Struct { value: StructValue::Guid(guid), .. }) => Some(*guid),
+        _ => None,
+    }
+}
+
+fn counter_structs(props: &Properties, kind: CounterKind) -> Result<&Vec<StructValue>> {
+    let path = kind.array_path();
+    match property_at(props, path) {
+        Some(Property::Array { value: ValueArray::Struct { value, .. }, .. }) => Ok(value),
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, "Struct Array", prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
+
+fn counter_structs_mut(props: &mut Properties, kind: CounterKind) -> Result<&mut Vec<StructValue>> {
+    let path = kind.array_path();
+    match property_at_mut(props, path) {
+        Some(Property::Array { value: ValueArray::Struct { value, .. }, .. }) => Ok(value),
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, "Struct Array", prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
+
+/// The counters of `kind` in the save.
+pub fn counters(props: &Properties, kind: CounterKind, db: &GuidDb) -> Result<Vec<Counter>> {
+    Ok(counter_structs(props, kind)?
+        .iter()
+        .filter_map(|counter| {
+            let StructValue::Struct(counter) = counter else {
+                return None;
+            };
+            let guid = guid_field(counter, kind.guid_field())?;
+            let value = match property(counter, kind.value_field())? {
+                Property::Int { value, .. } => f64::from(*value),
+                Property::Float { value, .. } => f64::from(*value),
+                _ => return None,
+            };
+            Some(Counter {
+                guid,
+                name: db.name(guid).map(str::to_string),
+                class: guid_field(counter, CLASS_FIELD),
+                value,
+            })
+        })
+        .collect())
+}
+
+/// Set the number property of `counter`, keeping the number type the save uses for it. Returns
+/// the previous value.
+fn set_value(counter: &mut Properties, kind: CounterKind, value: f64) -> Result<f64> {
+    let name = kind.value_field();
+    match property_mut(counter, name) {
+        Some(Property::Int { value: old, .. }) => {
+            if value.fract() != 0.0 || value < f64::from(i32::MIN) || value > f64::from(i32::MAX) {
+                return Err(DrgSaveError::InvalidInput(format!(
+                    "{kind} `{name}` is an Int, got {value}"
+                )));
+            }
+            Ok(f64::from(std::mem::replace(old, value as i32)))
+        }
+        Some(Property::Float { value: old, .. }) => {
+            if value.abs() > f64::from(f32::MAX) {
+                return Err(DrgSaveError::InvalidInput(format!(
+                    "{kind} `{name}` is a Float, {value} is out of its range"
+                )));
+            }
+            Ok(f64::from(std::mem::replace(old, value as f32)))
+        }
+        Some(prop) => Err(DrgSaveError::type_mismatch(name, "Int or Float", prop)),
+        None => Err(DrgSaveError::missing(name)),
+    }
+}
+
+/// Set the counter `guid` of `kind` to `value`: a milestone, or a statistic of every class or only
+/// of the class with the `SavegameID` `class` (milestones have no class, so none matches `class`).
+/// Returns the previous values.
+pub fn set_counter(
+    props: &mut Properties,
+    kind: CounterKind,
+    guid: Uuid,
+    class: Option<Uuid>,
+    value: f64,
+) -> Result<Vec<f64>> {
+    if !value.is_finite() {
+        return Err(DrgSaveError::InvalidInput(format!("{kind} must be a finite number")));
+    }
+    let mut old = vec![];
+    for counter in counter_structs_mut(props, kind)? {
+        let StructValue::Struct(counter) = counter else {
+            continue;
+        };
+        if guid_field(counter, kind.guid_field()) != Some(guid)
+            || class.is_some_and(|class| guid_field(counter, CLASS_FIELD) != Some(class))
+        {
+            continue;
+        }
+        old.push(set_value(counter, kind, value)?);
+    }
+    if old.is_empty() {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "no {kind} {guid} in `{}`",
+            kind.array_path()
+        )));
+    }
+    Ok(old)
+}
+
+/// Zero every counter of `kind`, returning how many there were.
+pub fn reset_counters(props: &mut Properties, kind: CounterKind) -> Result<usize> {
+    let mut n = 0;
+    for counter in counter_structs_mut(props, kind)? {
+        if let StructValue::Struct(counter) = counter {
+            set_value(counter, kind, 0.0)?;
+            n += 1;
+        }
+    }
+    Ok(n)
+}
+
+/// Find the GUID of the `kind` counter called `name` (or given as a GUID).
+pub fn resolve_counter(kind: CounterKind, name: &str, db: &GuidDb) -> Result<Uuid> {
+    if let Ok(guid) = Uuid::from_str(name) {
+        return Ok(guid);
+    }
+    db.require(kind.guid_kind())?;
+    db.find(kind.guid_kind(), name)
+        .ok_or_else(|| DrgSaveError::InvalidInput(format!("unknown {kind} `{name}`")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{synthetic_save, GUNNER, MILESTONE, STATISTIC};
+
+    fn values(props: &Properties, kind: CounterKind) -> Vec<f64> {
+        counters(props, kind, &GuidDb::default())
+            .unwrap()
+            .into_iter()
+            .map(|counter| counter.value)
+            .collect()
+    }
+
+    #[test]
+    fn sets_statistics_per_class() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        let statistics = CounterKind::Statistics;
+        assert_eq!(set_counter(props, statistics, STATISTIC, Some(GUNNER), 7.0).unwrap(), [5.0]);
+        assert_eq!(values(props, statistics), [10.0, 7.0]);
+        assert_eq!(set_counter(props, statistics, STATISTIC, None, 1.5).unwrap(), [10.0, 7.0]);
+        assert_eq!(values(props, statistics), [1.5, 1.5]);
+        assert!(set_counter(props, statistics, MILESTONE, None, 1.0).is_err());
+    }
+
+    #[test]
+    fn rejects_values_the_field_cant_hold() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e39] {
+            assert!(set_counter(props, CounterKind::Statistics, STATISTIC, None, value).is_err());
+        }
+        // Milestone tiers are ints.
+        assert!(set_counter(props, CounterKind::Milestones, MILESTONE, None, 2.5).is_err());
+        assert_eq!(
+            set_counter(props, CounterKind::Milestones, MILESTONE, None, 2.0).unwrap(),
+            [4.0]
+        );
+    }
+
+    #[test]
+    fn reset_zeroes_statistics_only() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        assert_eq!(reset_counters(props, CounterKind::Statistics).unwrap(), 2);
+        assert_eq!(values(props, CounterKind::Statistics), [0.0, 0.0]);
+        assert_eq!(values(props, CounterKind::Milestones), [4.0]);
+    }
+}
@@ -0,0 +1,92 @@
+//! Short human-readable descriptions of properties, for commands that print parts of a save.
+
+use serde_json::Value;
+use uesave::{Property, StructValue, ValueArray, ValueSet};
+
+use crate::value::serialized_value;
+
+/// Name of the `Property` variant of `prop`, e.g. `Int`.
+pub fn property_type_name(prop: &Property) -> &'static str {
+    match prop {
+        Property::Int8 { .. } => "Int8",
+        Property::Int16 { .. } => "Int16",
+        Property::Int { .. } => "Int",
+        Property::Int64 { .. } => "Int64",
+        Property::UInt8 { .. } => "UInt8",
+        Property::UInt16 { .. } => "UInt16",
+        Property::UInt32 { .. } => "UInt32",
+        Property::UInt64 { .. } => "UInt64",
+        Property::Float { .. } => "Float",
+        Property::Double { .. } => "Double",
+        Property::Bool { .. } => "Bool",
+        Property::Byte { .. } => "Byte",
+        Property::Enum { .. } => "Enum",
+        Property::Str { .. } => "Str",
+        Property::FieldPath { .. } => "FieldPath",
+        Property::SoftObject { .. } => "SoftObject",
+        Property::Name { .. } => "Name",
+        Property::Object { .. } => "Object",
+        Property::Text { .. } => "Text",
+        Property::Delegate { .. } => "Delegate",
+        Property::MulticastDelegate { .. } => "MulticastDelegate",
+        Property::MulticastInlineDelegate { .. } => "MulticastInlineDelegate",
+        Property::MulticastSparseDelegate { .. } => "MulticastSparseDelegate",
+        Property::Set { .. } => "Set",
+        Property::Map { .. } => "Map",
+        Property::Struct { .. } => "Struct",
+        Property::Array { .. } => "Array",
+    }
+}
+
+/// The value of `prop` if it is a scalar, or the size of it if it is a container.
+pub fn summarize_property(prop: &Property) -> String {
+    match prop {
+        Property::Struct { value: StructValue::Struct(props), .. } => {
+            format!("{{{} properties}}", props.0.len())
+        }
+        Property::Struct { value: StructValue::Guid(guid), .. } => guid.to_string(),
+        Property::Array { value: ValueArray::Struct { value, .. }, .. }
+        | Property::Set { value: ValueSet::Struct(value), .. } => {
+            format!("[{} structs]", value.len())
+        }
+        Property::Map { value, .. } => format!("{{{} entries}}", value.len()),
+        _ => match serialized_value(prop) {
+            Some(value) => match array_len(&value) {
+                Some(len) => format!("[{len} elements]"),
+                None => truncate(value.to_string()),
+            },
+            None => truncate(serde_json::to_value(prop).unwrap_or_default().to_string()),
+        },
+    }
+}
+
+/// Number of elements of `prop` if it is an array, set or map.
+pub fn property_len(prop: &Property) -> Option<usize> {
+    match prop {
+        Property::Array { value: ValueArray::Struct { value, .. }, .. }
+        | Property::Set { value: ValueSet::Struct(value), .. } => Some(value.len()),
+        Property::Map { value, .. } => Some(value.len()),
+        Property::Array { .. } | Property::Set { .. } => array_len(&serialized_value(prop)?),
+        _ => None,
+    }
+}
+
+/// Length of `value` if it is an array, possibly wrapped in enum variants (single-key objects) like
+/// `{"Int": [1, 2, 3]}`.
+fn array_len(value: &Value) -> Option<usize> {
+    match value {
+        Value::Array(values) => Some(values.len()),
+        Value::Object(variant) if variant.len() == 1 => array_len(variant.values().next()?),
+        _ => None,
+    }
+}
+
+fn truncate(mut s: String) -> String {
+    const MAX_LEN: usize = 80;
+    if s.len() > MAX_LEN {
+        let end = (0..=MAX_LEN).rev().find(|i| s.is_char_boundary(*i)).unwrap_or(0);
+        s.truncate(end);
+        s.push('…');
+    }
+    s
+}
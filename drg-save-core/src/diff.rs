@@ -0,0 +1,109 @@
+use uesave::{Properties, Property, Save, StructValue, ValueArray};
+
+use crate::describe::summarize_property;
+use crate::walk::{join_path, property_at};
+
+/// Find the path of the first property that differs between `a` and `b`, if any.
+pub fn find_difference(a: &Save, b: &Save) -> Option<String> {
+    if a.header != b.header {
+        return Some("<header>".to_string());
+    }
+    if a.root.save_game_type != b.root.save_game_type {
+        return Some("<save_game_type>".to_string());
+    }
+    if let Some(path) =
+        find_property_differences(&a.root.properties, &b.root.properties).into_iter().next()
+    {
+        return Some(path);
+    }
+    if a.extra != b.extra {
+        return Some("<extra>".to_string());
+    }
+    None
+}
+
+/// Find the paths of all properties that differ between `a` and `b`, including properties only
+/// present on one side. Structs and arrays of structs are descended into, so only the innermost
+/// differing properties are reported.
+pub fn find_property_differences(a: &Properties, b: &Properties) -> Vec<String> {
+    let mut paths = vec![];
+    find_properties_differences("", a, b, &mut paths);
+    paths
+}
+
+/// [`find_property_differences`] as one line per property: `~ path: old -> new` for changed
+/// properties, `- path: old` for removed ones and `+ path: new` for added ones.
+pub fn describe_property_differences(a: &Properties, b: &Properties) -> Vec<String> {
+    find_property_differences(a, b)
+        .into_iter()
+        .filter_map(|path| {
+            let describe = |props| property_at(props, &path).map(summarize_property);
+            match (describe(a), describe(b)) {
+                (Some(old), Some(new)) => Some(format!("~ {path}: {old} -> {new}")),
+                (Some(old), None) => Some(format!("- {path}: {old}")),
+                (None, Some(new)) => Some(format!("+ {path}: {new}")),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+fn find_properties_differences(
+    prefix: &str,
+    a: &Properties,
+    b: &Properties,
+    paths: &mut Vec<String>,
+) {
+    for (key, a_prop) in a {
+        let path = join_path(prefix, key);
+        match b.0.get(key) {
+            Some(b_prop) => find_property_differences_inner(&path, a_prop, b_prop, paths),
+            None => paths.push(path),
+        }
+    }
+    paths
+        .extend(b.0.keys().filter(|key| !a.0.contains_key(*key)).map(|key| join_path(prefix, key)));
+}
+
+fn find_property_differences_inner(
+    path: &str,
+    a: &Property,
+    b: &Property,
+    paths: &mut Vec<String>,
+) {
+    let n_paths = paths.len();
+    match (a, b) {
+        (
+            Property::Struct { value: StructValue::Struct(a_props), .. },
+            Property::Struct { value: StructValue::Struct(b_props), .. },
+        ) => find_properties_differences(path, a_props, b_props, paths),
+        (
+            Property::Array { value: ValueArray::Struct { value: a_values, .. }, .. },
+            Property::Array { value: ValueArray::Struct { value: b_values, .. }, .. },
+        ) if a_values.len() == b_values.len() => {
+            for (i, (a_value, b_value)) in a_values.iter().zip(b_values).enumerate() {
+                match (a_value, b_value) {
+                    (StructValue::Struct(a_props), StructValue::Struct(b_props)) => {
+                        find_properties_differences(
+                            &format!("{path}[{i}]"),
+                            a_props,
+                            b_props,
+                            paths,
+                        )
+                    }
+                    // Non-struct elements can't be addressed on their own, report the array.
+                    _ if a_value != b_value => {
+                        paths.truncate(n_paths);
+                        paths.push(path.to_string());
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    if paths.len() == n_paths && a != b {
+        paths.push(path.to_string());
+    }
+}
@@ -0,0 +1,93 @@
+//! Edit modules that the unified CLI discovers and dispatches to (`drg-save edit <name>`).
+//!
+//! To add an edit, implement [`Editor`] in a module below this one and register it in
+//! [`editors`]. The CLI takes care of reading, backing up, journaling and writing the save.
+
+pub mod blue_number;
+pub mod cosmetics;
+pub mod credits;
+pub mod events;
+pub mod forge;
+pub mod identity;
+pub mod milestones;
+pub mod missions;
+pub mod pools;
+pub mod resources;
+pub mod rewards;
+pub mod unlock;
+pub mod weapon_mods;
+pub mod xp;
+
+#[cfg(test)]
+mod golden_tests;
+#[cfg(test)]
+mod robustness_tests;
+
+use std::any::Any;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use uesave::Save;
+
+use crate::error::DrgSaveError;
+
+/// What an editor did to a save, as human-readable lines.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub lines: Vec<String>,
+}
+
+impl Report {
+    pub fn push(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+}
+
+pub trait Editor: Send + Sync {
+    /// Name of the editor on the command line, e.g. `blue-number`.
+    fn name(&self) -> &'static str;
+
+    /// One-line description for `--help`.
+    fn about(&self) -> &'static str;
+
+    /// The editor's own arguments. The path of the save is added by the CLI.
+    fn args(&self) -> Vec<clap::Arg> {
+        vec![]
+    }
+
+    /// Edit `save` in place according to `args`, parsed from [`Editor::args`].
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report>;
+}
+
+/// The value of the required argument `id`. Clap enforces required arguments when `args` is parsed
+/// from [`Editor::args`], but `args` may be built elsewhere, so a missing one is still an error.
+pub(crate) fn required_arg<'a, T: Any + Clone + Send + Sync>(
+    args: &'a ArgMatches,
+    id: &str,
+) -> crate::error::Result<&'a T> {
+    match args.try_get_one::<T>(id) {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(DrgSaveError::InvalidInput(format!("`{id}` is required"))),
+        Err(e) => Err(DrgSaveError::InvalidInput(format!("invalid `{id}`: {e}"))),
+    }
+}
+
+/// All registered editors.
+pub fn editors() -> Vec<Box<dyn Editor>> {
+    vec![
+        Box::new(blue_number::BlueNumberResetter),
+        Box::new(cosmetics::CosmeticsEditor),
+        Box::new(credits::CreditsEditor),
+        Box::new(events::EventsEditor),
+        Box::new(forge::ForgeEditor),
+        Box::new(identity::IdentityEditor),
+        Box::new(milestones::MilestonesEditor),
+        Box::new(missions::MissionsEditor),
+        Box::new(pools::PoolsEditor),
+        Box::new(resources::ResourcesEditor),
+        Box::new(rewards::RewardsEditor),
+        Box::new(unlock::UnlockEditor),
+        Box::new(weapon_mods::WeaponModsEditor),
+        Box::new(xp::XpEditor),
+    ]
+}
@@ -0,0 +1,235 @@
+//! Resets the blue level (player rank) to a target, `-69` by default, while still being able to
+//! play EDDs. This is achieved in part by setting each active class to 1 promo + 25 residual red
+//! levels, and making up the difference with the hidden class. Individual classes can be kept as
+//! they are or given a different number of promotions.
+
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use tracing::*;
+use uesave::{Save, StructValue};
+
+use crate::blue_level::{blue_level, red_levels, red_levels_needed_for};
+use crate::classes::{
+    class_saves_mut, resolve_class, savegame_id, ClassSelector, HIDDEN_CLASS_SAVEGAME_ID,
+};
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_db::GuidDb;
+use crate::util::int_mut;
+
+pub const DEFAULT_TARGET_BLUE_LEVEL: i32 = -69;
+
+/// Promotions given to active classes, so they can still play EDDs.
+pub const MIN_PROMOS: i32 = 1;
+
+/// Red levels left on active classes on top of their promotions.
+const RESIDUAL_RED_LEVELS: i32 = 25;
+
+const XP_REQUIRED_FOR_25_RED_LEVELS: i32 = 315_000;
+
+#[derive(Debug, Clone)]
+pub struct BlueNumberOptions {
+    pub target_blue_level: i32,
+    /// Active classes whose promotions and levels are left as they are.
+    pub keep_classes: Vec<ClassSelector>,
+    /// Active classes given a number of promotions other than [`MIN_PROMOS`].
+    pub promos: Vec<ClassPromos>,
+}
+
+impl Default for BlueNumberOptions {
+    fn default() -> Self {
+        Self { target_blue_level: DEFAULT_TARGET_BLUE_LEVEL, keep_classes: vec![], promos: vec![] }
+    }
+}
+
+/// `<class>=<n>`: give a class `n` promotions.
+#[derive(Debug, Clone)]
+pub struct ClassPromos {
+    pub class: ClassSelector,
+    pub promos: i32,
+}
+
+impl FromStr for ClassPromos {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((class, promos)) = s.split_once('=') else {
+            return Err(format!("expected `<class>=<promos>`, got `{s}`"));
+        };
+        let promos = promos.parse().map_err(|e| format!("invalid promotions `{promos}`: {e}"))?;
+        let class = ClassSelector::from_str(class).unwrap_or_else(|e| match e {});
+        Ok(Self { class, promos })
+    }
+}
+
+pub struct BlueNumberResetter;
+
+impl Editor for BlueNumberResetter {
+    fn name(&self) -> &'static str {
+        "blue-number"
+    }
+
+    fn about(&self) -> &'static str {
+        "Reset the blue level to a target while keeping every class able to play EDDs"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("target")
+                .long("target")
+                .help("Blue level to end up at [default: -69]")
+                .allow_negative_numbers(true)
+                .value_parser(value_parser!(i32)),
+            Arg::new("keep-class")
+                .long("keep-class")
+                .help("Class name or slot to leave as it is. Can be given multiple times")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(ClassSelector)),
+            Arg::new("promos")
+                .long("promos")
+                .help("`<class>=<n>`: give a class n promotions instead of 1")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(ClassPromos)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let options = BlueNumberOptions {
+            target_blue_level: args
+                .get_one::<i32>("target")
+                .copied()
+                .unwrap_or(DEFAULT_TARGET_BLUE_LEVEL),
+            keep_classes: args
+                .get_many::<ClassSelector>("keep-class")
+                .unwrap_or_default()
+                .cloned()
+                .collect(),
+            promos: args.get_many::<ClassPromos>("promos").unwrap_or_default().cloned().collect(),
+        };
+        reset_blue_number(save, &options)
+    }
+}
+
+/// Set the active classes to [`MIN_PROMOS`] promotions (or the number given for them in
+/// `options`) + 25 red levels, except for the kept ones, and the hidden class to whatever is left
+/// to end up at the target blue level.
+pub fn reset_blue_number(save: &mut Save, options: &BlueNumberOptions) -> Result<Report> {
+    let mut report = Report::default();
+    let target_blue_level = options.target_blue_level;
+
+    // The properties this relies on are listed in the expected schema (see
+    // `crate::expected_schema`), which callers check before editing.
+    let class_saves = class_saves_mut(save)?;
+
+    // There are 5 class save slots. 4 of them are active classes, but 1 of them is unused (but
+    // still contributes to blue number). This means we can manipulate overall blue number by
+    // manipulating hidden class's `RetiredCharacterLevels`.
+
+    // Resolve the per-class options to slots before the class saves are borrowed mutably.
+    let db = GuidDb::load()?;
+    let mut kept_slots = vec![];
+    for class in &options.keep_classes {
+        kept_slots.push(resolve_class(class_saves, class, &db)?);
+    }
+    let mut promos_by_slot = vec![];
+    for ClassPromos { class, promos } in &options.promos {
+        let slot = resolve_class(class_saves, class, &db)?;
+        ensure!(
+            !kept_slots.contains(&slot),
+            DrgSaveError::InvalidInput(format!("{class} is both kept and given promotions"))
+        );
+        ensure!(
+            *promos >= 0,
+            DrgSaveError::InvalidInput(format!("promotions of {class} must not be negative"))
+        );
+        promos_by_slot.push((slot, *promos));
+    }
+
+    // A class save without a `SavegameID` counts as active, and fails when it is set below.
+    let (mut inactive_class_saves, mut active_class_saves): (Vec<_>, Vec<_>) = class_saves
+        .iter_mut()
+        .enumerate()
+        .partition(|(_, class_save)| savegame_id(class_save) == Some(HIDDEN_CLASS_SAVEGAME_ID));
+    ensure!(
+        inactive_class_saves.len() == 1,
+        DrgSaveError::UnexpectedShape("expected exactly 1 inactive class".to_string())
+    );
+    if let Some(slot) = kept_slots
+        .iter()
+        .chain(promos_by_slot.iter().map(|(slot, _)| slot))
+        .find(|slot| **slot == inactive_class_saves[0].0)
+    {
+        bail!(DrgSaveError::InvalidInput(format!(
+            "class save slot {slot} is the hidden class, which is always set"
+        )));
+    }
+
+    for (slot, class_save) in active_class_saves.iter_mut() {
+        if kept_slots.contains(slot) {
+            report.push(format!("kept class save slot {slot} as it is"));
+            continue;
+        }
+        let promos = promos_by_slot
+            .iter()
+            .find(|(promos_slot, _)| promos_slot == slot)
+            .map_or(MIN_PROMOS, |(_, promos)| *promos);
+        set_class_save(
+            format!("active_class {slot}"),
+            class_save,
+            promos,
+            RESIDUAL_RED_LEVELS,
+            XP_REQUIRED_FOR_25_RED_LEVELS,
+        )?;
+        report.push(format!(
+            "set class save slot {slot} to {promos} promotions and {RESIDUAL_RED_LEVELS} red levels"
+        ));
+    }
+
+    let mut active_red_level: i32 = 0;
+    for (_, class_save) in &active_class_saves {
+        active_red_level = active_red_level
+            .checked_add(red_levels(class_save)?)
+            .ok_or_else(|| DrgSaveError::InvalidInput("too many red levels".to_string()))?;
+    }
+    let target_red_level = red_levels_needed_for(target_blue_level)?;
+    let diff_red_level = target_red_level.checked_sub(active_red_level).ok_or_else(|| {
+        DrgSaveError::InvalidInput(format!(
+            "the hidden class can't make up blue level {target_blue_level} with the active \
+             classes at {active_red_level} red levels"
+        ))
+    })?;
+
+    // Use the inactive class to modify blue level, which does not show up for active classes.
+    set_class_save("inactive_class", inactive_class_saves.remove(0).1, 0, diff_red_level, 0)?;
+    report.push(format!("set hidden class to {diff_red_level} red levels"));
+    ensure!(
+        blue_level(class_saves)? == target_blue_level,
+        DrgSaveError::UnexpectedShape("unexpected resulting blue level".to_string())
+    );
+    report.push(format!("blue level is now {target_blue_level}"));
+
+    Ok(report)
+}
+
+#[instrument(level = "debug", skip(class_save), fields(save_name = save_name.as_ref()))]
+fn set_class_save<S: AsRef<str>>(
+    save_name: S,
+    class_save: &mut StructValue,
+    promos: i32,
+    red_levels: i32,
+    xp: i32,
+) -> Result<()> {
+    let StructValue::Struct(ref mut props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind().into());
+    };
+
+    // Unneeded, zeroed so do not affect blue level calculation.
+    *int_mut(props, "TimesRetired")? = promos;
+    // Use this to influence the desired blue level. Blue level and red level can be negative!
+    *int_mut(props, "RetiredCharacterLevels")? = red_levels;
+    *int_mut(props, "XP")? = xp;
+
+    Ok(())
+}
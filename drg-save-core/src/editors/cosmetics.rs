@@ -0,0 +1,157 @@
+//! Changes the victory move or a cosmetic slot a class has equipped, or replaces the items from
+//! DLC the account doesn't own.
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::classes::{class_saves_mut, resolve_class, ClassSelector};
+use crate::cosmetics::{
+    default_cosmetic, equip, resolve_cosmetic, selected_loadout, unowned_dlc_cosmetics,
+    CosmeticSlot,
+};
+use crate::editors::{required_arg, Editor, Report};
+use crate::guid_db::GuidDb;
+
+/// `<slot>=<item>`: equip `item` in `slot` in place of items from unowned DLC.
+#[derive(Debug, Clone)]
+pub struct Substitute {
+    pub slot: CosmeticSlot,
+    pub item: String,
+}
+
+impl FromStr for Substitute {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((slot, item)) = s.split_once('=') else {
+            return Err(format!("expected `<slot>=<item>`, got `{s}`"));
+        };
+        Ok(Self { slot: slot.parse()?, item: item.to_string() })
+    }
+}
+
+pub struct CosmeticsEditor;
+
+impl Editor for CosmeticsEditor {
+    fn name(&self) -> &'static str {
+        "cosmetics"
+    }
+
+    fn about(&self) -> &'static str {
+        "Equip a victory move, helmet, beard, armor, paintjob or other cosmetic, or replace unowned \
+         DLC items"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("class")
+                .long("class")
+                .help("Class name or index of the class save slot in `CharacterSaves`")
+                .required_unless_present("strip-dlc")
+                .value_parser(value_parser!(ClassSelector)),
+            Arg::new("slot")
+                .long("slot")
+                .help(
+                    "`victory-move`, `helmet`, `beard`, `moustache`, `sideburns`, `eyebrows`, \
+                     `beard-color`, `skin-color`, `armor` or `paintjob`",
+                )
+                .required_unless_present("strip-dlc")
+                .value_parser(value_parser!(CosmeticSlot)),
+            Arg::new("item")
+                .long("item")
+                .help("Name in the GUID database or GUID of the item to equip")
+                .required_unless_present("strip-dlc"),
+            Arg::new("loadout")
+                .long("loadout")
+                .help("Index of the vanity loadout to change. Defaults to the selected one")
+                .value_parser(value_parser!(usize)),
+            Arg::new("strip-dlc")
+                .long("strip-dlc")
+                .help("Replace every equipped item from a DLC not given with `--owned-dlc`")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["class", "slot", "item", "loadout"]),
+            Arg::new("owned-dlc")
+                .long("owned-dlc")
+                .help("Name of a DLC the account owns. Can be given multiple times")
+                .action(ArgAction::Append)
+                .requires("strip-dlc"),
+            Arg::new("substitute")
+                .long("substitute")
+                .help(
+                    "`<slot>=<item>`: equip item instead of unowned DLC items, not the class's \
+                     default",
+                )
+                .action(ArgAction::Append)
+                .requires("strip-dlc")
+                .value_parser(value_parser!(Substitute)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        if args.get_flag("strip-dlc") {
+            let owned_dlc: Vec<String> =
+                args.get_many::<String>("owned-dlc").unwrap_or_default().cloned().collect();
+            let substitutes: Vec<Substitute> =
+                args.get_many::<Substitute>("substitute").unwrap_or_default().cloned().collect();
+            return strip_dlc(save, &owned_dlc, &substitutes);
+        }
+
+        let class = required_arg::<ClassSelector>(args, "class")?;
+        let slot = *required_arg::<CosmeticSlot>(args, "slot")?;
+        let item = required_arg::<String>(args, "item")?;
+
+        let mut report = Report::default();
+        let db = GuidDb::load()?;
+        let guid = resolve_cosmetic(slot, item, &db)?;
+        let class_saves = class_saves_mut(save)?;
+        let index = resolve_class(class_saves, class, &db)?;
+        let loadout = match args.get_one::<usize>("loadout") {
+            Some(loadout) => *loadout,
+            None => selected_loadout(&class_saves[index])?,
+        };
+        let old = equip(&mut class_saves[index], slot, loadout, guid)?;
+        report.push(format!(
+            "equipped {} as {slot} of {class} in loadout {loadout}, replacing {}",
+            db.label(guid),
+            db.label(old)
+        ));
+        Ok(report)
+    }
+}
+
+/// Replace every equipped item from a DLC not in `owned_dlc` with the substitute for its slot, or
+/// the class's default item if there is none.
+pub fn strip_dlc(
+    save: &mut Save,
+    owned_dlc: &[String],
+    substitutes: &[Substitute],
+) -> Result<Report> {
+    let mut report = Report::default();
+    let db = GuidDb::load()?;
+    let class_saves = class_saves_mut(save)?;
+    for unowned in unowned_dlc_cosmetics(class_saves, &db, owned_dlc) {
+        let class_save = &mut class_saves[unowned.class_slot];
+        let replacement =
+            match substitutes.iter().find(|substitute| substitute.slot == unowned.slot) {
+                Some(substitute) => resolve_cosmetic(unowned.slot, &substitute.item, &db)?,
+                None => default_cosmetic(class_save, unowned.slot, &db)?,
+            };
+        equip(class_save, unowned.slot, unowned.loadout, replacement)?;
+        report.push(format!(
+            "replaced {} ({}) as {} of class save slot {} in loadout {} with {}",
+            db.label(unowned.guid),
+            unowned.dlc,
+            unowned.slot,
+            unowned.class_slot,
+            unowned.loadout,
+            db.label(replacement)
+        ));
+    }
+    if report.lines.is_empty() {
+        report.push("no equipped items from unowned DLC".to_string());
+    }
+    Ok(report)
+}
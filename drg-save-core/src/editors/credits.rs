@@ -0,0 +1,100 @@
+//! Sets or adds to the credits balance, clamped to what the save can hold.
+
+use anyhow::{bail, Result};
+use clap::{value_parser, Arg, ArgMatches};
+use tracing::*;
+use uesave::{Property, Save};
+
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::walk::walk_properties_mut;
+
+/// Credits are stored in an `Int` property, so this is the most a save can hold.
+pub const MAX_CREDITS: i64 = i32::MAX as i64;
+
+/// Balances above this are far beyond what regular play earns and stand out in matchmade lobbies.
+pub const PLAUSIBLE_CREDITS: i64 = 10_000_000;
+
+pub struct CreditsEditor;
+
+impl Editor for CreditsEditor {
+    fn name(&self) -> &'static str {
+        "credits"
+    }
+
+    fn about(&self) -> &'static str {
+        "Set or add to the credits balance"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("set")
+                .long("set")
+                .help("Credits balance to set")
+                .conflicts_with("add")
+                .required_unless_present("add")
+                .value_parser(value_parser!(i64)),
+            Arg::new("add")
+                .long("add")
+                .help("Credits to add to the balance, negative to take them away")
+                .allow_negative_numbers(true)
+                .value_parser(value_parser!(i64)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let amount = match (args.get_one::<i64>("set"), args.get_one::<i64>("add")) {
+            (Some(set), _) => CreditsChange::Set(*set),
+            (None, Some(add)) => CreditsChange::Add(*add),
+            (None, None) => {
+                bail!(DrgSaveError::Usage("either `--set` or `--add` is required".to_string()))
+            }
+        };
+        change_credits(save, amount)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CreditsChange {
+    Set(i64),
+    Add(i64),
+}
+
+/// Apply `change` to the credits balance, clamping the result to `0..=MAX_CREDITS`.
+pub fn change_credits(save: &mut Save, change: CreditsChange) -> Result<Report> {
+    let mut report = Report::default();
+
+    let mut changed = None;
+    walk_properties_mut(&mut save.root.properties, &mut |path, prop| {
+        let Property::Int { value, .. } = prop else {
+            return;
+        };
+        if changed.is_some() || !(path == "Credits" || path.ends_with(".Credits")) {
+            return;
+        }
+        let old = *value;
+        let wanted = match change {
+            CreditsChange::Set(credits) => credits,
+            CreditsChange::Add(credits) => i64::from(old) + credits,
+        };
+        let new = wanted.clamp(0, MAX_CREDITS);
+        *value = new as i32;
+        changed = Some((path.to_string(), old, wanted, new));
+    });
+    let Some((path, old, wanted, new)) = changed else {
+        return Err(DrgSaveError::missing("Credits").into());
+    };
+
+    if wanted != new {
+        let line = format!("{wanted} credits does not fit in `{path}`, clamped to {new}");
+        warn!("{line}");
+        report.push(line);
+    }
+    if new > PLAUSIBLE_CREDITS {
+        let line = format!("{new} credits is implausibly many and may stand out to other players");
+        warn!("{line}");
+        report.push(line);
+    }
+    report.push(format!("set `{path}` from {old} to {new} credits"));
+    Ok(report)
+}
@@ -0,0 +1,78 @@
+//! Resets or completes the weekly core hunt and machine events, e.g. to test them again (see
+//! `drg-save events`).
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::events::{edit_progress, ProgressAction};
+use crate::guid_db::GuidDb;
+
+pub struct EventsEditor;
+
+impl Editor for EventsEditor {
+    fn name(&self) -> &'static str {
+        "events"
+    }
+
+    fn about(&self) -> &'static str {
+        "Reset or complete the weekly core hunt and machine events (see `drg-save events`)"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("reset")
+                .long("reset")
+                .help(
+                    "Mark the core hunt as not completed, zero machine event counters and clear \
+                     their flags",
+                )
+                .action(ArgAction::SetTrue)
+                .conflicts_with("complete"),
+            Arg::new("complete")
+                .long("complete")
+                .help(
+                    "Mark the core hunt as completed, set machine event flags and their counters \
+                     to `--count`",
+                )
+                .action(ArgAction::SetTrue),
+            Arg::new("count")
+                .long("count")
+                .help("What `--complete` sets machine event counters to")
+                .value_parser(clap::value_parser!(f32))
+                .requires("complete"),
+            Arg::new("event").long("event").help(
+                "Only edit events whose name contains this, e.g. `core hunt` or `omen` [default: \
+                 all]",
+            ),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let action = if args.get_flag("reset") {
+            ProgressAction::Reset
+        } else if args.get_flag("complete") {
+            ProgressAction::Complete { count: args.get_one::<f32>("count").copied() }
+        } else {
+            bail!(DrgSaveError::Usage("either `--reset` or `--complete` is required".to_string()));
+        };
+        let event = args.get_one::<String>("event").map(String::as_str);
+
+        let verb = match action {
+            ProgressAction::Reset => "reset",
+            ProgressAction::Complete { .. } => "completed",
+        };
+
+        let mut report = Report::default();
+        let db = GuidDb::load()?;
+        for path in edit_progress(&mut save.root.properties, &db, event, action)? {
+            report.push(format!("{verb} `{path}`"));
+        }
+        if report.lines.is_empty() {
+            report.push("every matching event was already in that state");
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,151 @@
+//! Forges schematics: moves them from `OwnedSchematics` (unforged) to `ForgedSchematics`, as
+//! forging them with matrix cores in-game would.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches};
+use uesave::{Save, StructValue};
+
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_array::{guid_array_mut, guids};
+use crate::guid_db::{GuidDb, GuidKind};
+
+const UNFORGED: &str = "SchematicSave.OwnedSchematics";
+const FORGED: &str = "SchematicSave.ForgedSchematics";
+
+pub struct ForgeEditor;
+
+impl Editor for ForgeEditor {
+    fn name(&self) -> &'static str {
+        "forge"
+    }
+
+    fn about(&self) -> &'static str {
+        "Forge unforged schematics (overclocks, cosmetics) without spending matrix cores"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("all")
+                .long("all")
+                .help("Forge every unforged schematic")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("filter"),
+            Arg::new("filter")
+                .long("filter")
+                .help(
+                    "Only forge schematics whose name in the GUID database contains this, e.g. a \
+                     weapon name. Can be repeated",
+                )
+                .action(ArgAction::Append),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let filters: Vec<String> =
+            args.get_many::<String>("filter").unwrap_or_default().cloned().collect();
+        if !args.get_flag("all") && filters.is_empty() {
+            bail!(DrgSaveError::Usage("either `--all` or `--filter` is required".to_string()));
+        }
+        forge(save, &filters)
+    }
+}
+
+/// Forge the unforged schematics whose name contains one of `filters` (ignoring case), or all of
+/// them if `filters` is empty.
+pub fn forge(save: &mut Save, filters: &[String]) -> Result<Report> {
+    let mut report = Report::default();
+    let db = GuidDb::load()?;
+    if !filters.is_empty() {
+        db.require(GuidKind::Schematic)?;
+    }
+    let props = &mut save.root.properties;
+
+    let filters: Vec<String> = filters.iter().map(|filter| filter.to_lowercase()).collect();
+    // The unforged array can hold a schematic several times, it's forged once.
+    let mut seen = HashSet::new();
+    let selected: Vec<_> = guids(props, UNFORGED)?
+        .into_iter()
+        .filter(|guid| seen.insert(*guid))
+        .filter(|guid| {
+            // Schematics missing from the GUID database can't match a name.
+            filters.is_empty()
+                || db
+                    .name(*guid)
+                    .map(str::to_lowercase)
+                    .is_some_and(|name| filters.iter().any(|filter| name.contains(filter.as_str())))
+        })
+        .collect();
+    if selected.is_empty() {
+        report.push("no unforged schematics matched");
+        return Ok(report);
+    }
+
+    // Look each array up once rather than once per schematic.
+    let forged = guid_array_mut(props, FORGED)?;
+    let already_forged: HashSet<_> = forged
+        .iter()
+        .filter_map(|value| match value {
+            StructValue::Guid(guid) => Some(*guid),
+            _ => None,
+        })
+        .collect();
+    forged.extend(
+        selected
+            .iter()
+            .filter(|guid| !already_forged.contains(guid))
+            .map(|guid| StructValue::Guid(*guid)),
+    );
+    let selected_set: HashSet<_> = selected.iter().copied().collect();
+    guid_array_mut(props, UNFORGED)?
+        .retain(|value| !matches!(value, StructValue::Guid(guid) if selected_set.contains(guid)));
+    for guid in selected {
+        report.push(format!("forged {}", db.label(guid)));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{sample_save, synthetic_save, FORGED_SCHEMATIC, UNFORGED_SCHEMATICS};
+
+    #[test]
+    fn filter_needs_schematic_names() {
+        let mut save = synthetic_save();
+        let err = forge(&mut save, &["Lead Storm".to_string()]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DrgSaveError>(),
+            Some(DrgSaveError::MissingGuidData { .. })
+        ));
+    }
+
+    #[test]
+    fn forges_repeated_schematics_once() {
+        let mut save = synthetic_save();
+        let unforged = guid_array_mut(&mut save.root.properties, UNFORGED).unwrap();
+        unforged.push(StructValue::Guid(UNFORGED_SCHEMATICS[0]));
+        unforged.push(StructValue::Guid(UNFORGED_SCHEMATICS[0]));
+
+        let report = forge(&mut save, &[]).unwrap();
+        assert_eq!(report.lines.len(), 2);
+        let props = &save.root.properties;
+        assert!(guids(props, UNFORGED).unwrap().is_empty());
+        assert_eq!(
+            guids(props, FORGED).unwrap(),
+            [FORGED_SCHEMATIC, UNFORGED_SCHEMATICS[0], UNFORGED_SCHEMATICS[1]]
+        );
+    }
+
+    #[test]
+    fn forges_the_sample_save_without_repeats() {
+        let mut save = sample_save();
+        forge(&mut save, &[]).unwrap();
+        let forged = guids(&save.root.properties, FORGED).unwrap();
+        // 486 forged before, plus the 6 of the 7 unforged schematics that weren't forged already.
+        assert_eq!(forged.len(), 492);
+        assert_eq!(forged.iter().collect::<HashSet<_>>().len(), forged.len());
+    }
+}
@@ -0,0 +1,200 @@
+//! Golden tests: each editor is run against [`synthetic_save`] and the resulting property
+//! differences are compared with `testdata/golden/<case>.txt`. Run with `UPDATE_GOLDEN=1` to
+//! rewrite the golden files after an intended change, and review their diff.
+
+use std::path::PathBuf;
+
+use clap::Command;
+
+use super::editors;
+use crate::describe::summarize_property;
+use crate::diff::find_property_differences;
+use crate::fixtures::{fixture_guid_db, synthetic_save, with_guid_db};
+use crate::walk::property_at;
+
+/// Apply the editor called `name` with `args` to the synthetic save, with [`fixture_guid_db`]
+/// loaded, and describe what changed, one `path: old -> new` line per property.
+fn edit(name: &'static str, args: &[&str]) -> String {
+    let editor = editors().into_iter().find(|editor| editor.name() == name).unwrap();
+    let matches = Command::new(name)
+        .args(editor.args())
+        .try_get_matches_from(std::iter::once(name).chain(args.iter().copied()))
+        .unwrap();
+
+    let original = synthetic_save();
+    let mut save = synthetic_save();
+    with_guid_db(fixture_guid_db(), || editor.apply(&mut save, &matches)).unwrap();
+
+    let (old, new) = (&original.root.properties, &save.root.properties);
+    let mut lines = String::new();
+    for path in find_property_differences(old, new) {
+        let describe =
+            |props| property_at(props, &path).map_or("<none>".to_string(), summarize_property);
+        lines.push_str(&format!("{path}: {} -> {}\n", describe(old), describe(new)));
+    }
+    lines
+}
+
+fn check_golden(case: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("testdata")
+        .join("golden")
+        .join(format!("{case}.txt"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read `{}`: {e}", path.display()));
+    assert_eq!(actual, expected, "golden file `{}` differs", path.display());
+}
+
+#[test]
+fn blue_number() {
+    check_golden("blue_number", &edit("blue-number", &[]));
+}
+
+#[test]
+fn blue_number_keep_class() {
+    check_golden(
+        "blue_number_keep_class",
+        &edit("blue-number", &["--keep-class", "Gunner", "--promos", "Scout=3"]),
+    );
+}
+
+#[test]
+fn blue_number_is_idempotent() {
+    let editor = editors().into_iter().find(|editor| editor.name() == "blue-number").unwrap();
+    let matches = Command::new("blue-number").args(editor.args()).get_matches_from(["blue-number"]);
+    let mut once = synthetic_save();
+    editor.apply(&mut once, &matches).unwrap();
+    let mut twice = synthetic_save();
+    editor.apply(&mut twice, &matches).unwrap();
+    editor.apply(&mut twice, &matches).unwrap();
+    assert!(find_property_differences(&once.root.properties, &twice.root.properties).is_empty());
+}
+
+#[test]
+fn credits() {
+    check_golden("credits", &edit("credits", &["--add", "1000"]));
+}
+
+#[test]
+fn credits_clamped() {
+    check_golden("credits_clamped", &edit("credits", &["--add", "-200000"]));
+}
+
+#[test]
+fn xp() {
+    check_golden("xp", &edit("xp", &["--class", "Driller", "--level", "5"]));
+}
+
+#[test]
+fn forge_all() {
+    check_golden("forge_all", &edit("forge", &["--all"]));
+}
+
+#[test]
+fn forge_filter() {
+    check_golden("forge_filter", &edit("forge", &["--filter", "overclock 2"]));
+}
+
+#[test]
+fn resources_insert() {
+    check_golden(
+        "resources_insert",
+        &edit("resources", &["--set", "00000000-0000-0000-0000-00000000000c=5"]),
+    );
+}
+
+#[test]
+fn resources_by_name() {
+    check_golden("resources_by_name", &edit("resources", &["--set", "error cube=3"]));
+}
+
+#[test]
+fn unlock_drink() {
+    check_golden("unlock_drink", &edit("unlock", &["--kind", "drinks", "--name", "test drink 2"]));
+}
+
+#[test]
+fn unlock_all_pickaxe_parts() {
+    check_golden(
+        "unlock_all_pickaxe_parts",
+        &edit("unlock", &["--kind", "pickaxe-parts", "--all"]),
+    );
+}
+
+#[test]
+fn missions_reset() {
+    check_golden(
+        "missions_reset",
+        &edit(
+            "missions",
+            &["--reset-deep-dives", "--reset-assignment", "00000000-0000-0000-0000-000000000501"],
+        ),
+    );
+}
+
+#[test]
+fn missions_complete() {
+    check_golden(
+        "missions_complete",
+        &edit(
+            "missions",
+            &[
+                "--complete-deep-dive",
+                "elite",
+                "--complete-assignment",
+                "00000000-0000-0000-0000-000000000502",
+            ],
+        ),
+    );
+}
+
+#[test]
+fn weapon_mods_of_class() {
+    check_golden("weapon_mods_of_class", &edit("weapon-mods", &["--class", "driller"]));
+}
+
+#[test]
+fn weapon_mods_up_to_tier() {
+    check_golden("weapon_mods_up_to_tier", &edit("weapon-mods", &["--all", "--max-tier", "1"]));
+}
+
+#[test]
+fn milestones() {
+    check_golden(
+        "milestones",
+        &edit(
+            "milestones",
+            &["--milestone", "test milestone=5", "--stat", "Test Statistic=2", "--stat-class", "0"],
+        ),
+    );
+}
+
+#[test]
+fn milestones_reset_stats() {
+    check_golden("milestones_reset_stats", &edit("milestones", &["--reset-stats"]));
+}
+
+#[test]
+fn cosmetics_equip() {
+    check_golden(
+        "cosmetics_equip",
+        &edit("cosmetics", &["--class", "driller", "--slot", "beard", "--item", "test dlc beard"]),
+    );
+}
+
+#[test]
+fn cosmetics_strip_dlc() {
+    check_golden("cosmetics_strip_dlc", &edit("cosmetics", &["--strip-dlc"]));
+}
+
+#[test]
+fn rewards_unclaim() {
+    check_golden(
+        "rewards_unclaim",
+        &edit("rewards", &["--unclaim", "SeasonSave.Seasons[*].RewardsClaimed[*].NormalClaimed"]),
+    );
+}
@@ -0,0 +1,86 @@
+//! Changes the player rank title and class names where the save stores them (see
+//! `drg-save identity`). Derived ones can't be changed this way.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::classes::{class_saves, resolve_class, ClassSelector};
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_db::GuidDb;
+use crate::identity::{set_class_name, set_rank_title};
+
+/// `<class>=<name>`: rename a class.
+#[derive(Debug, Clone)]
+pub struct ClassRename {
+    pub class: ClassSelector,
+    pub name: String,
+}
+
+impl FromStr for ClassRename {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((class, name)) = s.split_once('=') else {
+            return Err(format!("expected `<class>=<name>`, got `{s}`"));
+        };
+        let class = ClassSelector::from_str(class).unwrap_or_else(|e| match e {});
+        Ok(Self { class, name: name.to_string() })
+    }
+}
+
+pub struct IdentityEditor;
+
+impl Editor for IdentityEditor {
+    fn name(&self) -> &'static str {
+        "identity"
+    }
+
+    fn about(&self) -> &'static str {
+        "Set the stored player rank title and class names (see `drg-save identity`)"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("title")
+                .long("title")
+                .help("Rank title index to store")
+                .value_parser(value_parser!(i32)),
+            Arg::new("name")
+                .long("name")
+                .help("`<class>=<name>`: store a class's name. Can be given multiple times")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(ClassRename)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let title = args.get_one::<i32>("title").copied();
+        let renames: Vec<&ClassRename> =
+            args.get_many::<ClassRename>("name").unwrap_or_default().collect();
+        if title.is_none() && renames.is_empty() {
+            bail!(DrgSaveError::Usage("either `--title` or `--name` is required".to_string()));
+        }
+
+        let mut report = Report::default();
+        if let Some(title) = title {
+            for path in set_rank_title(&mut save.root.properties, title)? {
+                report.push(format!("set `{path}` to {title}"));
+            }
+        }
+        let db = GuidDb::load()?;
+        for ClassRename { class, name } in renames {
+            let slot = resolve_class(class_saves(save)?, class, &db)?;
+            for path in set_class_name(save, slot, name)? {
+                report.push(format!("set `{path}` to `{name}`"));
+            }
+        }
+        if report.lines.is_empty() {
+            report.push("every field already had that value");
+        }
+        Ok(report)
+    }
+}
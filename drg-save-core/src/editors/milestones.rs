@@ -0,0 +1,115 @@
+//! Sets milestone tiers and lifetime statistics, or resets the statistics page without losing any
+//! unlocks.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::classes::{class_saves, resolve_class, savegame_id, ClassSelector};
+use crate::counters::{reset_counters, resolve_counter, set_counter, CounterKind};
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_db::GuidDb;
+
+pub struct MilestonesEditor;
+
+impl Editor for MilestonesEditor {
+    fn name(&self) -> &'static str {
+        "milestones"
+    }
+
+    fn about(&self) -> &'static str {
+        "Set milestone tiers and lifetime statistics, or reset the statistics"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("reset-stats")
+                .long("reset-stats")
+                .help("Zero every lifetime statistic. Milestones and unlocks are kept")
+                .action(ArgAction::SetTrue),
+            Arg::new("milestone")
+                .long("milestone")
+                .help("`<name or GUID>=<n>`: set the tier of a milestone. Can be repeated")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(CounterValue)),
+            Arg::new("stat")
+                .long("stat")
+                .help(
+                    "`<name or GUID>=<n>`: set a lifetime statistic of every class, or of \
+                     `--stat-class`. Can be repeated",
+                )
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(CounterValue)),
+            Arg::new("stat-class")
+                .long("stat-class")
+                .help("Only set `--stat`s of this class, by class save slot or name")
+                .value_parser(value_parser!(ClassSelector))
+                .requires("stat"),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let mut report = Report::default();
+        let db = GuidDb::load()?;
+        let stat_class = match args.get_one::<ClassSelector>("stat-class") {
+            Some(selector) => {
+                let class_saves = class_saves(save)?;
+                let slot = resolve_class(class_saves, selector, &db)?;
+                Some(savegame_id(&class_saves[slot]).ok_or_else(|| {
+                    anyhow!(DrgSaveError::InvalidInput(format!(
+                        "{selector} has no `SavegameID` to match statistics with"
+                    )))
+                })?)
+            }
+            None => None,
+        };
+        let props = &mut save.root.properties;
+
+        if args.get_flag("reset-stats") {
+            let n = reset_counters(props, CounterKind::Statistics)?;
+            report.push(format!("reset {n} statistics"));
+        }
+        for (arg, kind, class) in [
+            ("milestone", CounterKind::Milestones, None),
+            ("stat", CounterKind::Statistics, stat_class),
+        ] {
+            for CounterValue { counter, value } in
+                args.get_many::<CounterValue>(arg).unwrap_or_default()
+            {
+                let guid = resolve_counter(kind, counter, &db)?;
+                for old in set_counter(props, kind, guid, class, *value)? {
+                    report.push(format!("set {kind} {} from {old} to {value}", db.label(guid)));
+                }
+            }
+        }
+
+        if report.lines.is_empty() {
+            bail!(DrgSaveError::Usage(
+                "nothing to do, see `drg-save edit milestones --help`".to_string()
+            ));
+        }
+        Ok(report)
+    }
+}
+
+/// `<name or GUID>=<n>`: set a counter to `n`.
+#[derive(Debug, Clone)]
+pub struct CounterValue {
+    pub counter: String,
+    pub value: f64,
+}
+
+impl FromStr for CounterValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((counter, value)) = s.rsplit_once('=') else {
+            return Err(format!("expected `<name>=<value>`, got `{s}`"));
+        };
+        let value = value.parse().map_err(|e| format!("invalid value `{value}`: {e}"))?;
+        Ok(Self { counter: counter.to_string(), value })
+    }
+}
@@ -0,0 +1,84 @@
+//! Resets or completes the weekly deep dives and assignments, so they can be tested without
+//! waiting for the in-game reset.
+
+use anyhow::{bail, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+use uuid::Uuid;
+
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::missions::{set_assignment_completed, set_deep_dive_completed, DeepDive};
+
+pub struct MissionsEditor;
+
+impl Editor for MissionsEditor {
+    fn name(&self) -> &'static str {
+        "missions"
+    }
+
+    fn about(&self) -> &'static str {
+        "Reset or complete the weekly deep dives and assignments"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("reset-deep-dives")
+                .long("reset-deep-dives")
+                .help("Mark this week's deep dives as not completed")
+                .action(ArgAction::SetTrue),
+            Arg::new("complete-deep-dive")
+                .long("complete-deep-dive")
+                .help("`normal` or `elite`: mark this week's deep dive as completed")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(DeepDive)),
+            Arg::new("complete-assignment")
+                .long("complete-assignment")
+                .help("GUID of an assignment to mark as completed. Can be repeated")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(Uuid)),
+            Arg::new("reset-assignment")
+                .long("reset-assignment")
+                .help(
+                    "GUID of an assignment to mark as not completed (see `drg-save missions`). \
+                     Can be repeated",
+                )
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(Uuid)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let mut report = Report::default();
+        let props = &mut save.root.properties;
+
+        if args.get_flag("reset-deep-dives") {
+            for dive in DeepDive::ALL {
+                set_deep_dive_completed(props, dive, false)?;
+                report.push(format!("reset {dive} deep dive"));
+            }
+        }
+        for dive in args.get_many::<DeepDive>("complete-deep-dive").unwrap_or_default() {
+            set_deep_dive_completed(props, *dive, true)?;
+            report.push(format!("completed {dive} deep dive"));
+        }
+        for (id, completed, verb) in
+            [("complete-assignment", true, "completed"), ("reset-assignment", false, "reset")]
+        {
+            for guid in args.get_many::<Uuid>(id).unwrap_or_default() {
+                if set_assignment_completed(props, *guid, completed)? {
+                    report.push(format!("{verb} assignment {guid}"));
+                } else {
+                    report.push(format!("assignment {guid} was already {verb}"));
+                }
+            }
+        }
+
+        if report.lines.is_empty() {
+            bail!(DrgSaveError::Usage(
+                "nothing to do, see `drg-save edit missions --help`".to_string()
+            ));
+        }
+        Ok(report)
+    }
+}
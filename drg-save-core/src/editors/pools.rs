@@ -0,0 +1,40 @@
+//! Prunes the schematic arrays lost packs and cargo crates check what to drop against of forged
+//! schematics still recorded as unforged, so they stop dropping schematics twice.
+
+use anyhow::Result;
+use clap::ArgMatches;
+use uesave::Save;
+
+use crate::editors::{Editor, Report};
+use crate::guid_db::GuidDb;
+use crate::pools::prune_pools;
+
+pub struct PoolsEditor;
+
+impl Editor for PoolsEditor {
+    fn name(&self) -> &'static str {
+        "pools"
+    }
+
+    fn about(&self) -> &'static str {
+        "Remove forged schematics still recorded as unforged (see `drg-save pools`)"
+    }
+
+    fn apply(&self, save: &mut Save, _args: &ArgMatches) -> Result<Report> {
+        let db = GuidDb::load()?;
+        let removed = prune_pools(&mut save.root.properties)?;
+
+        let mut report = Report::default();
+        for duplicate in &removed {
+            report.push(format!(
+                "removed {} entries of forged {} from `SchematicSave.OwnedSchematics`",
+                duplicate.count,
+                db.label(duplicate.guid)
+            ));
+        }
+        if removed.is_empty() {
+            report.push("no forged schematic is recorded as unforged");
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,72 @@
+//! Sets resource amounts, including the "miscellaneous" resources the game only adds to the save
+//! once they are first earned.
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::editors::{Editor, Report};
+use crate::guid_db::GuidDb;
+use crate::resources::{resolve_resource, set_resource, ResourceSelector};
+
+pub struct ResourcesEditor;
+
+impl Editor for ResourcesEditor {
+    fn name(&self) -> &'static str {
+        "resources"
+    }
+
+    fn about(&self) -> &'static str {
+        "Set the amount of minerals and other resources"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![Arg::new("set")
+            .long("set")
+            .help("`<resource>=<amount>`, the resource given by name or GUID. Can be repeated")
+            .required(true)
+            .action(ArgAction::Append)
+            .value_parser(value_parser!(ResourceAmount))]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let mut report = Report::default();
+        let db = GuidDb::load()?;
+        for ResourceAmount { resource, amount } in
+            args.get_many::<ResourceAmount>("set").unwrap_or_default()
+        {
+            let guid = resolve_resource(resource, &db)?;
+            let label = db.label(guid);
+            match set_resource(&mut save.root.properties, guid, *amount)? {
+                Some(old) => report.push(format!("set {label} from {old} to {amount}")),
+                None => report.push(format!("added {label} with {amount}")),
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// `<resource>=<amount>`: set a resource to `amount`.
+#[derive(Debug, Clone)]
+pub struct ResourceAmount {
+    pub resource: ResourceSelector,
+    pub amount: f32,
+}
+
+impl FromStr for ResourceAmount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((resource, amount)) = s.rsplit_once('=') else {
+            return Err(format!("expected `<resource>=<amount>`, got `{s}`"));
+        };
+        let amount: f32 = amount.parse().map_err(|e| format!("invalid amount `{amount}`: {e}"))?;
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(format!("amount must be a non-negative number, got `{amount}`"));
+        }
+        let resource = ResourceSelector::from_str(resource).unwrap_or_else(|e| match e {});
+        Ok(Self { resource, amount })
+    }
+}
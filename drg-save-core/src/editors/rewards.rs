@@ -0,0 +1,62 @@
+//! Repairs rewards stuck as claimed (or unclaimed) by setting their claim flags (see
+//! `drg-save rewards`).
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::rewards::set_claimed;
+
+pub struct RewardsEditor;
+
+impl Editor for RewardsEditor {
+    fn name(&self) -> &'static str {
+        "rewards"
+    }
+
+    fn about(&self) -> &'static str {
+        "Mark rewards as unclaimed (or claimed) to repair stuck rewards (see `drg-save rewards`)"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("unclaim")
+                .long("unclaim")
+                .help(
+                    "Path of a claim flag to clear, so the reward can be claimed again. `[*]` \
+                     matches any index. Can be repeated",
+                )
+                .action(ArgAction::Append),
+            Arg::new("claim")
+                .long("claim")
+                .help("Path of a claim flag to set. `[*]` matches any index. Can be repeated")
+                .action(ArgAction::Append),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let unclaim: Vec<&String> =
+            args.get_many::<String>("unclaim").unwrap_or_default().collect();
+        let claim: Vec<&String> = args.get_many::<String>("claim").unwrap_or_default().collect();
+        if unclaim.is_empty() && claim.is_empty() {
+            bail!(DrgSaveError::Usage("either `--unclaim` or `--claim` is required".to_string()));
+        }
+
+        let mut report = Report::default();
+        let props = &mut save.root.properties;
+        for (patterns, claimed) in [(unclaim, false), (claim, true)] {
+            for pattern in patterns {
+                for path in set_claimed(props, pattern, claimed)? {
+                    let state = if claimed { "claimed" } else { "unclaimed" };
+                    report.push(format!("marked `{path}` as {state}"));
+                }
+            }
+        }
+        if report.lines.is_empty() {
+            report.push("every matching reward already had that claim state");
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,69 @@
+//! Robustness tests: mutated and truncated copies of [`synthetic_save`] are fed through the same
+//! parse → edit → write pipeline as `drg-save edit`. Any step may fail, but only with an error: a
+//! panic fails the test, and so does a written save that doesn't parse back to what was written.
+//! `fuzz/` runs the same pipeline under cargo-fuzz.
+
+use std::path::Path;
+
+use clap::Command;
+use proptest::prelude::*;
+
+use super::editors;
+use crate::diff::find_difference;
+use crate::expected_schema::check_preconditions;
+use crate::fixtures::{fixture_guid_db, synthetic_save, with_guid_db, EDITS};
+
+fn synthetic_bytes() -> Vec<u8> {
+    let mut buf = vec![];
+    synthetic_save().write(&mut buf).unwrap();
+    buf
+}
+
+/// Run every edit in [`EDITS`] on the save in `buf`, writing the result like the CLI does.
+fn parse_edit_write(buf: &[u8]) {
+    with_guid_db(fixture_guid_db(), || edit_and_write(buf));
+}
+
+fn edit_and_write(buf: &[u8]) {
+    for (name, args) in EDITS {
+        let editor = editors().into_iter().find(|editor| editor.name() == *name).unwrap();
+        let matches = Command::new(*name)
+            .args(editor.args())
+            .try_get_matches_from(std::iter::once(*name).chain(args.iter().copied()))
+            .unwrap();
+        let Ok(mut save) = crate::parse_save(buf, Path::new("mutated.sav")) else {
+            return;
+        };
+        if check_preconditions(&save, name).is_err() || editor.apply(&mut save, &matches).is_err() {
+            continue;
+        }
+        if let Ok(written) = crate::serialize_save_verified(&save) {
+            let reparsed = crate::parse_save(&written, Path::new("written.sav")).unwrap();
+            assert_eq!(find_difference(&save, &reparsed), None, "`{name}` wrote a corrupt save");
+        }
+    }
+}
+
+#[test]
+fn unmutated_save_edits() {
+    parse_edit_write(&synthetic_bytes());
+}
+
+proptest! {
+    #[test]
+    fn truncated_save(len in 0..synthetic_bytes().len()) {
+        parse_edit_write(&synthetic_bytes()[..len]);
+    }
+
+    #[test]
+    fn mutated_save(
+        mutations in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8)
+    ) {
+        let mut buf = synthetic_bytes();
+        for (index, byte) in mutations {
+            let i = index.index(buf.len());
+            buf[i] = byte;
+        }
+        parse_edit_write(&buf);
+    }
+}
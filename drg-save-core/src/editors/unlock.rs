@@ -0,0 +1,80 @@
+//! Unlocks Abyss Bar drink recipes and pickaxe customization parts.
+
+use anyhow::{bail, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+use uuid::Uuid;
+
+use crate::editors::{required_arg, Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_db::GuidDb;
+use crate::unlocks::{list_unlocks, resolve_unlock, unlock, UnlockKind};
+
+pub struct UnlockEditor;
+
+impl Editor for UnlockEditor {
+    fn name(&self) -> &'static str {
+        "unlock"
+    }
+
+    fn about(&self) -> &'static str {
+        "Unlock drink recipes or pickaxe parts (see `drg-save unlocks` for what is locked)"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("kind")
+                .long("kind")
+                .help("What to unlock: `drinks` or `pickaxe-parts`")
+                .required(true)
+                .value_parser(value_parser!(UnlockKind)),
+            Arg::new("all")
+                .long("all")
+                .help("Unlock everything of this kind in the GUID database")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("name"),
+            Arg::new("name")
+                .long("name")
+                .help("Name in the GUID database or GUID to unlock. Can be repeated")
+                .action(ArgAction::Append),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let kind = *required_arg::<UnlockKind>(args, "kind")?;
+        let db = GuidDb::load()?;
+        let props = &mut save.root.properties;
+
+        let guids: Vec<Uuid> = if args.get_flag("all") {
+            db.require(kind.guid_kind())?;
+            list_unlocks(props, kind, &db)?
+                .into_iter()
+                .filter(|unlock| !unlock.unlocked)
+                .map(|unlock| unlock.guid)
+                .collect()
+        } else {
+            let names: Vec<&String> = args.get_many::<String>("name").unwrap_or_default().collect();
+            if names.is_empty() {
+                bail!(DrgSaveError::Usage("either `--all` or `--name` is required".to_string()));
+            }
+            names
+                .into_iter()
+                .map(|name| resolve_unlock(kind, name, &db))
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut report = Report::default();
+        for guid in guids {
+            let label = db.label(guid);
+            if unlock(props, kind, guid)? {
+                report.push(format!("unlocked {label}"));
+            } else {
+                report.push(format!("{label} was already unlocked"));
+            }
+        }
+        if report.lines.is_empty() {
+            report.push(format!("no locked {kind} known"));
+        }
+        Ok(report)
+    }
+}
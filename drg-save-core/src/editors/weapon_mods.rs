@@ -0,0 +1,160 @@
+//! Unlocks weapon modifications, as buying them at the equipment terminal would, so a fresh class
+//! can be brought to fully modded in one go. The save tracks bought mods as an array of their
+//! GUIDs, `PurchasedItemUpgrades` in the sample save uesave is tested with (`drg-save-test.sav`);
+//! which mod belongs to which weapon, class and tier comes from the GUID database, whose embedded
+//! dataset has none.
+
+use anyhow::{anyhow, bail, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use uesave::Save;
+
+use crate::classes::{class_saves, resolve_class, savegame_id, ClassSelector};
+use crate::editors::{Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_array::insert_guid;
+use crate::guid_db::{GuidDb, GuidEntry, GuidKind};
+
+/// GUID array of the weapon mods bought.
+const PURCHASED_UPGRADES: &str = "PurchasedItemUpgrades";
+
+pub struct WeaponModsEditor;
+
+impl Editor for WeaponModsEditor {
+    fn name(&self) -> &'static str {
+        "weapon-mods"
+    }
+
+    fn about(&self) -> &'static str {
+        "Unlock weapon modification tiers without spending credits and resources"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("all")
+                .long("all")
+                .help("Unlock the mods of every weapon of every class")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["class", "weapon"]),
+            Arg::new("class")
+                .long("class")
+                .help(
+                    "Only unlock the mods of this class's weapons, by class save slot or name. \
+                     Can be repeated",
+                )
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(ClassSelector)),
+            Arg::new("weapon")
+                .long("weapon")
+                .help("Only unlock the mods of this weapon. Can be repeated")
+                .action(ArgAction::Append),
+            Arg::new("max-tier")
+                .long("max-tier")
+                .help("Only unlock mods up to this tier")
+                .value_parser(value_parser!(u32)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let classes: Vec<ClassSelector> =
+            args.get_many::<ClassSelector>("class").unwrap_or_default().cloned().collect();
+        let weapons: Vec<String> =
+            args.get_many::<String>("weapon").unwrap_or_default().cloned().collect();
+        if !args.get_flag("all") && classes.is_empty() && weapons.is_empty() {
+            bail!(DrgSaveError::Usage(
+                "either `--all`, `--class` or `--weapon` is required".to_string()
+            ));
+        }
+        let max_tier = args.get_one::<u32>("max-tier").copied();
+        unlock_weapon_mods(save, &classes, &weapons, max_tier)
+    }
+}
+
+/// Unlock the weapon mods of `classes` and `weapons` (weapons by name, ignoring case; any if empty)
+/// up to `max_tier`, if given.
+pub fn unlock_weapon_mods(
+    save: &mut Save,
+    classes: &[ClassSelector],
+    weapons: &[String],
+    max_tier: Option<u32>,
+) -> Result<Report> {
+    let mut report = Report::default();
+    let db = GuidDb::load()?;
+    db.require(GuidKind::WeaponMod)?;
+    let class_saves = class_saves(save)?;
+    let classes = classes
+        .iter()
+        .map(|selector| {
+            let slot = resolve_class(class_saves, selector, &db)?;
+            savegame_id(&class_saves[slot])
+                .and_then(|id| db.name(id))
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    anyhow!(DrgSaveError::InvalidInput(format!(
+                        "{selector} has no class name in the GUID database"
+                    )))
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let matches_filter = |filters: &[String], value: &Option<String>| {
+        filters.is_empty()
+            || value.as_ref().is_some_and(|value| {
+                filters.iter().any(|filter| filter.eq_ignore_ascii_case(value))
+            })
+    };
+    let mods: Vec<&GuidEntry> = db
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == GuidKind::WeaponMod)
+        .filter(|entry| {
+            matches_filter(&classes, &entry.class) && matches_filter(weapons, &entry.weapon)
+        })
+        .filter(|entry| max_tier.is_none_or(|max| entry.tier.is_some_and(|tier| tier <= max)))
+        .collect();
+    if mods.is_empty() {
+        bail!(DrgSaveError::InvalidInput("no weapon mods in the GUID database match".to_string()));
+    }
+
+    let mut n_unlocked = 0;
+    for entry in mods {
+        if insert_guid(&mut save.root.properties, PURCHASED_UPGRADES, entry.guid)? {
+            let weapon = entry.weapon.as_deref().unwrap_or("unknown weapon");
+            report.push(format!("unlocked {weapon}: {}", entry.name));
+            n_unlocked += 1;
+        }
+    }
+    report.push(format!("unlocked {n_unlocked} weapon mods"));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fixture_guid_db, synthetic_save, with_guid_db, WEAPON_MODS};
+    use crate::guid_array::guids;
+
+    fn unlock(classes: &[ClassSelector], max_tier: Option<u32>) -> Result<Vec<uuid::Uuid>> {
+        let mut save = synthetic_save();
+        with_guid_db(fixture_guid_db(), || unlock_weapon_mods(&mut save, classes, &[], max_tier))?;
+        Ok(guids(&save.root.properties, PURCHASED_UPGRADES)?)
+    }
+
+    #[test]
+    fn unlocks_by_class_and_tier() {
+        // Gunner is in slot 2.
+        let gunner = [ClassSelector::Slot(2)];
+        assert_eq!(unlock(&gunner, None).unwrap(), [WEAPON_MODS[0], WEAPON_MODS[2]]);
+        let driller = [ClassSelector::Name("driller".to_string())];
+        assert_eq!(unlock(&driller, Some(1)).unwrap(), [WEAPON_MODS[0]]);
+        assert_eq!(unlock(&driller, None).unwrap(), [WEAPON_MODS[0], WEAPON_MODS[1]]);
+        assert!(unlock(&[ClassSelector::Slot(5)], None).is_err());
+    }
+
+    #[test]
+    fn needs_weapon_mods_in_the_guid_database() {
+        let error = unlock_weapon_mods(&mut synthetic_save(), &[], &[], None).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(crate::error::DrgSaveError::MissingGuidData { kind: GuidKind::WeaponMod })
+        ));
+    }
+}
@@ -0,0 +1,81 @@
+//! Sets a class's current level and XP into that level, so the level shown in-game matches,
+//! instead of only manipulating retired levels.
+
+use anyhow::Result;
+use clap::{value_parser, Arg, ArgMatches};
+use uesave::{Property, Save, StructValue};
+
+use crate::classes::{class_saves_mut, resolve_class, ClassSelector};
+use crate::editors::{required_arg, Editor, Report};
+use crate::error::DrgSaveError;
+use crate::guid_db::GuidDb;
+use crate::util::property_mut;
+use crate::xp::{level_for_xp, xp_for, MAX_LEVEL};
+
+pub struct XpEditor;
+
+impl Editor for XpEditor {
+    fn name(&self) -> &'static str {
+        "xp"
+    }
+
+    fn about(&self) -> &'static str {
+        "Set a class's current level and XP into that level"
+    }
+
+    fn args(&self) -> Vec<Arg> {
+        vec![
+            Arg::new("class")
+                .long("class")
+                .help("Class name or index of the class save slot in `CharacterSaves`")
+                .required(true)
+                .value_parser(value_parser!(ClassSelector)),
+            Arg::new("level")
+                .long("level")
+                .help("Level to show in-game, 1 to 25")
+                .required(true)
+                .value_parser(value_parser!(u8).range(1..=i64::from(MAX_LEVEL))),
+            Arg::new("xp-into-level")
+                .long("xp-into-level")
+                .help("XP earned towards the next level [default: 0]")
+                .value_parser(value_parser!(i32)),
+        ]
+    }
+
+    fn apply(&self, save: &mut Save, args: &ArgMatches) -> Result<Report> {
+        let class = required_arg::<ClassSelector>(args, "class")?;
+        let level = *required_arg::<u8>(args, "level")?;
+        let xp_into_level = args.get_one::<i32>("xp-into-level").copied().unwrap_or(0);
+        set_level(save, class, level, xp_into_level)
+    }
+}
+
+/// Set the `XP` of `class` so that it shows up at `level` with `xp_into_level` towards the next
+/// level.
+pub fn set_level(
+    save: &mut Save,
+    class: &ClassSelector,
+    level: u8,
+    xp_into_level: i32,
+) -> Result<Report> {
+    let mut report = Report::default();
+    let xp = xp_for(level, xp_into_level)?;
+
+    let class_saves = class_saves_mut(save)?;
+    let slot = resolve_class(class_saves, class, &GuidDb::load()?)?;
+    let StructValue::Struct(props) = &mut class_saves[slot] else {
+        return Err(DrgSaveError::unexpected_class_save_kind().into());
+    };
+    let Some(Property::Int { value, .. }) = property_mut(props, "XP") else {
+        return Err(DrgSaveError::missing("XP").into());
+    };
+    let (old_level, old_xp_into_level) = level_for_xp(*value);
+    *value = xp;
+
+    report
+        .push(format!("{class} was level {old_level} with {old_xp_into_level} XP into the level"));
+    report.push(format!(
+        "set {class} to level {level} with {xp_into_level} XP into the level ({xp} XP)"
+    ));
+    Ok(report)
+}
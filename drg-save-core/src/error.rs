@@ -0,0 +1,76 @@
+//! Error type of the save editing logic, so library consumers can tell failures apart without
+//! matching on messages. The binaries wrap it in `anyhow` like any other error.
+
+use std::path::PathBuf;
+
+use uesave::Property;
+
+use crate::describe::property_type_name;
+use crate::guid_db::GuidKind;
+
+pub type Result<T, E = DrgSaveError> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DrgSaveError {
+    /// A property the editing logic relies on is not in the save.
+    #[error("`{path}` not found")]
+    MissingProperty { path: String },
+    /// A property is in the save but isn't of the expected type.
+    #[error("expected {expected} for `{path}`, found {found}")]
+    TypeMismatch { path: String, expected: String, found: String },
+    /// The save (or a part of it) isn't laid out the way the editing logic expects.
+    #[error("{0}")]
+    UnexpectedShape(String),
+    /// The requested edit doesn't make sense for this save, e.g. an unknown class.
+    #[error("{0}")]
+    InvalidInput(String),
+    /// The arguments don't say what to do, e.g. none of several alternative options was given.
+    #[error("{0}")]
+    Usage(String),
+    /// The GUID database has no entries of a kind a feature needs (see [`crate::guid_db`]).
+    #[error(
+        "the GUID database has no {kind} entries, install a dataset that has them with `db update`"
+    )]
+    MissingGuidData { kind: GuidKind },
+    /// Another process kept the save (or the snapshot store) locked (see [`crate::lock`]) for too
+    /// long.
+    #[error("`{}` is locked by another tool", path.display())]
+    Locked { path: PathBuf },
+    /// Another process wrote the save since it was read, so writing it back would lose that write.
+    #[error("save file `{}` was written by another tool since it was read", path.display())]
+    Changed { path: PathBuf },
+    /// The save is from a newer game version than the editing logic was validated against (see
+    /// [`crate::version`]).
+    #[error("save file is from a newer game version than this tool supports: {version}")]
+    UnsupportedVersion { version: String },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// `uesave` could not parse the save. `offset` is how far into the file it got, which points
+    /// at (or just past) the property it choked on.
+    #[error("failed to parse save file `{}` at byte {offset} of {len}", path.display())]
+    Parse {
+        path: PathBuf,
+        offset: u64,
+        len: u64,
+        #[source]
+        source: uesave::ParseError,
+    },
+}
+
+impl DrgSaveError {
+    pub(crate) fn missing(path: impl Into<String>) -> Self {
+        Self::MissingProperty { path: path.into() }
+    }
+
+    pub(crate) fn type_mismatch(path: impl Into<String>, expected: &str, found: &Property) -> Self {
+        Self::TypeMismatch {
+            path: path.into(),
+            expected: expected.to_string(),
+            found: property_type_name(found).to_string(),
+        }
+    }
+
+    pub(crate) fn unexpected_class_save_kind() -> Self {
+        Self::UnexpectedShape("unexpected `class_save` struct value kind".to_string())
+    }
+}
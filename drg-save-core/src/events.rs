@@ -0,0 +1,395 @@
+//! Progress of the weekly core hunt and of machine events (the Omen tower, the Tritilyte crystal,
+//! data deposits, ...).
+//!
+//! The core hunt is the `WeeklyCampaignItem` at [`CORE_HUNT_PATH`]: the week it was last started
+//! and the week it was last completed, so it counts as completed once both are the same week.
+//! Machine events don't sit at a fixed path and are recognized by their GUIDs (kind
+//! `machine_event` in the GUID database): as the key of a counter in a map, or as a GUID property
+//! of a struct, whose other values are then the event's progress. The embedded database has no
+//! machine events, so editing them needs a community dataset (see `drg-save db update`).
+
+use std::fmt;
+
+use serde::Serialize;
+use uesave::{Properties, Property, PropertyValue, StructValue};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::{GuidDb, GuidKind};
+use crate::walk::{property_at, property_at_mut, split_path, walk_properties, walk_properties_mut};
+
+/// What the core hunt's values are reported as belonging to.
+pub const CORE_HUNT: &str = "core hunt";
+
+/// Path of the core hunt's `WeeklyCampaignItem`, with the game's spelling.
+pub const CORE_HUNT_PATH: &str = "CampaignSave.MaxtrixCoreHuntSave";
+const LAST_STARTED_WEEK: &str = "LastStartedWeek";
+const LAST_COMPLETED_WEEK: &str = "LastCompletedWeek";
+/// What [`ProgressAction::Reset`] sets `LastCompletedWeek` to: no week, so the core hunt of the
+/// week it was started in can be completed again.
+const NO_WEEK: i32 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ProgressValue {
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+}
+
+impl fmt::Display for ProgressValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Progress {
+    /// Path of the value, e.g. `CampaignSave.MaxtrixCoreHuntSave.LastStartedWeek`, or of the map
+    /// entry holding it.
+    pub path: String,
+    /// [`CORE_HUNT`] or the name of the machine event.
+    pub event: String,
+    pub value: ProgressValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressAction {
+    /// Mark the core hunt as not completed, zero machine event counters and clear their flags,
+    /// e.g. to test them again.
+    Reset,
+    /// Mark the core hunt as completed in the week it was started, set machine event flags and
+    /// set their counters to `count`. The save doesn't say how far counters go, so completing an
+    /// event with counters needs `count`.
+    Complete { count: Option<f32> },
+}
+
+impl ProgressAction {
+    fn apply(self, value: ProgressValue) -> Result<ProgressValue> {
+        Ok(match (self, value) {
+            (Self::Reset, ProgressValue::Int(_)) => ProgressValue::Int(0),
+            (Self::Reset, ProgressValue::Float(_)) => ProgressValue::Float(0.0),
+            (Self::Reset, ProgressValue::Bool(_)) => ProgressValue::Bool(false),
+            (Self::Complete { .. }, ProgressValue::Bool(_)) => ProgressValue::Bool(true),
+            (Self::Complete { count: Some(count) }, ProgressValue::Int(_)) => {
+                ProgressValue::Int(count as i32)
+            }
+            (Self::Complete { count: Some(count) }, ProgressValue::Float(_)) => {
+                ProgressValue::Float(count)
+            }
+            (Self::Complete { count: None }, _) => {
+                return Err(DrgSaveError::InvalidInput(
+                    "the save doesn't say how far machine event counters go, pass the count to \
+                     complete them with"
+                        .to_string(),
+                ))
+            }
+        })
+    }
+}
+
+fn core_hunt_week(props: &Properties, field: &str) -> Option<i32> {
+    match property_at(props, &format!("{CORE_HUNT_PATH}.{field}"))? {
+        Property::Int { value, .. } => Some(*value),
+        _ => None,
+    }
+}
+
+/// Whether `db` knows any machine events, which locating their progress needs.
+pub fn has_machine_events(db: &GuidDb) -> bool {
+    db.has(GuidKind::MachineEvent)
+}
+
+fn machine_event(guid: Uuid, db: &GuidDb) -> Option<&str> {
+    db.get(guid)
+        .filter(|entry| entry.kind == GuidKind::MachineEvent)
+        .map(|entry| entry.name.as_str())
+}
+
+/// Paths of the structs holding a machine event's GUID, with the name of the event.
+fn event_structs(props: &Properties, db: &GuidDb) -> Vec<(String, String)> {
+    let mut structs = vec![];
+    walk_properties(props, &mut |path, prop| {
+        if let Property::Struct { value: StructValue::Guid(guid), .. } = prop {
+            let parent = split_path(path).0;
+            if let (false, Some(event)) = (parent.is_empty(), machine_event(*guid, db)) {
+                structs.push((parent.to_string(), event.to_string()));
+            }
+        }
+    });
+    structs
+}
+
+/// The machine event the value at `path` belongs to.
+fn event_of(path: &str, structs: &[(String, String)]) -> Option<String> {
+    structs
+        .iter()
+        .find(|(parent, _)| path.starts_with(&format!("{parent}.")))
+        .map(|(_, event)| event.clone())
+}
+
+fn scalar(prop: &Property) -> Option<ProgressValue> {
+    match prop {
+        Property::Int { value, .. } => Some(ProgressValue::Int(*value)),
+        Property::Float { value, .. } => Some(ProgressValue::Float(*value)),
+        Property::Bool { value, .. } => Some(ProgressValue::Bool(*value)),
+        _ => None,
+    }
+}
+
+fn map_counter(value: &PropertyValue) -> Option<ProgressValue> {
+    match value {
+        PropertyValue::Int(value) => Some(ProgressValue::Int(*value)),
+        PropertyValue::Float(value) => Some(ProgressValue::Float(*value)),
+        _ => None,
+    }
+}
+
+/// The core hunt and machine event progress in the save.
+pub fn event_progress(props: &Properties, db: &GuidDb) -> Vec<Progress> {
+    let mut progress = vec![];
+    for field in [LAST_STARTED_WEEK, LAST_COMPLETED_WEEK] {
+        if let Some(week) = core_hunt_week(props, field) {
+            progress.push(Progress {
+                path: format!("{CORE_HUNT_PATH}.{field}"),
+                event: CORE_HUNT.to_string(),
+                value: ProgressValue::Int(week),
+            });
+        }
+    }
+    let structs = event_structs(props, db);
+    walk_properties(props, &mut |path, prop| match prop {
+        Property::Map { value, .. } => {
+            for (i, entry) in value.iter().enumerate() {
+                let PropertyValue::Struct(StructValue::Guid(guid)) = entry.key else {
+                    continue;
+                };
+                if let (Some(event), Some(value)) =
+                    (machine_event(guid, db), map_counter(&entry.value))
+                {
+                    let path = format!("{path}[{i}]");
+                    progress.push(Progress { path, event: event.to_string(), value });
+                }
+            }
+        }
+        prop => {
+            if let (Some(event), Some(value)) = (event_of(path, &structs), scalar(prop)) {
+                progress.push(Progress { path: path.to_string(), event, value });
+            }
+        }
+    });
+    progress
+}
+
+/// Apply `action` to the progress of the events whose name contains `event` (case-insensitive),
+/// or of all of them. Returns the paths of the values that changed.
+pub fn edit_progress(
+    props: &mut Properties,
+    db: &GuidDb,
+    event: Option<&str>,
+    action: ProgressAction,
+) -> Result<Vec<String>> {
+    let selected =
+        |name: &str| event.is_none_or(|event| name.to_lowercase().contains(&event.to_lowercase()));
+    let mut n_matched = 0;
+    let mut changed = vec![];
+    if selected(CORE_HUNT) {
+        if let Some(path) = edit_core_hunt(props, action)? {
+            changed.push(path);
+        }
+        n_matched += 1;
+    }
+
+    if !has_machine_events(db) {
+        if n_matched == 0 {
+            return Err(DrgSaveError::InvalidInput(
+                "the GUID database has no machine events, install a dataset with them with `db \
+                 update` to edit them"
+                    .to_string(),
+            ));
+        }
+        return Ok(changed);
+    }
+    let structs = event_structs(props, db);
+    let mut error = None;
+    walk_properties_mut(props, &mut |path, prop| match prop {
+        Property::Map { value, .. } => {
+            for (i, entry) in value.iter_mut().enumerate() {
+                let PropertyValue::Struct(StructValue::Guid(guid)) = entry.key else {
+                    continue;
+                };
+                if !machine_event(guid, db).is_some_and(selected) {
+                    continue;
+                }
+                let Some(old) = map_counter(&entry.value) else {
+                    continue;
+                };
+                n_matched += 1;
+                let new = match action.apply(old) {
+                    Ok(new) => new,
+                    Err(e) => return error = Some(e),
+                };
+                if new != old {
+                    entry.value = match new {
+                        ProgressValue::Int(value) => PropertyValue::Int(value),
+                        ProgressValue::Float(value) => PropertyValue::Float(value),
+                        ProgressValue::Bool(value) => PropertyValue::Bool(value),
+                    };
+                    changed.push(format!("{path}[{i}]"));
+                }
+            }
+        }
+        prop => {
+            let (Some(event), Some(old)) = (event_of(path, &structs), scalar(prop)) else {
+                return;
+            };
+            if !selected(&event) {
+                return;
+            }
+            n_matched += 1;
+            let new = match action.apply(old) {
+                Ok(new) => new,
+                Err(e) => return error = Some(e),
+            };
+            if new != old {
+                match (prop, new) {
+                    (Property::Int { value, .. }, ProgressValue::Int(new)) => *value = new,
+                    (Property::Float { value, .. }, ProgressValue::Float(new)) => *value = new,
+                    (Property::Bool { value, .. }, ProgressValue::Bool(new)) => *value = new,
+                    _ => return,
+                }
+                changed.push(path.to_string());
+            }
+        }
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    if n_matched == 0 {
+        return Err(DrgSaveError::InvalidInput(match event {
+            Some(event) => format!("no core hunt or machine event progress matches `{event}`"),
+            None => "the save has no core hunt or machine event progress the GUID database can \
+                     locate"
+                .to_string(),
+        }));
+    }
+    Ok(changed)
+}
+
+/// Mark the core hunt as completed or not. Returns the path of `LastCompletedWeek` if it changed.
+fn edit_core_hunt(props: &mut Properties, action: ProgressAction) -> Result<Option<String>> {
+    let started = core_hunt_week(props, LAST_STARTED_WEEK);
+    let path = format!("{CORE_HUNT_PATH}.{LAST_COMPLETED_WEEK}");
+    let (Some(started), Some(Property::Int { value: completed, .. })) =
+        (started, property_at_mut(props, &path))
+    else {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "the save has no core hunt progress at `{CORE_HUNT_PATH}`"
+        )));
+    };
+    let new = match action {
+        ProgressAction::Reset => NO_WEEK,
+        ProgressAction::Complete { .. } => started,
+    };
+    if *completed == new {
+        return Ok(None);
+    }
+    *completed = new;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::{MapEntry, PropertyKey, PropertyType};
+
+    use super::*;
+    use crate::fixtures::{synthetic_save, CORE_HUNT_STARTED_WEEK};
+    use crate::guid_db::GuidEntry;
+
+    const OMEN: Uuid = uuid::uuid!("00000000-0000-0000-0000-00000000e001");
+
+    fn db_with_omen() -> GuidDb {
+        GuidDb {
+            entries: vec![GuidEntry {
+                guid: OMEN,
+                kind: GuidKind::MachineEvent,
+                name: "Omen".to_string(),
+                dlc: None,
+                weapon: None,
+                class: None,
+                tier: None,
+                slot: None,
+                default: false,
+            }],
+        }
+    }
+
+    fn completed_week(props: &Properties) -> Option<i32> {
+        core_hunt_week(props, LAST_COMPLETED_WEEK)
+    }
+
+    #[test]
+    fn core_hunt_is_completed_in_the_week_it_was_started() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        let complete = ProgressAction::Complete { count: None };
+        let changed = edit_progress(props, &GuidDb::default(), None, complete).unwrap();
+        assert_eq!(changed, [format!("{CORE_HUNT_PATH}.{LAST_COMPLETED_WEEK}")]);
+        assert_eq!(completed_week(props), Some(CORE_HUNT_STARTED_WEEK));
+
+        edit_progress(props, &GuidDb::default(), Some("core"), ProgressAction::Reset).unwrap();
+        assert_eq!(completed_week(props), Some(NO_WEEK));
+        // Only the completion is touched, not the week it was started or the other campaigns.
+        assert_eq!(core_hunt_week(props, LAST_STARTED_WEEK), Some(CORE_HUNT_STARTED_WEEK));
+        assert!(matches!(
+            property_at(props, "CampaignSave.WeeklySave.LastCompletedWeek"),
+            Some(Property::Int { value: 1_630_837_160, .. })
+        ));
+    }
+
+    #[test]
+    fn machine_events_need_the_guid_database() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        let error = edit_progress(props, &GuidDb::default(), Some("omen"), ProgressAction::Reset);
+        assert!(error.unwrap_err().to_string().contains("no machine events"));
+    }
+
+    #[test]
+    fn machine_event_counters() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        props.0.insert(
+            PropertyKey::from("EventCounters"),
+            Property::Map {
+                key_type: PropertyType::StructProperty,
+                value_type: PropertyType::IntProperty,
+                id: None,
+                value: vec![MapEntry {
+                    key: PropertyValue::Struct(StructValue::Guid(OMEN)),
+                    value: PropertyValue::Int(2),
+                }],
+            },
+        );
+        let db = db_with_omen();
+        let progress = event_progress(props, &db);
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress[2].event, "Omen");
+
+        let complete = ProgressAction::Complete { count: None };
+        assert!(edit_progress(props, &db, Some("omen"), complete).is_err());
+        let complete = ProgressAction::Complete { count: Some(5.0) };
+        let changed = edit_progress(props, &db, Some("omen"), complete).unwrap();
+        assert_eq!(changed, ["EventCounters[0]"]);
+        assert_eq!(event_progress(props, &db)[2].value, ProgressValue::Int(5));
+
+        edit_progress(props, &db, Some("omen"), ProgressAction::Reset).unwrap();
+        assert_eq!(event_progress(props, &db)[2].value, ProgressValue::Int(0));
+        // The core hunt wasn't selected.
+        assert_eq!(completed_week(props), Some(1_617_748_626));
+    }
+}
@@ -0,0 +1,184 @@
+//! The layout the editors expect of a save, as data: for a property path, its expected type,
+//! struct type and number of elements, and which editors rely on it. When a game update changes
+//! the layout, only `data/expected_schema.json` needs updating.
+//!
+//! Types are named as `drg-save schema` prints them, struct types as uesave serializes them (e.g.
+//! `"Guid"` or `{ "Struct": "CharacterSave" }`), and `[*]` in a path matches any index (see
+//! [`crate::restore::path_matches`]).
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uesave::{Property, Save, StructType, ValueArray};
+
+use crate::describe::{property_len, property_type_name};
+use crate::error::{DrgSaveError, Result};
+use crate::restore::path_matches;
+use crate::walk::walk_properties;
+
+const EMBEDDED: &str = include_str!("../data/expected_schema.json");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expectation {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub struct_type: Option<StructType>,
+    /// Number of elements of an array, set or map.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub len: Option<usize>,
+    /// Names of the editors that rely on the property.
+    #[serde(default)]
+    pub editors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpectedSchema {
+    pub expectations: Vec<Expectation>,
+}
+
+/// A way in which a save differs from an [`Expectation`].
+#[derive(Debug, Serialize)]
+pub struct Mismatch {
+    pub path: String,
+    pub problem: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.path, self.problem)
+    }
+}
+
+impl ExpectedSchema {
+    /// The expected schema embedded in the binary.
+    pub fn embedded() -> Self {
+        serde_json::from_str(EMBEDDED).expect("embedded expected schema is valid")
+    }
+
+    /// Every way in which `save` differs from the expectations, or only from those `editor`
+    /// relies on.
+    pub fn check(&self, save: &Save, editor: Option<&str>) -> Vec<Mismatch> {
+        let expectations: Vec<&Expectation> = self
+            .expectations
+            .iter()
+            .filter(|expectation| {
+                editor.is_none_or(|editor| expectation.editors.iter().any(|name| name == editor))
+            })
+            .collect();
+        let mut found = vec![false; expectations.len()];
+        let mut mismatches = vec![];
+        walk_properties(&save.root.properties, &mut |path, prop| {
+            for (expectation, found) in expectations.iter().zip(&mut found) {
+                if path_matches(&expectation.path, path) {
+                    *found = true;
+                    mismatches.extend(
+                        check_property(expectation, prop)
+                            .map(|problem| Mismatch { path: path.to_string(), problem }),
+                    );
+                }
+            }
+        });
+        for (expectation, found) in expectations.iter().zip(found) {
+            if !found {
+                mismatches.push(Mismatch {
+                    path: expectation.path.clone(),
+                    problem: "missing".to_string(),
+                });
+            }
+        }
+        mismatches
+    }
+}
+
+fn check_property(expectation: &Expectation, prop: &Property) -> Option<String> {
+    let type_name = property_type_name(prop);
+    if type_name != expectation.type_name {
+        return Some(format!("expected {}, found {type_name}", expectation.type_name));
+    }
+    if let Some(expected) = &expectation.struct_type {
+        let struct_type = match prop {
+            Property::Struct { struct_type, .. }
+            | Property::Array { value: ValueArray::Struct { struct_type, .. }, .. } => {
+                Some(struct_type)
+            }
+            _ => None,
+        };
+        if struct_type != Some(expected) {
+            return Some(format!(
+                "expected struct type {expected:?}, found {}",
+                struct_type.map_or_else(|| "none".to_string(), |found| format!("{found:?}"))
+            ));
+        }
+    }
+    if let Some(expected) = expectation.len {
+        let len = property_len(prop);
+        if len != Some(expected) {
+            return Some(format!(
+                "expected {expected} elements, found {}",
+                len.map_or_else(|| "none".to_string(), |len| len.to_string())
+            ));
+        }
+    }
+    None
+}
+
+/// Check that `save` has the layout `editor` relies on.
+pub fn check_preconditions(save: &Save, editor: &str) -> Result<()> {
+    let mismatches = ExpectedSchema::embedded().check(save, Some(editor));
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    let mismatches: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+    Err(DrgSaveError::UnexpectedShape(format!(
+        "save is not laid out as `{editor}` expects: {}",
+        mismatches.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editors::editors;
+    use crate::fixtures::synthetic_save;
+    use crate::walk::property_at_mut;
+
+    #[test]
+    fn every_editor_has_expectations() {
+        let schema = ExpectedSchema::embedded();
+        for editor in editors() {
+            assert!(
+                schema.expectations.iter().any(|e| e.editors.iter().any(|n| n == editor.name())),
+                "`{}` has no expectations",
+                editor.name()
+            );
+        }
+    }
+
+    #[test]
+    fn synthetic_save_is_laid_out_as_expected() {
+        let mismatches = ExpectedSchema::embedded().check(&synthetic_save(), None);
+        assert!(mismatches.is_empty(), "{mismatches:?}");
+    }
+
+    #[test]
+    fn compares_struct_types_structurally() {
+        let mut save = synthetic_save();
+        let Some(Property::Array { value: ValueArray::Struct { struct_type, .. }, .. }) =
+            property_at_mut(&mut save.root.properties, "Milestones.ClaimedKPIRewards")
+        else {
+            panic!("`Milestones.ClaimedKPIRewards` is a struct array");
+        };
+        *struct_type = StructType::Struct(Some("MissionStatCounter".to_string()));
+
+        let mismatches = ExpectedSchema::embedded().check(&save, Some("milestones"));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].path, "Milestones.ClaimedKPIRewards");
+        assert_eq!(
+            mismatches[0].problem,
+            "expected struct type Struct(Some(\"MilestoneCounter\")), found \
+             Struct(Some(\"MissionStatCounter\"))"
+        );
+    }
+}
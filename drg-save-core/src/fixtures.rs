@@ -0,0 +1,620 @@
+//! Synthetic saves mimicking the layout of a DRG save, for tests of the editing logic without
+//! shipping real (account-identifying) saves.
+//!
+//! [`synthetic_save`] holds the 4 active classes plus the hidden one in `CharacterSaves`, credits,
+//! an `OwnedResources` map, the schematic arrays, the weekly campaigns and the drink and pickaxe
+//! part sets, with the values documented on each helper. [`fixture_guid_db`] names the made-up
+//! GUIDs. [`sample_save`] is the real (and already public) save uesave is tested with, for checks
+//! against the quirks of saves the game wrote itself.
+
+use std::path::{Path, PathBuf};
+
+use uesave::{
+    Header, MapEntry, Properties, Property, PropertyKey, PropertyType, PropertyValue, Root, Save,
+    StructType, StructValue, ValueArray, ValueSet,
+};
+use uuid::Uuid;
+
+use crate::classes::HIDDEN_CLASS_SAVEGAME_ID;
+use crate::guid_db::{GuidDb, GuidEntry, GuidKind, TEST_ENTRIES};
+use crate::version::KNOWN_VERSIONS;
+
+pub const DRILLER: Uuid = uuid::uuid!("f156dd9e-48c5-bcee-5b5e-5b8db42db680");
+pub const ENGINEER: Uuid = uuid::uuid!("6c62ef85-4a02-f165-d0b5-fe8d2e9d90f3");
+pub const GUNNER: Uuid = uuid::uuid!("80e156ae-4dc4-c0fe-c229-fa967bb96683");
+pub const SCOUT: Uuid = uuid::uuid!("17ead830-4cba-fbd8-e96d-30958c2f5c65");
+
+/// Made-up resource GUIDs held in `OwnedResources`.
+pub const RESOURCE_A: Uuid = uuid::uuid!("00000000-0000-0000-0000-00000000000a");
+pub const RESOURCE_B: Uuid = uuid::uuid!("00000000-0000-0000-0000-00000000000b");
+/// Made-up GUID of the error cube resource, which the save doesn't hold yet.
+pub const ERROR_CUBE: Uuid = uuid::uuid!("00000000-0000-0000-0000-00000000000c");
+
+/// Made-up schematic GUIDs: two unforged and one forged.
+pub const UNFORGED_SCHEMATICS: [Uuid; 2] = [
+    uuid::uuid!("00000000-0000-0000-0000-000000000101"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000102"),
+];
+pub const FORGED_SCHEMATIC: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000201");
+
+/// Made-up drink GUIDs: the first is unlocked, the second isn't.
+pub const DRINKS: [Uuid; 2] = [
+    uuid::uuid!("00000000-0000-0000-0000-000000000301"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000302"),
+];
+/// Made-up pickaxe part GUIDs: the first is unlocked, the second isn't.
+pub const PICKAXE_PARTS: [Uuid; 2] = [
+    uuid::uuid!("00000000-0000-0000-0000-000000000401"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000402"),
+];
+
+/// Made-up weapon mod GUIDs: tiers 1 and 2 of a Driller weapon, tier 1 of a Gunner weapon. Only the
+/// first is bought.
+pub const WEAPON_MODS: [Uuid; 3] = [
+    uuid::uuid!("00000000-0000-0000-0000-000000000701"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000702"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000703"),
+];
+
+/// Made-up GUID of the victory move every active class has equipped.
+pub const VICTORY_MOVE: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000901");
+/// Made-up beard GUIDs: the Driller's default, the Gunner's default and one from a DLC.
+pub const BEARDS: [Uuid; 3] = [
+    uuid::uuid!("00000000-0000-0000-0000-000000000911"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000912"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000913"),
+];
+
+/// Made-up GUID of the milestone in `Milestones.ClaimedKPIRewards`.
+pub const MILESTONE: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000801");
+/// Made-up GUID of the statistic in `MissionStatsSave.Counters`.
+pub const STATISTIC: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000802");
+
+/// Made-up GUID of the season in `SeasonSave.Seasons`.
+pub const SEASON: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000a01");
+/// Made-up season challenge GUIDs: the first two are assigned, the third isn't.
+pub const SEASON_CHALLENGES: [Uuid; 3] = [
+    uuid::uuid!("00000000-0000-0000-0000-000000000b01"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000b02"),
+    uuid::uuid!("00000000-0000-0000-0000-000000000b03"),
+];
+
+/// Made-up GUID of the completed assignment of [`synthetic_save`].
+pub const COMPLETED_ASSIGNMENT: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000501");
+
+/// The week the core hunt of [`synthetic_save`] was last started in.
+pub const CORE_HUNT_STARTED_WEEK: i32 = -1_486_169_451;
+
+/// A save with:
+///
+/// - `CharacterSaves`: Driller (3 promotions, 10 retired levels, 50 000 XP), Engineer (1, 0, 0),
+///   Gunner (5, 25, 315 000), Scout (0, 4, 12 000) and the hidden class (0, 0, 0). The active
+///   classes have [`VICTORY_MOVE`] equipped and two vanity loadouts, the first selected, with the
+///   beards [`BEARDS`]`[0]` and `[2]` for the Driller, `[2]` and `[1]` for the Gunner and none for
+///   the others.
+/// - `Credits`: 123 456.
+/// - `Resources.OwnedResources`: 100 of [`RESOURCE_A`] and 2.5 of [`RESOURCE_B`].
+/// - `SchematicSave`: [`UNFORGED_SCHEMATICS`] owned, [`FORGED_SCHEMATIC`] forged.
+/// - `CampaignSave`: `WeeklySave` and `MaxtrixCoreHuntSave`, with the week ids of the sample save
+///   uesave is tested with (`drg-save-test.sav`); the core hunt was last started in
+///   [`CORE_HUNT_STARTED_WEEK`] and is not completed. `CompletedCampaigns` holds
+///   [`COMPLETED_ASSIGNMENT`].
+/// - `DeepDiveSave`: this week's normal deep dive is completed, the elite one isn't.
+/// - `Drinks.UnlockedDrinks` and `UnlockedPickaxeParts`: the first of [`DRINKS`] and of
+///   [`PICKAXE_PARTS`].
+/// - `PurchasedItemUpgrades`: the first of [`WEAPON_MODS`].
+/// - `Milestones.ClaimedKPIRewards`: tier 4 of [`MILESTONE`].
+/// - `MissionStatsSave.Counters`: [`STATISTIC`] at 10 for the Driller and 5 for the Gunner.
+/// - `SeasonSave.Seasons`: [`SEASON`] at 5 000 XP and 3 tokens, with the normal reward of the
+///   first of its two levels claimed, and the first two [`SEASON_CHALLENGES`] assigned at 1 of 3
+///   and 2 of 2 wins.
+pub fn synthetic_save() -> Save {
+    let class_saves = vec![
+        with_cosmetics(class_save(DRILLER, 3, 10, 50_000), [BEARDS[0], BEARDS[2]]),
+        with_cosmetics(class_save(ENGINEER, 1, 0, 0), [Uuid::nil(); 2]),
+        with_cosmetics(class_save(GUNNER, 5, 25, 315_000), [BEARDS[2], BEARDS[1]]),
+        with_cosmetics(class_save(SCOUT, 0, 4, 12_000), [Uuid::nil(); 2]),
+        class_save(HIDDEN_CLASS_SAVEGAME_ID, 0, 0, 0),
+    ];
+
+    let mut resources = Properties::default();
+    insert(
+        &mut resources,
+        "OwnedResources",
+        Property::Map {
+            key_type: PropertyType::StructProperty,
+            value_type: PropertyType::FloatProperty,
+            id: None,
+            value: vec![resource(RESOURCE_A, 100.0), resource(RESOURCE_B, 2.5)],
+        },
+    );
+
+    let mut schematics = Properties::default();
+    insert(&mut schematics, "OwnedSchematics", guid_array("OwnedSchematics", &UNFORGED_SCHEMATICS));
+    insert(
+        &mut schematics,
+        "ForgedSchematics",
+        guid_array("ForgedSchematics", &[FORGED_SCHEMATIC]),
+    );
+
+    let mut campaigns = Properties::default();
+    insert(
+        &mut campaigns,
+        "CompletedCampaigns",
+        guid_array("CompletedCampaigns", &[COMPLETED_ASSIGNMENT]),
+    );
+    insert(&mut campaigns, "WeeklySave", weekly_campaign(1_630_837_160, -1_030_983_351));
+    insert(
+        &mut campaigns,
+        "MaxtrixCoreHuntSave",
+        weekly_campaign(1_617_748_626, CORE_HUNT_STARTED_WEEK),
+    );
+
+    let mut deep_dives = Properties::default();
+    insert(&mut deep_dives, "NormalSave", deep_dive(true));
+    insert(&mut deep_dives, "EliteSave", deep_dive(false));
+
+    let mut milestones = Properties::default();
+    let mut milestone = Properties::default();
+    insert(&mut milestone, "KPIGuid", guid(MILESTONE));
+    insert(&mut milestone, "Tier", Property::Int { id: None, value: 4 });
+    insert(
+        &mut milestones,
+        "ClaimedKPIRewards",
+        struct_array("ClaimedKPIRewards", "MilestoneCounter", vec![StructValue::Struct(milestone)]),
+    );
+
+    let statistics = [(DRILLER, 10.0), (GUNNER, 5.0)]
+        .into_iter()
+        .map(|(class, value)| {
+            let mut counter = Properties::default();
+            insert(&mut counter, "PlayerClassID", guid(class));
+            insert(&mut counter, "MissionStatID", guid(STATISTIC));
+            insert(&mut counter, "Value", Property::Float { id: None, value });
+            StructValue::Struct(counter)
+        })
+        .collect();
+    let mut mission_stats = Properties::default();
+    insert(
+        &mut mission_stats,
+        "Counters",
+        struct_array("Counters", "MissionStatCounter", statistics),
+    );
+
+    let rewards_claimed = [true, false]
+        .into_iter()
+        .map(|claimed| {
+            let mut level = Properties::default();
+            insert(&mut level, "NormalClaimed", Property::Bool { id: None, value: claimed });
+            insert(&mut level, "SpecialClaimed", Property::Bool { id: None, value: false });
+            StructValue::Struct(level)
+        })
+        .collect();
+    let mut season = Properties::default();
+    insert(&mut season, "XP", Property::Int { id: None, value: 5_000 });
+    insert(&mut season, "Tokens", Property::Int { id: None, value: 3 });
+    insert(
+        &mut season,
+        "RewardsClaimed",
+        struct_array("RewardsClaimed", "RewardsClaimed", rewards_claimed),
+    );
+    insert(&mut season, "HasClaimedAllRewards", Property::Bool { id: None, value: false });
+    let active_challenges = [(SEASON_CHALLENGES[0], 1, 3), (SEASON_CHALLENGES[1], 2, 2)]
+        .into_iter()
+        .map(|(challenge_guid, current_wins, number_of_wins)| {
+            let mut challenge = Properties::default();
+            insert(&mut challenge, "ChallengeGuid", guid(challenge_guid));
+            insert(&mut challenge, "CurrentWins", Property::Int { id: None, value: current_wins });
+            insert(
+                &mut challenge,
+                "NumberOfWins",
+                Property::Int { id: None, value: number_of_wins },
+            );
+            StructValue::Struct(challenge)
+        })
+        .collect();
+    insert(
+        &mut season,
+        "ActiveChallenges",
+        struct_array("ActiveChallenges", "ChallengeSaveInfo", active_challenges),
+    );
+    let mut seasons = Properties::default();
+    insert(
+        &mut seasons,
+        "Seasons",
+        Property::Map {
+            key_type: PropertyType::StructProperty,
+            value_type: PropertyType::StructProperty,
+            id: None,
+            value: vec![MapEntry {
+                key: PropertyValue::Struct(StructValue::Guid(SEASON)),
+                value: PropertyValue::Struct(StructValue::Struct(season)),
+            }],
+        },
+    );
+
+    let mut drinks = Properties::default();
+    insert(&mut drinks, "UnlockedDrinks", guid_set(&DRINKS[..1]));
+
+    let mut props = Properties::default();
+    insert(
+        &mut props,
+        "CharacterSaves",
+        struct_array("CharacterSaves", "CharacterSave", class_saves),
+    );
+    insert(&mut props, "Credits", Property::Int { id: None, value: 123_456 });
+    insert(&mut props, "Resources", structure("ResourcesSave", resources));
+    insert(&mut props, "SchematicSave", structure("SchematicSave", schematics));
+    insert(&mut props, "CampaignSave", structure("CampaignSave", campaigns));
+    insert(&mut props, "DeepDiveSave", structure("DeepDiveSave", deep_dives));
+    insert(&mut props, "Milestones", structure("MilestoneSave", milestones));
+    insert(&mut props, "MissionStatsSave", structure("MissionStatSave", mission_stats));
+    insert(&mut props, "SeasonSave", structure("SeasonSave", seasons));
+    insert(&mut props, "Drinks", structure("DrinkSave", drinks));
+    insert(&mut props, "UnlockedPickaxeParts", guid_set(&PICKAXE_PARTS[..1]));
+    insert(
+        &mut props,
+        "PurchasedItemUpgrades",
+        guid_array("PurchasedItemUpgrades", &WEAPON_MODS[..1]),
+    );
+
+    Save {
+        header: header(),
+        root: Root { save_game_type: "/Script/FSD.FSDSaveGame".to_string(), properties: props },
+        extra: vec![],
+    }
+}
+
+/// A GUID database naming the made-up GUIDs of [`synthetic_save`] (`Test Drink 1`, ...), to use
+/// with [`with_guid_db`].
+pub fn fixture_guid_db() -> GuidDb {
+    let mut entries = vec![
+        entry(RESOURCE_A, GuidKind::Resource, "Test Resource A"),
+        entry(RESOURCE_B, GuidKind::Resource, "Test Resource B"),
+        entry(ERROR_CUBE, GuidKind::Resource, "Error Cube"),
+        entry(FORGED_SCHEMATIC, GuidKind::Schematic, "Test Overclock 3"),
+    ];
+    for (i, guid) in UNFORGED_SCHEMATICS.into_iter().enumerate() {
+        entries.push(entry(guid, GuidKind::Schematic, &format!("Test Overclock {}", i + 1)));
+    }
+    for (i, guid) in DRINKS.into_iter().enumerate() {
+        entries.push(entry(guid, GuidKind::Drink, &format!("Test Drink {}", i + 1)));
+    }
+    for (i, guid) in PICKAXE_PARTS.into_iter().enumerate() {
+        entries.push(entry(guid, GuidKind::PickaxePart, &format!("Test Pickaxe Part {}", i + 1)));
+    }
+    entries.push(GuidEntry {
+        class: Some("Driller".to_string()),
+        default: true,
+        ..entry(VICTORY_MOVE, GuidKind::VictoryMove, "Test Victory Move")
+    });
+    for (guid, name, class, dlc) in [
+        (BEARDS[0], "Test Beard", Some("Driller"), None),
+        (BEARDS[1], "Test Gunner Beard", Some("Gunner"), None),
+        (BEARDS[2], "Test DLC Beard", None, Some("Test Pack")),
+    ] {
+        entries.push(GuidEntry {
+            class: class.map(str::to_string),
+            dlc: dlc.map(str::to_string),
+            slot: Some("beard".to_string()),
+            default: class.is_some(),
+            ..entry(guid, GuidKind::Cosmetic, name)
+        });
+    }
+    entries.push(entry(SEASON, GuidKind::Season, "Test Season"));
+    for (i, guid) in SEASON_CHALLENGES.into_iter().enumerate() {
+        entries.push(entry(guid, GuidKind::SeasonChallenge, &format!("Test Challenge {}", i + 1)));
+    }
+    entries.push(entry(MILESTONE, GuidKind::Milestone, "Test Milestone"));
+    entries.push(entry(STATISTIC, GuidKind::Statistic, "Test Statistic"));
+    for (guid, class, weapon, tier) in [
+        (WEAPON_MODS[0], "Driller", "Test Flamethrower", 1),
+        (WEAPON_MODS[1], "Driller", "Test Flamethrower", 2),
+        (WEAPON_MODS[2], "Gunner", "Test Minigun", 1),
+    ] {
+        entries.push(GuidEntry {
+            class: Some(class.to_string()),
+            weapon: Some(weapon.to_string()),
+            tier: Some(tier),
+            ..entry(guid, GuidKind::WeaponMod, &format!("{weapon} Tier {tier}"))
+        });
+    }
+    GuidDb { entries }
+}
+
+fn entry(guid: Uuid, kind: GuidKind, name: &str) -> GuidEntry {
+    GuidEntry {
+        guid,
+        kind,
+        name: name.to_string(),
+        dlc: None,
+        weapon: None,
+        class: None,
+        tier: None,
+        slot: None,
+        default: false,
+    }
+}
+
+/// The header of a save from the newest of the [`KNOWN_VERSIONS`], without custom versions.
+fn header() -> Header {
+    let known = KNOWN_VERSIONS.last().expect("there are known versions");
+    Header {
+        magic: u32::from_le_bytes(*b"GVAS"),
+        save_game_version: 2,
+        // `uesave` keeps the fields of `PackageVersion` private and only exposes them through
+        // serde.
+        package_version: serde_json::from_value(serde_json::json!({ "ue4": 522, "ue5": null }))
+            .expect("package version is valid"),
+        engine_version_major: 4,
+        engine_version_minor: 27,
+        engine_version_patch: 2,
+        engine_version_build: 1 << 31 | known.changelist,
+        engine_version: "++UE4+Release-4.27".to_string(),
+        custom_format_version: 3,
+        custom_format: vec![],
+    }
+}
+
+/// [`synthetic_save`] grown to the size of a save late in the game, a few hundred KB, for
+/// benchmarks: `Padding` holds `ItemSaves`, 500 structs of 10 ints each, and `UnlockedItems`, 4000
+/// GUIDs.
+pub fn large_synthetic_save() -> Save {
+    let mut save = synthetic_save();
+
+    let item_saves = (0..500)
+        .map(|i| {
+            let mut props = Properties::default();
+            for j in 0..10 {
+                insert(&mut props, &format!("Value{j}"), Property::Int { id: None, value: i * j });
+            }
+            StructValue::Struct(props)
+        })
+        .collect();
+    let unlocked_items: Vec<_> = (0..4000u128).map(Uuid::from_u128).collect();
+
+    let mut padding = Properties::default();
+    insert(
+        &mut padding,
+        "ItemSaves",
+        Property::Array {
+            array_type: PropertyType::StructProperty,
+            id: None,
+            value: ValueArray::Struct {
+                _type: "ItemSaves".to_string(),
+                name: "StructProperty".to_string(),
+                struct_type: StructType::Struct(Some("ItemSave".to_string())),
+                id: Uuid::nil(),
+                value: item_saves,
+            },
+        },
+    );
+    insert(&mut padding, "UnlockedItems", guid_array("UnlockedItems", &unlocked_items));
+    insert(&mut save.root.properties, "Padding", structure("PaddingSave", padding));
+    save
+}
+
+/// Write [`synthetic_save`] to `save.sav` in `dir`. Returns its path.
+pub fn synthetic_save_file(dir: &Path) -> PathBuf {
+    let path = dir.join("save.sav");
+    let mut buf = vec![];
+    synthetic_save().write(&mut buf).unwrap();
+    std::fs::write(&path, buf).unwrap();
+    path
+}
+
+/// The sample save uesave is tested with, `drg-save-test.sav`, copied to `testdata`.
+pub const SAMPLE_SAVE: &[u8] = include_bytes!("../testdata/drg-save-test.sav");
+
+/// [`SAMPLE_SAVE`] parsed.
+pub fn sample_save() -> Save {
+    crate::parse_save(SAMPLE_SAVE, Path::new("drg-save-test.sav")).unwrap()
+}
+
+/// [`synthetic_save`] serialized with an `IntProperty` called `Odd` of 8 bytes as its last
+/// property, which `uesave` reads 4 bytes of and then fails on. Returns the save and the bytes of
+/// `Odd`.
+pub fn synthetic_save_with_unparsable_property() -> (Vec<u8>, Vec<u8>) {
+    fn string(s: &str) -> Vec<u8> {
+        [&(s.len() as u32 + 1).to_le_bytes()[..], s.as_bytes(), b"\0"].concat()
+    }
+    let odd = [
+        string("Odd"),
+        string("IntProperty"),
+        8u32.to_le_bytes().to_vec(),
+        0u32.to_le_bytes().to_vec(),
+        vec![0],
+        // Read back as a negative string length, so `uesave` fails fast.
+        vec![1, 2, 3, 4, 1, 0, 0, 0xf0],
+    ]
+    .concat();
+    let mut buf = vec![];
+    synthetic_save().write(&mut buf).unwrap();
+    // Before the `None` ending the properties, the save has no trailing bytes.
+    let at = buf.len() - string("None").len();
+    buf.splice(at..at, odd.iter().copied());
+    (buf, odd)
+}
+
+/// Run `f` with `db` merged into what [`GuidDb::load`] returns on this thread, e.g. for a dataset
+/// naming the made-up GUIDs of [`synthetic_save`].
+pub fn with_guid_db<T>(db: GuidDb, f: impl FnOnce() -> T) -> T {
+    let previous = TEST_ENTRIES.with(|entries| entries.replace(Some(db)));
+    let result = f();
+    TEST_ENTRIES.with(|entries| entries.replace(previous));
+    result
+}
+
+/// Edits of [`synthetic_save`] covering every editor, as `(editor, args)`, for robustness tests and
+/// `fuzz/`. Names of GUIDs resolve with [`fixture_guid_db`].
+pub const EDITS: &[(&str, &[&str])] = &[
+    ("blue-number", &[]),
+    ("blue-number", &["--keep-class", "Gunner", "--promos", "Scout=3"]),
+    ("cosmetics", &["--class", "driller", "--slot", "beard", "--item", "test dlc beard"]),
+    ("cosmetics", &["--strip-dlc"]),
+    ("credits", &["--add", "1000"]),
+    ("credits", &["--add", "-200000"]),
+    ("events", &["--reset"]),
+    ("events", &["--complete", "--count", "3"]),
+    ("forge", &["--all"]),
+    ("forge", &["--filter", "overclock 2"]),
+    ("identity", &["--title", "3", "--name", "Driller=Bob"]),
+    ("milestones", &["--milestone", "test milestone=5", "--stat", "Test Statistic=2"]),
+    ("milestones", &["--reset-stats"]),
+    (
+        "missions",
+        &["--reset-deep-dives", "--reset-assignment", "00000000-0000-0000-0000-000000000501"],
+    ),
+    ("missions", &["--complete-deep-dive", "elite"]),
+    ("pools", &[]),
+    ("resources", &["--set", "00000000-0000-0000-0000-00000000000c=5"]),
+    ("resources", &["--set", "error cube=3"]),
+    ("rewards", &["--unclaim", "SeasonSave.Seasons[*].RewardsClaimed[*].NormalClaimed"]),
+    ("unlock", &["--kind", "drinks", "--name", "test drink 2"]),
+    ("unlock", &["--kind", "pickaxe-parts", "--all"]),
+    ("weapon-mods", &["--class", "driller"]),
+    ("weapon-mods", &["--all", "--max-tier", "1"]),
+    ("xp", &["--class", "Driller", "--level", "5"]),
+];
+
+/// A `CharacterSave` struct as found in `CharacterSaves`.
+pub fn class_save(
+    savegame_id: Uuid,
+    times_retired: i32,
+    retired_levels: i32,
+    xp: i32,
+) -> StructValue {
+    let mut props = Properties::default();
+    insert(
+        &mut props,
+        "SavegameID",
+        Property::Struct {
+            id: None,
+            value: StructValue::Guid(savegame_id),
+            struct_type: StructType::Guid,
+            struct_id: Uuid::nil(),
+        },
+    );
+    insert(&mut props, "TimesRetired", Property::Int { id: None, value: times_retired });
+    insert(&mut props, "RetiredCharacterLevels", Property::Int { id: None, value: retired_levels });
+    insert(&mut props, "XP", Property::Int { id: None, value: xp });
+    StructValue::Struct(props)
+}
+
+/// `class_save` with the cosmetics of an active class: [`VICTORY_MOVE`] and two vanity loadouts,
+/// the first selected, with `beards` and nothing in the other slots.
+fn with_cosmetics(mut class_save: StructValue, beards: [Uuid; 2]) -> StructValue {
+    let StructValue::Struct(props) = &mut class_save else {
+        unreachable!("`class_save` returns a struct");
+    };
+    let loadouts = beards
+        .into_iter()
+        .map(|beard| {
+            let mut loadout = Properties::default();
+            for field in [
+                "EquippedHeadItemID",
+                "EquippedBeardItemID",
+                "EquippedArmorItemID",
+                "EquippedArmorMaterialID",
+                "EquippedBeardColorItemID",
+                "EquippedSkinColorItemID",
+                "EquippedEyebrowsItemID",
+                "EquippedMoustacheItemID",
+                "EquippedSideburnsItemID",
+            ] {
+                let item = if field == "EquippedBeardItemID" { beard } else { Uuid::nil() };
+                insert(&mut loadout, field, guid(item));
+            }
+            StructValue::Struct(loadout)
+        })
+        .collect();
+    let mut vanity = Properties::default();
+    insert(&mut vanity, "Loadouts", struct_array("Loadouts", "CharacterVanityLoadout", loadouts));
+    insert(props, "Vanity", structure("CharacterVanitySave", vanity));
+    insert(props, "SelectedLoadout", Property::Int { id: None, value: 0 });
+    let mut victory_pose = Properties::default();
+    insert(&mut victory_pose, "EquippedVictoryPose", guid(VICTORY_MOVE));
+    insert(props, "VictoryPose", structure("VictoryPoseSave", victory_pose));
+    class_save
+}
+
+/// A `WeeklyCampaignItem` struct as found in `CampaignSave`.
+fn weekly_campaign(last_completed_week: i32, last_started_week: i32) -> Property {
+    let mut props = Properties::default();
+    insert(&mut props, "LastCompletedWeek", Property::Int { id: None, value: last_completed_week });
+    insert(&mut props, "LastStartedWeek", Property::Int { id: None, value: last_started_week });
+    structure("WeeklyCampaignItem", props)
+}
+
+/// A `DeepDiveItem` struct as found in `DeepDiveSave`, with the seed of the sample save.
+fn deep_dive(completed: bool) -> Property {
+    let mut props = Properties::default();
+    insert(&mut props, "Seed", Property::Int { id: None, value: 1_027_742_581 });
+    insert(&mut props, "HasEverCompleted", Property::Bool { id: None, value: completed });
+    structure("DeepDiveItem", props)
+}
+
+fn insert(props: &mut Properties, name: &str, prop: Property) {
+    props.0.insert(PropertyKey::from(name), prop);
+}
+
+fn structure(struct_type: &str, props: Properties) -> Property {
+    Property::Struct {
+        id: None,
+        value: StructValue::Struct(props),
+        struct_type: StructType::Struct(Some(struct_type.to_string())),
+        struct_id: Uuid::nil(),
+    }
+}
+
+fn guid(guid: Uuid) -> Property {
+    Property::Struct {
+        id: None,
+        value: StructValue::Guid(guid),
+        struct_type: StructType::Guid,
+        struct_id: Uuid::nil(),
+    }
+}
+
+fn struct_array(name: &str, struct_type: &str, value: Vec<StructValue>) -> Property {
+    Property::Array {
+        array_type: PropertyType::StructProperty,
+        id: None,
+        value: ValueArray::Struct {
+            _type: name.to_string(),
+            name: "StructProperty".to_string(),
+            struct_type: StructType::Struct(Some(struct_type.to_string())),
+            id: Uuid::nil(),
+            value,
+        },
+    }
+}
+
+fn guid_array(name: &str, guids: &[Uuid]) -> Property {
+    Property::Array {
+        array_type: PropertyType::StructProperty,
+        id: None,
+        value: ValueArray::Struct {
+            _type: name.to_string(),
+            name: "StructProperty".to_string(),
+            struct_type: StructType::Guid,
+            id: Uuid::nil(),
+            value: guids.iter().copied().map(StructValue::Guid).collect(),
+        },
+    }
+}
+
+fn guid_set(guids: &[Uuid]) -> Property {
+    Property::Set {
+        id: None,
+        set_type: PropertyType::StructProperty,
+        value: ValueSet::Struct(guids.iter().copied().map(StructValue::Guid).collect()),
+    }
+}
+
+fn resource(guid: Uuid, amount: f32) -> MapEntry {
+    MapEntry {
+        key: PropertyValue::Struct(StructValue::Guid(guid)),
+        value: PropertyValue::Float(amount),
+    }
+}
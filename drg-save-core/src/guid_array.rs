@@ -0,0 +1,93 @@
+//! Access to arrays and sets of GUIDs, which the save uses for everything owned or unlocked
+//! (schematics as arrays, drink recipes and pickaxe parts as sets, ...).
+
+use uesave::{Properties, Property, StructType, StructValue, ValueArray, ValueSet};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::walk::{property_at, property_at_mut, walk_properties};
+
+/// Path of the first property called `name` below `props`, e.g. `SchematicSave.OwnedSchematics`
+/// for `OwnedSchematics`. A full path is found as is.
+pub fn find_path(props: &Properties, name: &str) -> Option<String> {
+    let mut found = None;
+    walk_properties(props, &mut |path, _| {
+        if found.is_none() && (path == name || path.ends_with(&format!(".{name}"))) {
+            found = Some(path.to_string());
+        }
+    });
+    found
+}
+
+const EXPECTED: &str = "Guid Array or Set";
+
+fn is_guid_set(value: &[StructValue]) -> bool {
+    value.iter().all(|value| matches!(value, StructValue::Guid(_)))
+}
+
+/// The GUIDs in the GUID array or set called `name` below `props`.
+pub fn guids(props: &Properties, name: &str) -> Result<Vec<Uuid>> {
+    let path = find_path(props, name).ok_or_else(|| DrgSaveError::missing(name))?;
+    let array = match property_at(props, &path) {
+        Some(Property::Array {
+            value: ValueArray::Struct { struct_type: StructType::Guid, value, .. },
+            ..
+        }) => value,
+        Some(Property::Set { value: ValueSet::Struct(value), .. }) if is_guid_set(value) => value,
+        Some(prop) => return Err(DrgSaveError::type_mismatch(path, EXPECTED, prop)),
+        None => return Err(DrgSaveError::missing(path)),
+    };
+    Ok(array
+        .iter()
+        .filter_map(|value| match value {
+            StructValue::Guid(guid) => Some(*guid),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Add `guid` to the GUID array or set called `name` below `props` unless it is already in there.
+/// Returns whether it was added.
+pub fn insert_guid(props: &mut Properties, name: &str, guid: Uuid) -> Result<bool> {
+    let array = guid_array_mut(props, name)?;
+    if array.iter().any(|value| matches!(value, StructValue::Guid(existing) if *existing == guid)) {
+        return Ok(false);
+    }
+    array.push(StructValue::Guid(guid));
+    Ok(true)
+}
+
+/// Remove `guid` from the GUID array or set called `name` below `props`. Returns whether it was
+/// in there.
+pub fn remove_guid(props: &mut Properties, name: &str, guid: Uuid) -> Result<bool> {
+    let array = guid_array_mut(props, name)?;
+    let len = array.len();
+    array.retain(|value| !matches!(value, StructValue::Guid(existing) if *existing == guid));
+    Ok(array.len() != len)
+}
+
+pub(crate) fn guid_array_mut<'a>(
+    props: &'a mut Properties,
+    name: &str,
+) -> Result<&'a mut Vec<StructValue>> {
+    let path = find_path(props, name).ok_or_else(|| DrgSaveError::missing(name))?;
+    match property_at_mut(props, &path) {
+        Some(Property::Array {
+            value: ValueArray::Struct { struct_type: StructType::Guid, value, .. },
+            ..
+        }) => Ok(value),
+        Some(Property::Set { value: ValueSet::Struct(value), .. }) => {
+            if is_guid_set(value) {
+                Ok(value)
+            } else {
+                Err(DrgSaveError::TypeMismatch {
+                    path,
+                    expected: EXPECTED.to_string(),
+                    found: "Set".to_string(),
+                })
+            }
+        }
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, EXPECTED, prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
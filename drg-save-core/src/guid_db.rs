@@ -0,0 +1,243 @@
+//! Mapping of known DRG GUIDs (classes, resources, schematics, seasons) to human-readable names.
+//!
+//! A small dataset is embedded in the binary. A larger community-maintained dataset in the same
+//! JSON format can be installed into [`GuidDb::user_path`] (see `drg-save db update`, which fetches
+//! the latest version of the embedded dataset from [`DEFAULT_URL`] unless given another URL), and
+//! its entries take precedence over the embedded ones.
+//!
+//! The embedded dataset only names the classes, whose GUIDs are taken from the sample save uesave
+//! is tested with (`drg-save-test.sav`). Everything else has to come from an installed dataset, so
+//! features looking up other kinds by name [require](GuidDb::require) them first and fail with a
+//! clear error otherwise.
+
+#[cfg(any(test, feature = "fixtures"))]
+use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::DrgSaveError;
+
+const EMBEDDED: &str = include_str!("../data/guids.json");
+
+/// Where `drg-save db update` fetches the dataset from by default: the embedded one as it is on the
+/// main branch, so entries added after a release reach existing installs.
+pub const DEFAULT_URL: &str =
+    "https://raw.githubusercontent.com/jieyouxu/uesave-drg-playground/main/drg-save-core/data/guids.json";
+
+/// [`EMBEDDED`] parsed, once. `embedded_database_is_valid` checks that this can't fail.
+static EMBEDDED_DB: LazyLock<GuidDb> =
+    LazyLock::new(|| GuidDb::from_json(EMBEDDED).expect("embedded GUID database is valid"));
+
+/// The embedded dataset merged with the installed one, and when the installed one was modified.
+/// Batches and long-running services load the database for every save, and only re-read the
+/// installed dataset once it changes.
+static LOADED: Mutex<Option<(SystemTime, GuidDb)>> = Mutex::new(None);
+
+#[cfg(any(test, feature = "fixtures"))]
+thread_local! {
+    /// Entries [`GuidDb::load`] adds on this thread, see [`crate::fixtures::with_guid_db`].
+    pub(crate) static TEST_ENTRIES: RefCell<Option<GuidDb>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuidKind {
+    Class,
+    Resource,
+    Schematic,
+    Season,
+    SeasonChallenge,
+    Drink,
+    PickaxePart,
+    VictoryMove,
+    /// Helmets, beards and the other vanity items, armor and paintjobs.
+    Cosmetic,
+    /// A modification of a weapon, bought at the equipment terminal.
+    WeaponMod,
+    Milestone,
+    Statistic,
+    /// An event started by a machine found in caves, e.g. the Omen tower or a data deposit.
+    MachineEvent,
+    /// Anything newer versions of the dataset know about which this version doesn't.
+    #[serde(other)]
+    Other,
+}
+
+impl fmt::Display for GuidKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Class => "class",
+            Self::Resource => "resource",
+            Self::Schematic => "schematic",
+            Self::Season => "season",
+            Self::SeasonChallenge => "season_challenge",
+            Self::Drink => "drink",
+            Self::PickaxePart => "pickaxe_part",
+            Self::VictoryMove => "victory_move",
+            Self::Cosmetic => "cosmetic",
+            Self::WeaponMod => "weapon_mod",
+            Self::Milestone => "milestone",
+            Self::Statistic => "statistic",
+            Self::MachineEvent => "machine_event",
+            Self::Other => "other",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuidEntry {
+    pub guid: Uuid,
+    pub kind: GuidKind,
+    pub name: String,
+    /// The DLC the item comes with, e.g. `Dark Future Pack`, if it needs one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dlc: Option<String>,
+    /// For weapon mods: the weapon they modify.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weapon: Option<String>,
+    /// For weapon mods: the class using the weapon. For cosmetics and victory moves: the class
+    /// wearing them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    /// For weapon mods: the modification tier, starting at 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<u32>,
+    /// For cosmetics: the slot they are equipped in, as in `edit cosmetics --slot`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<String>,
+    /// For cosmetics and victory moves: whether the class has it equipped from the start.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuidDb {
+    pub entries: Vec<GuidEntry>,
+}
+
+impl GuidDb {
+    /// The dataset embedded in the binary.
+    pub fn embedded() -> Self {
+        EMBEDDED_DB.clone()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("failed to parse GUID database")
+    }
+
+    /// Where `drg-save db update` installs the community dataset.
+    pub fn user_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("drg-save").join("guids.json"))
+    }
+
+    /// The embedded dataset merged with the installed community dataset, if any.
+    pub fn load() -> Result<Self> {
+        let db = match Self::user_path().filter(|path| path.exists()) {
+            Some(path) => {
+                let modified = fs::metadata(&path)?.modified()?;
+                let mut loaded = LOADED.lock().unwrap_or_else(PoisonError::into_inner);
+                match &*loaded {
+                    Some((at, db)) if *at == modified => db.clone(),
+                    _ => {
+                        let mut db = Self::embedded();
+                        db.merge(Self::from_json(&fs::read_to_string(&path)?)?);
+                        *loaded = Some((modified, db.clone()));
+                        db
+                    }
+                }
+            }
+            None => Self::embedded(),
+        };
+        #[cfg(any(test, feature = "fixtures"))]
+        let db = {
+            let mut db = db;
+            if let Some(test_db) = TEST_ENTRIES.with(|entries| entries.borrow().clone()) {
+                db.merge(test_db);
+            }
+            db
+        };
+        Ok(db)
+    }
+
+    /// Add the entries of `other`, replacing entries for the same GUID.
+    pub fn merge(&mut self, other: GuidDb) {
+        for entry in other.entries {
+            match self.entries.iter_mut().find(|existing| existing.guid == entry.guid) {
+                Some(existing) => *existing = entry,
+                None => self.entries.push(entry),
+            }
+        }
+    }
+
+    pub fn get(&self, guid: Uuid) -> Option<&GuidEntry> {
+        self.entries.iter().find(|entry| entry.guid == guid)
+    }
+
+    pub fn name(&self, guid: Uuid) -> Option<&str> {
+        self.get(guid).map(|entry| entry.name.as_str())
+    }
+
+    /// The DLC needed for `guid`, if it is known to need one.
+    pub fn dlc(&self, guid: Uuid) -> Option<&str> {
+        self.get(guid).and_then(|entry| entry.dlc.as_deref())
+    }
+
+    /// Find the GUID of the `kind` entry called `name`, ignoring case.
+    pub fn find(&self, kind: GuidKind, name: &str) -> Option<Uuid> {
+        self.entries
+            .iter()
+            .find(|entry| entry.kind == kind && entry.name.eq_ignore_ascii_case(name))
+            .map(|entry| entry.guid)
+    }
+
+    /// Whether there are any `kind` entries.
+    pub fn has(&self, kind: GuidKind) -> bool {
+        self.entries.iter().any(|entry| entry.kind == kind)
+    }
+
+    /// Fail with [`DrgSaveError::MissingGuidData`] unless there are `kind` entries, for features
+    /// that can't do anything without them.
+    pub fn require(&self, kind: GuidKind) -> crate::error::Result<()> {
+        if self.has(kind) {
+            Ok(())
+        } else {
+            Err(DrgSaveError::MissingGuidData { kind })
+        }
+    }
+
+    /// Human-readable label for `guid`: its name if known, the GUID itself otherwise.
+    pub fn label(&self, guid: Uuid) -> String {
+        self.name(guid).map_or_else(|| guid.to_string(), str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::classes::HIDDEN_CLASS_SAVEGAME_ID;
+
+    #[test]
+    fn embedded_database_is_valid() {
+        let db = GuidDb::from_json(EMBEDDED).unwrap();
+        let guids: HashSet<_> = db.entries.iter().map(|entry| entry.guid).collect();
+        assert_eq!(guids.len(), db.entries.len(), "duplicate GUIDs");
+        assert_eq!(db.name(HIDDEN_CLASS_SAVEGAME_ID), Some("Hidden"));
+    }
+
+    #[test]
+    fn require_names_the_missing_kind() {
+        let db = GuidDb::embedded();
+        assert!(db.require(GuidKind::Class).is_ok());
+        let error = db.require(GuidKind::WeaponMod).unwrap_err();
+        assert!(error.to_string().contains("weapon_mod"), "{error}");
+    }
+}
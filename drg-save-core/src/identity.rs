@@ -0,0 +1,204 @@
+//! The player rank title and the names of the classes.
+//!
+//! Neither has to be stored: the title shown next to the player rank follows from the blue level,
+//! and a class is named after its `SavegameID` (the sample save uesave is tested with,
+//! `drg-save-test.sav`, stores neither). Fields a save does store are found by name (an int with
+//! `Title` in its name anywhere, a string called one of [`CLASS_NAME_FIELDS`] directly in a class
+//! save) and can be changed; derived values can only be reported.
+
+use serde::Serialize;
+use uesave::{Properties, Property, Save, StructValue};
+
+use crate::blue_level::blue_level;
+use crate::classes::{class_saves, class_saves_mut, savegame_id};
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::GuidDb;
+use crate::walk::{walk_properties, walk_properties_mut};
+
+/// Where a value shown in game comes from.
+#[derive(Debug, Serialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum Source {
+    /// Stored in the property at `path`, so it can be changed.
+    Stored { path: String },
+    /// Not stored, but derived from `from` whenever the game shows it.
+    Derived { from: &'static str },
+}
+
+#[derive(Debug, Serialize)]
+pub struct RankTitle {
+    /// The stored title index, or the blue level the title is derived from.
+    pub value: i32,
+    #[serde(flatten)]
+    pub source: Source,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassName {
+    pub slot: usize,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub source: Source,
+}
+
+fn is_title_field(name: &str) -> bool {
+    name.to_ascii_lowercase().contains("title")
+}
+
+/// Names of the class save properties that hold the name of the class.
+pub const CLASS_NAME_FIELDS: [&str; 2] = ["Name", "CharacterName"];
+
+fn name(path: &str) -> &str {
+    path.rsplit('.').next().unwrap_or(path)
+}
+
+/// The stored rank title fields, or the blue level the title is derived from if there are none.
+pub fn rank_titles(save: &Save) -> Result<Vec<RankTitle>> {
+    let mut titles = vec![];
+    walk_properties(&save.root.properties, &mut |path, prop| {
+        if let Property::Int { value, .. } = prop {
+            if is_title_field(name(path)) {
+                titles.push(RankTitle {
+                    value: *value,
+                    source: Source::Stored { path: path.to_string() },
+                });
+            }
+        }
+    });
+    if titles.is_empty() {
+        titles.push(RankTitle {
+            value: blue_level(class_saves(save)?)?,
+            source: Source::Derived { from: "blue level" },
+        });
+    }
+    Ok(titles)
+}
+
+/// The name of each class: the stored name fields of its class save, or the name of its
+/// `SavegameID` in `db` if there are none.
+pub fn class_names(save: &Save, db: &GuidDb) -> Result<Vec<ClassName>> {
+    let mut names = vec![];
+    for (slot, class_save) in class_saves(save)?.iter().enumerate() {
+        let StructValue::Struct(props) = class_save else {
+            return Err(DrgSaveError::unexpected_class_save_kind());
+        };
+        let n_stored = names.len();
+        for (key, prop) in &props.0 {
+            if let Property::Str { value, .. } | Property::Name { value, .. } = prop {
+                if CLASS_NAME_FIELDS.contains(&key.1.as_str()) {
+                    names.push(ClassName {
+                        slot,
+                        name: Some(value.clone()),
+                        source: Source::Stored {
+                            path: format!("CharacterSaves[{slot}].{}", key.1),
+                        },
+                    });
+                }
+            }
+        }
+        if names.len() == n_stored {
+            names.push(ClassName {
+                slot,
+                name: savegame_id(class_save).and_then(|id| db.name(id)).map(str::to_string),
+                source: Source::Derived { from: "SavegameID" },
+            });
+        }
+    }
+    Ok(names)
+}
+
+/// Set every stored rank title field to `title`. Returns the paths of the fields that changed.
+pub fn set_rank_title(props: &mut Properties, title: i32) -> Result<Vec<String>> {
+    let mut n_stored = 0;
+    let mut changed = vec![];
+    walk_properties_mut(props, &mut |path, prop| {
+        if let Property::Int { value, .. } = prop {
+            if is_title_field(name(path)) {
+                n_stored += 1;
+                if *value != title {
+                    *value = title;
+                    changed.push(path.to_string());
+                }
+            }
+        }
+    });
+    if n_stored == 0 {
+        return Err(DrgSaveError::InvalidInput(
+            "this save doesn't store a rank title, it follows from the blue level (see `edit \
+             blue-number`)"
+                .to_string(),
+        ));
+    }
+    Ok(changed)
+}
+
+/// Set the stored name fields of class save `slot` to `new_name`. Returns the paths of the fields
+/// that changed.
+pub fn set_class_name(save: &mut Save, slot: usize, new_name: &str) -> Result<Vec<String>> {
+    let Some(class_save) = class_saves_mut(save)?.get_mut(slot) else {
+        return Err(DrgSaveError::InvalidInput(format!("class save slot {slot} does not exist")));
+    };
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let mut n_stored = 0;
+    let mut changed = vec![];
+    for (key, prop) in props.0.iter_mut() {
+        if let Property::Str { value, .. } | Property::Name { value, .. } = prop {
+            if CLASS_NAME_FIELDS.contains(&key.1.as_str()) {
+                n_stored += 1;
+                if *value != new_name {
+                    *value = new_name.to_string();
+                    changed.push(format!("CharacterSaves[{slot}].{}", key.1));
+                }
+            }
+        }
+    }
+    if n_stored == 0 {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "class save slot {slot} doesn't store a name, classes are named after their \
+             `SavegameID`"
+        )));
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::PropertyKey;
+
+    use super::*;
+    use crate::classes::class_saves_mut;
+    use crate::fixtures::synthetic_save;
+
+    fn insert_str(class_save: &mut StructValue, name: &str, value: &str) {
+        let StructValue::Struct(props) = class_save else {
+            panic!("class saves are structs");
+        };
+        props
+            .0
+            .insert(PropertyKey::from(name), Property::Str { id: None, value: value.to_string() });
+    }
+
+    #[test]
+    fn only_exact_name_fields_are_class_names() {
+        let mut save = synthetic_save();
+        let class_saves = class_saves_mut(&mut save).unwrap();
+        insert_str(&mut class_saves[0], "CharacterName", "Karl");
+        insert_str(&mut class_saves[1], "TutorialName", "Tutorial_Hint_Deposite");
+
+        let names = class_names(&save, &GuidDb::embedded()).unwrap();
+        assert_eq!(names[0].name.as_deref(), Some("Karl"));
+        assert!(
+            matches!(&names[0].source, Source::Stored { path } if path == "CharacterSaves[0].CharacterName")
+        );
+        assert_eq!(names[1].name.as_deref(), Some("Engineer"));
+        assert!(matches!(names[1].source, Source::Derived { .. }));
+
+        assert_eq!(
+            set_class_name(&mut save, 0, "Mike").unwrap(),
+            ["CharacterSaves[0].CharacterName"]
+        );
+        assert!(set_class_name(&mut save, 1, "Mike").is_err());
+    }
+}
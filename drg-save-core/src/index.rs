@@ -0,0 +1,279 @@
+//! An index of property paths (as produced by [`crate::walk::walk_properties`]) to the positions
+//! of the properties in the tree, so repeated lookups by path don't walk the whole save each time.
+//!
+//! The index is built on the first lookup. It stays valid while properties are only changed in
+//! place; adding or removing properties or array elements moves others around. Every lookup checks
+//! that the property it finds is still at the path looked up, and rebuilds the index if it isn't
+//! or if the path is unknown, so a stale index is only slow, never wrong. Invalidating it after
+//! such changes ([`PathIndex::invalidate`]) saves the failed lookup.
+
+use std::collections::HashMap;
+
+use uesave::{Properties, Property, PropertyValue, StructValue, ValueArray, ValueSet};
+
+use crate::walk::join_path;
+
+/// One step down the tree: the position of a property among its siblings, and for arrays, sets and
+/// maps of structs the element to continue in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Step {
+    position: usize,
+    element: Option<usize>,
+}
+
+/// Where a property is in the tree, from the root properties down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeHandle(Vec<Step>);
+
+#[derive(Debug, Default)]
+pub struct PathIndex {
+    handles: Option<HashMap<String, NodeHandle>>,
+}
+
+impl PathIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the index, so it is rebuilt on the next lookup.
+    pub fn invalidate(&mut self) {
+        self.handles = None;
+    }
+
+    /// The handle of the property at `path` in `props`, building the index first if needed.
+    pub fn handle(&mut self, props: &Properties, path: &str) -> Option<&NodeHandle> {
+        self.handles.get_or_insert_with(|| build(props)).get(path)
+    }
+
+    /// Look up the property at `path`, like [`crate::walk::property_at`].
+    pub fn get<'a>(&mut self, props: &'a Properties, path: &str) -> Option<&'a Property> {
+        let handle = self.fresh_handle(props, path)?;
+        resolve(props, &handle).map(|(_, prop)| prop)
+    }
+
+    /// Mutable counterpart of [`PathIndex::get`].
+    pub fn get_mut<'a>(
+        &mut self,
+        props: &'a mut Properties,
+        path: &str,
+    ) -> Option<&'a mut Property> {
+        let handle = self.fresh_handle(props, path)?;
+        resolve_mut(props, &handle)
+    }
+
+    /// The handle of the property at `path` in `props`, rebuilding the index if it doesn't know
+    /// `path` or the handle leads to a property at another path, i.e. the tree changed shape
+    /// without the index being invalidated.
+    fn fresh_handle(&mut self, props: &Properties, path: &str) -> Option<NodeHandle> {
+        let built = self.handles.is_some();
+        let handle = self.handle(props, path).cloned();
+        if handle
+            .as_ref()
+            .is_some_and(|handle| resolve(props, handle).is_some_and(|(at, _)| at == path))
+        {
+            return handle;
+        }
+        if !built {
+            return None;
+        }
+        self.invalidate();
+        self.handle(props, path).cloned()
+    }
+
+    /// Replace the property at `path` with `value`, returning the replaced one. Replacing a struct,
+    /// array, set or map can change what is below it, so that invalidates the index.
+    pub fn replace(
+        &mut self,
+        props: &mut Properties,
+        path: &str,
+        value: Property,
+    ) -> Option<Property> {
+        let prop = self.get_mut(props, path)?;
+        let old = std::mem::replace(prop, value);
+        if has_children(&old) || has_children(prop) {
+            self.invalidate();
+        }
+        Some(old)
+    }
+}
+
+fn has_children(prop: &Property) -> bool {
+    matches!(
+        prop,
+        Property::Struct { value: StructValue::Struct(_), .. }
+            | Property::Array { value: ValueArray::Struct { .. }, .. }
+            | Property::Set { value: ValueSet::Struct(_), .. }
+            | Property::Map { .. }
+    )
+}
+
+fn build(props: &Properties) -> HashMap<String, NodeHandle> {
+    let mut handles = HashMap::new();
+    build_inner("", props, &mut vec![], &mut handles);
+    handles
+}
+
+fn build_inner(
+    prefix: &str,
+    props: &Properties,
+    steps: &mut Vec<Step>,
+    handles: &mut HashMap<String, NodeHandle>,
+) {
+    for (position, (key, prop)) in props.0.iter().enumerate() {
+        let path = join_path(prefix, key);
+        steps.push(Step { position, element: None });
+        // Like `walk_properties`, the first property with a path wins.
+        handles.entry(path.clone()).or_insert_with(|| NodeHandle(steps.clone()));
+        if let Property::Struct { value: StructValue::Struct(props), .. } = prop {
+            build_inner(&path, props, steps, handles);
+        }
+        steps.pop();
+        for (element, props) in children(prop) {
+            steps.push(Step { position, element: Some(element) });
+            build_inner(&format!("{path}[{element}]"), props, steps, handles);
+            steps.pop();
+        }
+    }
+}
+
+/// The structs in the elements of an array, set or map, with their index.
+fn children(prop: &Property) -> Vec<(usize, &Properties)> {
+    let mut children = vec![];
+    match prop {
+        Property::Array { value: ValueArray::Struct { value, .. }, .. }
+        | Property::Set { value: ValueSet::Struct(value), .. } => {
+            for (i, value) in value.iter().enumerate() {
+                if let StructValue::Struct(props) = value {
+                    children.push((i, props));
+                }
+            }
+        }
+        Property::Map { value, .. } => {
+            for (i, entry) in value.iter().enumerate() {
+                if let PropertyValue::Struct(StructValue::Struct(props)) = &entry.value {
+                    children.push((i, props));
+                }
+            }
+        }
+        _ => {}
+    }
+    children
+}
+
+/// The property at `handle` together with its path.
+fn resolve<'a>(props: &'a Properties, handle: &NodeHandle) -> Option<(String, &'a Property)> {
+    let (last, parents) = handle.0.split_last()?;
+    let mut props = props;
+    let mut path = String::new();
+    for step in parents {
+        let (key, prop) = props.0.get_index(step.position)?;
+        path = join_path(&path, key);
+        props = match (prop, step.element) {
+            (Property::Struct { value: StructValue::Struct(props), .. }, None) => props,
+            (Property::Array { value: ValueArray::Struct { value, .. }, .. }, Some(i))
+            | (Property::Set { value: ValueSet::Struct(value), .. }, Some(i)) => {
+                match value.get(i)? {
+                    StructValue::Struct(props) => props,
+                    _ => return None,
+                }
+            }
+            (Property::Map { value, .. }, Some(i)) => match &value.get(i)?.value {
+                PropertyValue::Struct(StructValue::Struct(props)) => props,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if let Some(i) = step.element {
+            path = format!("{path}[{i}]");
+        }
+    }
+    let (key, prop) = props.0.get_index(last.position)?;
+    Some((join_path(&path, key), prop))
+}
+
+/// Mutable counterpart of [`resolve`], without the path.
+fn resolve_mut<'a>(props: &'a mut Properties, handle: &NodeHandle) -> Option<&'a mut Property> {
+    let (last, parents) = handle.0.split_last()?;
+    let mut props = props;
+    for step in parents {
+        let (_, prop) = props.0.get_index_mut(step.position)?;
+        props = match (prop, step.element) {
+            (Property::Struct { value: StructValue::Struct(props), .. }, None) => props,
+            (Property::Array { value: ValueArray::Struct { value, .. }, .. }, Some(i))
+            | (Property::Set { value: ValueSet::Struct(value), .. }, Some(i)) => {
+                match value.get_mut(i)? {
+                    StructValue::Struct(props) => props,
+                    _ => return None,
+                }
+            }
+            (Property::Map { value, .. }, Some(i)) => match &mut value.get_mut(i)?.value {
+                PropertyValue::Struct(StructValue::Struct(props)) => props,
+                _ => return None,
+            },
+            _ => return None,
+        };
+    }
+    props.0.get_index_mut(last.position).map(|(_, prop)| prop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::synthetic_save;
+    use crate::walk::property_at;
+
+    fn xp(prop: Option<&Property>) -> Option<i32> {
+        match prop? {
+            Property::Int { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn finds_what_walking_finds() {
+        let save = synthetic_save();
+        let props = &save.root.properties;
+        let mut index = PathIndex::new();
+        for path in ["Credits", "CharacterSaves[0].XP", "CharacterSaves[2].XP", "SeasonSave.XP"] {
+            assert_eq!(index.get(props, path), property_at(props, path), "{path}");
+        }
+        assert!(index.get(props, "CharacterSaves[9].XP").is_none());
+    }
+
+    #[test]
+    fn never_resolves_a_stale_handle_to_another_property() {
+        let mut save = synthetic_save();
+        let mut index = PathIndex::new();
+        let props = &save.root.properties;
+        index.get(props, "Credits").unwrap();
+        let n_classes = (0..)
+            .take_while(|i| index.get(props, &format!("CharacterSaves[{i}].XP")).is_some())
+            .count();
+        let last = format!("CharacterSaves[{}].XP", n_classes - 1);
+        let second_xp = xp(property_at(&save.root.properties, "CharacterSaves[1].XP"));
+
+        // Remove the first class save without invalidating the index.
+        match crate::walk::property_at_mut(&mut save.root.properties, "CharacterSaves").unwrap() {
+            Property::Array { value: ValueArray::Struct { value, .. }, .. } => {
+                value.remove(0);
+            }
+            _ => panic!("`CharacterSaves` is not an array of structs"),
+        }
+        let props = &mut save.root.properties;
+        assert!(index.get(props, &last).is_none());
+        assert_eq!(xp(index.get(props, "CharacterSaves[0].XP")), second_xp);
+        assert_eq!(xp(index.get_mut(props, "CharacterSaves[0].XP").map(|prop| &*prop)), second_xp);
+    }
+
+    #[test]
+    fn finds_properties_added_without_invalidating() {
+        let mut save = synthetic_save();
+        let mut index = PathIndex::new();
+        index.get(&save.root.properties, "Credits").unwrap();
+        save.root.properties.0.insert(
+            uesave::PropertyKey(0, "Added".to_string()),
+            Property::Int { id: None, value: 7 },
+        );
+        assert_eq!(xp(index.get(&save.root.properties, "Added")), Some(7));
+    }
+}
@@ -0,0 +1,203 @@
+//! Integrity records of backups and snapshots, so bit rot is noticed before a copy is needed.
+//!
+//! Every backup made by [`crate::backup_save_with`] is recorded with its SHA-256 hash, size and
+//! source in `backups.jsonl` in the user's data directory. Snapshots are content-addressed by
+//! their hash already (see [`crate::snapshot`]). [`verify_backups`] and [`verify_snapshots`] check
+//! that the retained copies still match their records and parse.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::snapshot::SnapshotStore;
+use crate::util::unix_timestamp;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub path: PathBuf,
+    /// Hex SHA-256 hash of the backup contents, uncompressed.
+    pub sha256: String,
+    pub size: u64,
+    /// The save file that was backed up.
+    pub source: PathBuf,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Outcome of checking a backup or snapshot.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    /// The copy no longer exists, e.g. because it was pruned.
+    Missing,
+    Corrupt {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct Verification {
+    /// Path of a backup, or hash of a snapshot.
+    pub copy: String,
+    #[serde(flatten)]
+    pub status: Status,
+}
+
+fn manifest_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("drg-save").join("backups.jsonl"))
+}
+
+fn sha256(buf: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(buf))
+}
+
+/// Record the backup at `backup` of the save at `source`, replacing the record of an earlier
+/// backup overwritten in place.
+pub fn record_backup(backup: &Path, source: &Path) -> Result<()> {
+    let manifest = manifest_path().context("no data directory for this platform")?;
+    let buf = crate::io::read_backup(backup)?;
+    let record = BackupRecord {
+        path: backup.canonicalize().unwrap_or_else(|_| backup.to_path_buf()),
+        sha256: sha256(&buf),
+        size: buf.len() as u64,
+        source: source.canonicalize().unwrap_or_else(|_| source.to_path_buf()),
+        timestamp: unix_timestamp(),
+    };
+    let mut records = if manifest.exists() { read_records(&manifest)? } else { vec![] };
+    records.retain(|existing| existing.path != record.path);
+    records.push(record);
+    write_records(&manifest, &records)
+}
+
+/// Drop the records of the backups at `paths`, as recorded, e.g. because they were pruned.
+pub fn forget_backups(paths: &[PathBuf]) -> Result<()> {
+    let Some(manifest) = manifest_path().filter(|path| path.exists()) else {
+        return Ok(());
+    };
+    let mut records = read_records(&manifest)?;
+    records.retain(|record| !paths.contains(&record.path));
+    write_records(&manifest, &records)
+}
+
+/// All recorded backups, oldest first. A backup overwritten in place is only listed with its most
+/// recent record.
+pub fn backup_records() -> Result<Vec<BackupRecord>> {
+    match manifest_path().filter(|path| path.exists()) {
+        Some(manifest) => read_records(&manifest),
+        None => Ok(vec![]),
+    }
+}
+
+fn read_records(manifest: &Path) -> Result<Vec<BackupRecord>> {
+    let mut records: Vec<BackupRecord> = vec![];
+    for line in fs::read_to_string(manifest)?.lines().filter(|line| !line.trim().is_empty()) {
+        let record: BackupRecord = serde_json::from_str(line)
+            .with_context(|| format!("failed to parse `{}`", manifest.display()))?;
+        // Manifests written before records were replaced can list a path several times.
+        records.retain(|existing| existing.path != record.path);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn write_records(manifest: &Path, records: &[BackupRecord]) -> Result<()> {
+    if let Some(dir) = manifest.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut buf = String::new();
+    for record in records {
+        buf.push_str(&serde_json::to_string(record)?);
+        buf.push('\n');
+    }
+    fs::write(manifest, buf)?;
+    Ok(())
+}
+
+/// Check every recorded backup against its record.
+pub fn verify_backups() -> Result<Vec<Verification>> {
+    let mut verifications = vec![];
+    for record in backup_records()? {
+        let status = if !record.path.exists() {
+            Status::Missing
+        } else {
+            let buf = crate::io::read_backup(&record.path)?;
+            check(&buf, Some(&record.sha256), record.size, &record.path)
+        };
+        verifications.push(Verification { copy: record.path.display().to_string(), status });
+    }
+    Ok(verifications)
+}
+
+/// Check every snapshot in `store` against its hash and size.
+pub fn verify_snapshots(store: &SnapshotStore) -> Result<Vec<Verification>> {
+    let mut snapshots = store.log()?;
+    snapshots.sort_by(|a, b| a.hash.cmp(&b.hash));
+    snapshots.dedup_by(|a, b| a.hash == b.hash);
+    let mut verifications = vec![];
+    for snapshot in snapshots {
+        // `read` checks the hash itself, which may be keyed with the passphrase.
+        let status = match store.read(&snapshot.hash) {
+            Ok(buf) => check(&buf, None, snapshot.size, &snapshot.source),
+            Err(e) => Status::Corrupt { reason: format!("{e:#}") },
+        };
+        verifications.push(Verification { copy: snapshot.hash, status });
+    }
+    Ok(verifications)
+}
+
+/// Check `buf` against its recorded hash (if not checked already) and size (0 if unknown), and
+/// that it parses.
+fn check(buf: &[u8], expected_sha256: Option<&str>, expected_size: u64, path: &Path) -> Status {
+    if let Some(expected_sha256) = expected_sha256 {
+        let actual = sha256(buf);
+        if actual != expected_sha256 {
+            return Status::Corrupt { reason: format!("contents hash to `{actual}`") };
+        }
+    }
+    if expected_size != 0 && buf.len() as u64 != expected_size {
+        return Status::Corrupt {
+            reason: format!("size is {} bytes, expected {expected_size}", buf.len()),
+        };
+    }
+    match crate::parse_save(buf, path) {
+        Ok(_) => Status::Ok,
+        Err(e) => Status::Corrupt { reason: format!("{e:#}") },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(path: &str, timestamp: u64) -> BackupRecord {
+        BackupRecord {
+            path: PathBuf::from(path),
+            sha256: String::new(),
+            size: 0,
+            source: PathBuf::from("save.sav"),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn manifests_list_each_backup_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("backups.jsonl");
+        let lines: Vec<_> = [record("a", 1), record("b", 2), record("a", 3)]
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect();
+        fs::write(&manifest, lines.join("\n")).unwrap();
+
+        let records = read_records(&manifest).unwrap();
+        let listed: Vec<_> =
+            records.iter().map(|r| (r.path.to_str().unwrap(), r.timestamp)).collect();
+        assert_eq!(listed, [("b", 2), ("a", 3)]);
+        write_records(&manifest, &records).unwrap();
+        assert_eq!(fs::read_to_string(&manifest).unwrap().lines().count(), 2);
+    }
+}
@@ -0,0 +1,373 @@
+use std::io::{BufReader, Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use fs_err as fs;
+use tracing::*;
+use uesave::Save;
+
+use crate::diff::find_difference;
+use crate::error::DrgSaveError;
+use crate::journal::{append_journal, journal_entry};
+use crate::lenient::{splice_opaque, OpaqueProperty};
+use crate::lock::{SaveLock, LOCK_TIMEOUT};
+use crate::session::{SaveSession, SessionOptions};
+use crate::util::unix_timestamp_millis;
+
+/// Read and parse the save file at `path`, straight from the file without buffering all of it.
+pub fn read_save(path: &Path) -> crate::error::Result<Save> {
+    let file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    Save::read(&mut BufReader::new(file)).map_err(|source| DrgSaveError::Parse {
+        path: path.to_path_buf(),
+        offset: source.offset as u64,
+        len,
+        source,
+    })
+}
+
+/// Read and parse the save file at `path` for inspection only. With the `mmap` feature the file is
+/// parsed from a memory map, so large saves aren't copied into memory before parsing; otherwise
+/// this is [`read_save`].
+#[cfg(feature = "mmap")]
+pub fn read_save_mapped(path: &Path) -> crate::error::Result<Save> {
+    let file = fs::File::open(path)?;
+    // SAFETY: the map is only read while parsing. Another process truncating the save meanwhile
+    // (e.g. the game writing it) would fault, which is why this is opt-in and only used by
+    // commands that don't write.
+    let map = unsafe { memmap2::Mmap::map(file.file())? };
+    parse_save(&map, path)
+}
+
+/// Read and parse the save file at `path` for inspection only. With the `mmap` feature the file is
+/// parsed from a memory map, so large saves aren't copied into memory before parsing; otherwise
+/// this is [`read_save`].
+#[cfg(not(feature = "mmap"))]
+pub fn read_save_mapped(path: &Path) -> crate::error::Result<Save> {
+    read_save(path)
+}
+
+/// Parse the contents of a save file. `path` is only used to report errors.
+pub fn parse_save(buf: &[u8], path: &Path) -> crate::error::Result<Save> {
+    Save::read(&mut Cursor::new(buf)).map_err(|source| DrgSaveError::Parse {
+        path: path.to_path_buf(),
+        offset: source.offset as u64,
+        len: buf.len() as u64,
+        source,
+    })
+}
+
+/// Where backups go and how many are kept.
+#[derive(Debug, Clone, Default)]
+pub struct BackupOptions {
+    /// Directory to write backups to. Defaults to the directory of the save.
+    pub dir: Option<PathBuf>,
+    /// Number of backups to keep per save. Without a directory or retention, a single backup is
+    /// kept next to the save.
+    pub retention: Option<usize>,
+    /// Don't back up at all, e.g. when snapshots are kept anyway.
+    pub disabled: bool,
+}
+
+/// Start of every zstd-compressed file.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Read the contents of the backup at `path`, decompressing it if it is compressed (see
+/// [`backup_save_with`]).
+pub fn read_backup(path: &Path) -> crate::error::Result<Vec<u8>> {
+    let buf = fs::read(path)?;
+    if !buf.starts_with(ZSTD_MAGIC) {
+        return Ok(buf);
+    }
+    Ok(zstd::decode_all(buf.as_slice())?)
+}
+
+/// Copy the save file at `path` next to itself with a `.sav.bak` extension.
+pub fn backup_save(path: &Path) -> crate::error::Result<PathBuf> {
+    let backup_path = path.with_extension("sav.bak");
+    fs::copy(path, &backup_path)?;
+    info!("creating backup save file: `{}`", backup_path.display());
+    record_backup(&backup_path, path);
+    Ok(backup_path)
+}
+
+/// Record `backup` for `drg-save verify`. A backup that can't be recorded is still a backup, so
+/// this only warns.
+fn record_backup(backup: &Path, source: &Path) {
+    if let Err(e) = crate::integrity::record_backup(backup, source) {
+        warn!("failed to record backup `{}`: {e:#}", backup.display());
+    }
+}
+
+/// Back up the save file at `path` according to `options`. With a backup directory or retention,
+/// backups are zstd-compressed and named `<save name>.<unix timestamp in ms>.sav.bak.zst` so several can
+/// be kept, and the oldest ones beyond the retention count are deleted. The single backup next to
+/// the save otherwise made is a plain copy, so it can be copied back by hand. Returns the path of
+/// the backup, if one was made.
+pub fn backup_save_with(
+    path: &Path,
+    options: &BackupOptions,
+) -> crate::error::Result<Option<PathBuf>> {
+    if options.disabled {
+        info!("backups are disabled, not backing up `{}`", path.display());
+        return Ok(None);
+    }
+    if options.dir.is_none() && options.retention.is_none() {
+        return backup_save(path).map(Some);
+    }
+
+    let dir = match &options.dir {
+        Some(dir) => dir.clone(),
+        None => path.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    let backup_path = write_compressed_backup(path, &dir)?;
+    info!("creating backup save file: `{}`", backup_path.display());
+    record_backup(&backup_path, path);
+    if let Some(retention) = options.retention {
+        let pruned = prune_backups(path, &dir, retention)?;
+        if let Err(e) = crate::integrity::forget_backups(&pruned) {
+            warn!("failed to drop the records of pruned backups: {e:#}");
+        }
+    }
+    Ok(Some(backup_path))
+}
+
+/// Write a zstd-compressed, timestamped backup of the save file at `path` into `dir`. The
+/// timestamp is bumped past those of existing backups of the same millisecond, so none is
+/// overwritten.
+fn write_compressed_backup(path: &Path, dir: &Path) -> crate::error::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut timestamp = unix_timestamp_millis();
+    let mut backup_path = dir.join(format!("{stem}.{timestamp}.sav.bak.zst"));
+    while backup_path.exists() {
+        timestamp += 1;
+        backup_path = dir.join(format!("{stem}.{timestamp}.sav.bak.zst"));
+    }
+    let buf = fs::read(path)?;
+    fs::write(&backup_path, zstd::encode_all(buf.as_slice(), zstd::DEFAULT_COMPRESSION_LEVEL)?)?;
+    Ok(backup_path)
+}
+
+/// Delete the oldest timestamped backups of the save file at `path` in `dir` beyond the `retention`
+/// most recent ones, compressed or not. Returns the canonical paths of the deleted backups.
+fn prune_backups(path: &Path, dir: &Path, retention: usize) -> crate::error::Result<Vec<PathBuf>> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut backups = vec![];
+    for entry in fs::read_dir(dir)? {
+        let backup = entry?.path();
+        let name = backup.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let timestamp = name
+            .strip_prefix(&format!("{stem}."))
+            .and_then(|rest| rest.strip_suffix(".zst").unwrap_or(rest).strip_suffix(".sav.bak"))
+            .and_then(|timestamp| timestamp.parse::<u64>().ok());
+        if let Some(timestamp) = timestamp {
+            backups.push((timestamp, backup));
+        }
+    }
+    backups.sort();
+    let n_to_delete = backups.len().saturating_sub(retention.max(1));
+    let mut deleted = vec![];
+    for (_, backup) in backups.into_iter().take(n_to_delete) {
+        let canonical = backup.canonicalize()?;
+        fs::remove_file(&backup)?;
+        debug!("deleted old backup `{}`", backup.display());
+        deleted.push(canonical);
+    }
+    Ok(deleted)
+}
+
+/// Serialize `save` and re-parse the serialized bytes, failing if the re-parsed save is not
+/// structurally identical to `save`.
+pub fn serialize_save_verified(save: &Save) -> Result<Vec<u8>> {
+    serialize_save_verified_with_capacity(save, 0)
+}
+
+/// [`serialize_save_verified`] into a buffer with room for `capacity` bytes up front, e.g. the size
+/// of the file being replaced, so it doesn't have to grow while serializing.
+fn serialize_save_verified_with_capacity(save: &Save, capacity: usize) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(capacity);
+    save.write(&mut buf)?;
+
+    let reparsed =
+        Save::read(&mut Cursor::new(&buf)).context("modified save could not be re-parsed")?;
+    if let Some(prop_path) = find_difference(save, &reparsed) {
+        bail!(DrgSaveError::UnexpectedShape(format!(
+            "round trip of modified save differs at `{prop_path}`"
+        )));
+    }
+    debug!("round trip of modified save verified");
+    Ok(buf)
+}
+
+/// Serialize `save`, re-parse the serialized bytes and only replace the file at `path` if the
+/// re-parsed save is structurally identical to `save`. The original file is left untouched if the
+/// round trip fails.
+pub fn write_save_verified(path: &Path, save: &Save) -> Result<()> {
+    write_verified(path, save, &[]).map(drop)
+}
+
+/// [`write_save_verified`] with the `opaque` properties of a lenient parse spliced back in (see
+/// [`crate::lenient`]), returning the bytes written.
+fn write_verified(path: &Path, save: &Save, opaque: &[OpaqueProperty]) -> Result<Vec<u8>> {
+    let capacity = fs::metadata(path).map_or(0, |metadata| metadata.len() as usize);
+    let buf = serialize_save_verified_with_capacity(save, capacity)
+        .context("modified save failed verification, keeping original save file")?;
+    let buf = splice_opaque(&buf, opaque)?;
+
+    // Replace the file a symlinked save points to rather than the link, with the same permissions
+    // as before instead of those of a fresh temporary file.
+    let path = match fs::canonicalize(path) {
+        Ok(path) => path,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => path.to_path_buf(),
+        Err(e) => return Err(e.into()),
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(&buf)?;
+    if let Ok(metadata) = fs::metadata(&path) {
+        tmp.as_file().set_permissions(metadata.permissions())?;
+    }
+    tmp.persist(&path)?;
+    Ok(buf)
+}
+
+/// [`write_save_verified`] over the existing save file at `path`, then record the changed
+/// properties in its journal (see [`crate::journal`]) so the write can be undone. The save is
+/// locked meanwhile.
+pub fn write_save_journaled(path: &Path, save: &Save, tool: &str) -> Result<()> {
+    let _lock = SaveLock::acquire(path, LOCK_TIMEOUT)?;
+    let old = read_save(path)?;
+    write_journaled(path, &old, save, &[], tool).map(drop)
+}
+
+/// [`write_verified`] and [`append_journal`]. Returns the bytes written.
+pub(crate) fn write_journaled(
+    path: &Path,
+    old: &Save,
+    new: &Save,
+    opaque: &[OpaqueProperty],
+    tool: &str,
+) -> Result<Vec<u8>> {
+    let entry = journal_entry(old, new, tool)?;
+    let buf = write_verified(path, new, opaque)?;
+    append_journal(path, &entry)?;
+    debug!("journaled {} changed properties", entry.changes.len());
+    Ok(buf)
+}
+
+/// Result of [`edit_with`].
+#[derive(Debug)]
+pub struct Edited<T> {
+    /// What the edit returned.
+    pub value: T,
+    /// Whether the save was written. It isn't if the edit left it unchanged, e.g. when the same
+    /// edit is run twice.
+    pub written: bool,
+}
+
+/// Edit the save file at `path` in place in a [`SaveSession`]: read it once, apply `edit`, back it
+/// up according to `options` and write the result once, journaled as made by `tool`. Nothing is
+/// backed up or written if `edit` fails or doesn't change anything. The save is locked from reading
+/// to writing, so concurrent edits can't undo each other.
+pub fn edit_with<T>(
+    path: &Path,
+    tool: &str,
+    options: &SessionOptions,
+    edit: impl FnOnce(&mut Save) -> Result<T>,
+) -> Result<Edited<T>> {
+    edit_with_confirm(path, tool, options, edit, |_, _| Ok(true))
+}
+
+/// [`edit_with`], but `confirm` is asked with the original and the edited save whether to write
+/// the changes. Nothing is backed up or written if it returns `false`. The save is unlocked while
+/// asking (see [`SaveSession::unlocked`]).
+pub fn edit_with_confirm<T>(
+    path: &Path,
+    tool: &str,
+    options: &SessionOptions,
+    edit: impl FnOnce(&mut Save) -> Result<T>,
+    confirm: impl FnOnce(&Save, &Save) -> Result<bool>,
+) -> Result<Edited<T>> {
+    let mut session = SaveSession::open(path, tool, options.clone())?;
+    let value = edit(session.save_mut())?;
+    if !session.is_dirty()? {
+        info!("already at target state, leaving `{}` untouched", path.display());
+        return Ok(Edited { value, written: false });
+    }
+    let original = session.original()?;
+    if !session.unlocked(|session| confirm(&original, session.save()))? {
+        info!("changes not confirmed, leaving `{}` untouched", path.display());
+        return Ok(Edited { value, written: false });
+    }
+    session.commit()?;
+    Ok(Edited { value, written: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::{Property, PropertyKey};
+
+    use super::*;
+    use crate::fixtures::{synthetic_save, synthetic_save_file};
+
+    #[test]
+    fn keeps_the_original_when_the_round_trip_differs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = synthetic_save_file(dir.path());
+        let original = fs::read(&path).unwrap();
+
+        let mut save = synthetic_save();
+        // NaN never equals itself, so the re-parsed save always differs.
+        let odd = Property::Float { id: None, value: f32::NAN };
+        save.root.properties.0.insert(PropertyKey::from("Odd"), odd);
+        assert!(serialize_save_verified(&save).is_err());
+        assert!(write_save_verified(&path, &save).is_err());
+        assert_eq!(fs::read(&path).unwrap(), original);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_through_symlinks_keeping_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = synthetic_save_file(dir.path());
+        fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let link = dir.path().join("link.sav");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+
+        write_save_verified(&link, &synthetic_save()).unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o644);
+    }
+
+    #[test]
+    fn timestamped_backups_are_compressed_and_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("save.sav");
+        let contents = vec![42; 4096];
+        fs::write(&path, &contents).unwrap();
+        let backups = dir.path().join("backups");
+        fs::create_dir(&backups).unwrap();
+        // Left by an earlier version, before backups were compressed.
+        fs::write(backups.join("save.1.sav.bak"), &contents).unwrap();
+
+        let backup = write_compressed_backup(&path, &backups).unwrap();
+        assert!(backup.to_string_lossy().ends_with(".sav.bak.zst"));
+        // Backups made in quick succession don't overwrite each other.
+        let newer = write_compressed_backup(&path, &backups).unwrap();
+        assert_ne!(newer, backup);
+        assert!(fs::metadata(&backup).unwrap().len() < contents.len() as u64);
+        assert_eq!(read_backup(&backup).unwrap(), contents);
+        assert_eq!(read_backup(&backups.join("save.1.sav.bak")).unwrap(), contents);
+
+        let pruned = prune_backups(&path, &backups, 1).unwrap();
+        assert_eq!(pruned.len(), 2);
+        let left: Vec<_> = fs::read_dir(&backups).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(left, [newer]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Journal of the edits made to a save, used to undo them one write at a time.
+//!
+//! The journal lives next to the save with a `.sav.journal` extension and holds one JSON
+//! [`JournalEntry`] per line, oldest first.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::*;
+use uesave::{Properties, Property, Save};
+
+use crate::diff::find_property_differences;
+use crate::error::DrgSaveError;
+use crate::util::unix_timestamp;
+use crate::walk::{properties_at, properties_at_mut, property_at, property_key, split_path};
+
+/// A single property changed by a write. `old` is `None` if the write added the property, `new`
+/// is `None` if the write removed it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyChange {
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+    /// Position of the property among its siblings before the write, so that undoing its removal
+    /// puts it back where it was. Missing in entries journaled before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+}
+
+/// All property changes made by one write of the save.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The tool (and subcommand) that made the write, e.g. `drg-save clone-class`.
+    pub tool: String,
+    pub changes: Vec<PropertyChange>,
+}
+
+/// Path of the journal of the save file at `path`.
+pub fn journal_path(path: &Path) -> PathBuf {
+    path.with_extension("sav.journal")
+}
+
+/// Read the journal of the save file at `path`. A missing journal is empty.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>> {
+    let journal_path = journal_path(path);
+    if !journal_path.exists() {
+        return Ok(vec![]);
+    }
+    fs::read_to_string(&journal_path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line).with_context(|| {
+                format!("failed to parse entry {i} of journal `{}`", journal_path.display())
+            })
+        })
+        .collect()
+}
+
+/// Replace the journal of the save file at `path` with `entries`.
+pub fn write_journal(path: &Path, entries: &[JournalEntry]) -> Result<()> {
+    let mut buf = String::new();
+    for entry in entries {
+        buf.push_str(&serde_json::to_string(entry)?);
+        buf.push('\n');
+    }
+    fs::write(journal_path(path), buf)?;
+    Ok(())
+}
+
+/// Append `entry` to the journal of the save file at `path`.
+pub fn append_journal(path: &Path, entry: &JournalEntry) -> Result<()> {
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(journal_path(path))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Record the property changes between `old` and `new` as a journal entry made by `tool`.
+pub fn journal_entry(old: &Save, new: &Save, tool: &str) -> Result<JournalEntry> {
+    let changes = property_changes(&old.root.properties, &new.root.properties)?;
+    Ok(JournalEntry { timestamp: unix_timestamp(), tool: tool.to_string(), changes })
+}
+
+/// The innermost properties that differ between `old` and `new`, with their serialized values.
+pub fn property_changes(old: &Properties, new: &Properties) -> Result<Vec<PropertyChange>> {
+    find_property_differences(old, new)
+        .into_iter()
+        .map(|path| {
+            let (parent, name) = split_path(&path);
+            let index = properties_at(old, parent)
+                .and_then(|props| props.0.get_index_of(&property_key(name)));
+            let old = property_at(old, &path).map(serde_json::to_value);
+            let new = property_at(new, &path).map(serde_json::to_value);
+            Ok(PropertyChange { path, old: old.transpose()?, new: new.transpose()?, index })
+        })
+        .collect()
+}
+
+/// Revert the changes of `entry` in `save`, last change first. Unless `force` is set, every
+/// property has to still hold the value the entry wrote, so that edits made since (e.g. by
+/// playing the game) aren't silently overwritten. Properties the entry removed are put back at
+/// their old positions.
+pub fn undo_entry(save: &mut Save, entry: &JournalEntry, force: bool) -> Result<()> {
+    // Removed properties are put back once everything else is reverted, lowest position first,
+    // so that each goes back among the same siblings as before.
+    let mut removed = vec![];
+    for change in entry.changes.iter().rev() {
+        let (parent, name) = split_path(&change.path);
+        let Some(props) = properties_at_mut(&mut save.root.properties, parent) else {
+            bail!(DrgSaveError::InvalidInput(format!(
+                "`{parent}` not found, cannot undo change of `{}`",
+                change.path
+            )));
+        };
+
+        let current = property_at(props, name).map(serde_json::to_value).transpose()?;
+        if current != change.new {
+            if !force {
+                bail!(DrgSaveError::InvalidInput(format!(
+                    "`{}` was modified since it was journaled, not undoing it",
+                    change.path
+                )));
+            }
+            warn!("`{}` was modified since it was journaled, undoing it anyway", change.path);
+        }
+
+        match (&change.old, change.index) {
+            (Some(old), Some(index)) if current.is_none() => {
+                let old: Property = serde_json::from_value(old.clone())?;
+                removed.push((index, change, old));
+                continue;
+            }
+            (Some(old), _) => {
+                let old: Property = serde_json::from_value(old.clone())?;
+                props.0.insert(property_key(name), old);
+            }
+            (None, _) => {
+                props.0.shift_remove(&property_key(name));
+            }
+        }
+        debug!("reverted `{}`", change.path);
+    }
+
+    removed.sort_by_key(|(index, ..)| *index);
+    for (index, change, old) in removed {
+        let (parent, name) = split_path(&change.path);
+        let props = properties_at_mut(&mut save.root.properties, parent)
+            .context("parent of a removed property is gone")?;
+        props.0.shift_insert(index.min(props.0.len()), property_key(name), old);
+        debug!("reverted `{}`", change.path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::synthetic_save;
+    use crate::util::clone_via_serde;
+
+    fn names(save: &Save) -> Vec<String> {
+        save.root.properties.0.keys().map(|key| key.1.clone()).collect()
+    }
+
+    #[test]
+    fn undoing_removals_keeps_property_order() {
+        let old = synthetic_save();
+        let mut new = clone_via_serde(&old).unwrap();
+        let props = &mut new.root.properties.0;
+        assert!(props.len() > 4);
+        props.pop();
+        props.shift_remove_index(3);
+        props.shift_remove_index(1);
+
+        let entry = journal_entry(&old, &new, "test").unwrap();
+        undo_entry(&mut new, &entry, false).unwrap();
+        assert_eq!(names(&new), names(&old));
+        assert_eq!(new, old);
+    }
+}
@@ -0,0 +1,185 @@
+//! Validators for values the game could plausibly have produced itself, for `--legit` edits that
+//! shouldn't result in a save other players notice: values an edit pushes out of range are clamped
+//! back into it.
+//!
+//! The limits are deliberately generous: they catch impossible values (negative levels or XP) and
+//! values far beyond long-time players' saves, not merely unusual ones. XP past the level cap is
+//! fine, the game keeps counting it until the class is promoted. The hidden class save slot is
+//! left out, its `RetiredCharacterLevels` are how the blue level is adjusted (see
+//! [`crate::editors::blue_number`]).
+
+use std::fmt;
+
+use serde::Serialize;
+use uesave::{Property, PropertyValue, Save, StructValue};
+
+use crate::classes::{class_saves, savegame_id, HIDDEN_CLASS_SAVEGAME_ID};
+use crate::editors::credits::PLAUSIBLE_CREDITS;
+use crate::error::{DrgSaveError, Result};
+use crate::walk::{property_at_mut, walk_properties};
+
+/// Promotions beyond this are far past the most played accounts.
+pub const PLAUSIBLE_PROMOTIONS: i32 = 100;
+
+/// Amounts of a single resource beyond this are far past what regular play hoards.
+pub const PLAUSIBLE_RESOURCE_AMOUNT: f64 = 100_000.0;
+
+/// A value outside the range the game could plausibly produce.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub path: String,
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.max.is_infinite() {
+            write!(f, "`{}` is {}, less than {}", self.path, self.value, self.min)
+        } else {
+            write!(f, "`{}` is {}, outside of {}..={}", self.path, self.value, self.min, self.max)
+        }
+    }
+}
+
+/// All values in `save` outside the plausible ranges.
+pub fn violations(save: &Save) -> Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let mut check = |path: String, value: f64, min: f64, max: f64| {
+        if !(min..=max).contains(&value) {
+            violations.push(Violation { path, value, min, max });
+        }
+    };
+
+    for (slot, class_save) in class_saves(save)?.iter().enumerate() {
+        let StructValue::Struct(props) = class_save else {
+            continue;
+        };
+        if savegame_id(class_save) == Some(HIDDEN_CLASS_SAVEGAME_ID) {
+            continue;
+        }
+        for (name, max) in [
+            ("TimesRetired", f64::from(PLAUSIBLE_PROMOTIONS)),
+            ("RetiredCharacterLevels", f64::INFINITY),
+            ("XP", f64::INFINITY),
+        ] {
+            if let Some(Property::Int { value, .. }) = crate::property(props, name) {
+                check(format!("CharacterSaves[{slot}].{name}"), f64::from(*value), 0.0, max);
+            }
+        }
+    }
+
+    walk_properties(&save.root.properties, &mut |path, prop| match prop {
+        Property::Int { value, .. } if path == "Credits" || path.ends_with(".Credits") => {
+            check(path.to_string(), f64::from(*value), 0.0, PLAUSIBLE_CREDITS as f64)
+        }
+        Property::Map { value, .. } if path.ends_with("OwnedResources") => {
+            for (i, entry) in value.iter().enumerate() {
+                if let PropertyValue::Float(amount) = entry.value {
+                    let path = format!("{path}[{i}]");
+                    check(path, f64::from(amount), 0.0, PLAUSIBLE_RESOURCE_AMOUNT);
+                }
+            }
+        }
+        _ => {}
+    });
+    Ok(violations)
+}
+
+/// The violations in `edited` that aren't in `before` (the [`violations`] of the save before it
+/// was edited), i.e. the ones an edit caused.
+pub fn new_violations(before: &[Violation], edited: &Save) -> Result<Vec<Violation>> {
+    Ok(violations(edited)?.into_iter().filter(|violation| !before.contains(violation)).collect())
+}
+
+/// Clamp the [`new_violations`] in `edited` into their ranges. Returns them as they were before
+/// clamping.
+pub fn clamp_new_violations(before: &[Violation], edited: &mut Save) -> Result<Vec<Violation>> {
+    let violations = new_violations(before, edited)?;
+    for violation in &violations {
+        set_value(edited, &violation.path, violation.value.clamp(violation.min, violation.max))?;
+    }
+    Ok(violations)
+}
+
+/// Set the int or resource amount at `path`, as found by [`violations`], to `value`.
+fn set_value(save: &mut Save, path: &str, value: f64) -> Result<()> {
+    let props = &mut save.root.properties;
+    if let Some((map_path, index)) = path.strip_suffix(']').and_then(|path| path.rsplit_once('[')) {
+        let entry = match (property_at_mut(props, map_path), index.parse::<usize>()) {
+            (Some(Property::Map { value: entries, .. }), Ok(index)) => entries.get_mut(index),
+            _ => None,
+        };
+        match entry.map(|entry| &mut entry.value) {
+            Some(PropertyValue::Float(amount)) => *amount = value as f32,
+            _ => return Err(DrgSaveError::missing(path)),
+        }
+        return Ok(());
+    }
+    match property_at_mut(props, path) {
+        Some(Property::Int { value: int, .. }) => *int = value as i32,
+        Some(prop) => return Err(DrgSaveError::type_mismatch(path, "Int", prop)),
+        None => return Err(DrgSaveError::missing(path)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blue_level::blue_level;
+    use crate::editors::blue_number::{reset_blue_number, BlueNumberOptions};
+    use crate::fixtures::synthetic_save;
+
+    fn int(save: &mut Save, path: &str) -> i32 {
+        match property_at_mut(&mut save.root.properties, path) {
+            Some(Property::Int { value, .. }) => *value,
+            _ => panic!("`{path}` is an int"),
+        }
+    }
+
+    #[test]
+    fn blue_number_reset_is_legit() {
+        let mut save = synthetic_save();
+        let before = violations(&save).unwrap();
+        reset_blue_number(&mut save, &BlueNumberOptions::default()).unwrap();
+        assert_eq!(new_violations(&before, &save).unwrap(), []);
+        assert_eq!(blue_level(class_saves(&save).unwrap()).unwrap(), -69);
+    }
+
+    #[test]
+    fn xp_past_the_level_cap_is_legit() {
+        let mut save = synthetic_save();
+        let before = violations(&save).unwrap();
+        set_value(&mut save, "CharacterSaves[0].XP", 400_000.0).unwrap();
+        assert_eq!(new_violations(&before, &save).unwrap(), []);
+    }
+
+    #[test]
+    fn clamps_new_violations() {
+        let mut save = synthetic_save();
+        let before = violations(&save).unwrap();
+        set_value(&mut save, "CharacterSaves[0].TimesRetired", 500.0).unwrap();
+        set_value(&mut save, "CharacterSaves[0].XP", -1.0).unwrap();
+        set_value(&mut save, "Credits", -5.0).unwrap();
+        set_value(&mut save, "Resources.OwnedResources[0]", 1e9).unwrap();
+
+        let clamped = clamp_new_violations(&before, &mut save).unwrap();
+        let paths: Vec<_> = clamped.iter().map(|violation| violation.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "CharacterSaves[0].TimesRetired",
+                "CharacterSaves[0].XP",
+                "Credits",
+                "Resources.OwnedResources[0]"
+            ]
+        );
+        assert_eq!(int(&mut save, "CharacterSaves[0].TimesRetired"), PLAUSIBLE_PROMOTIONS);
+        assert_eq!(int(&mut save, "CharacterSaves[0].XP"), 0);
+        assert_eq!(int(&mut save, "Credits"), 0);
+        assert_eq!(clamped[3].value, 1e9);
+        assert_eq!(new_violations(&before, &save).unwrap(), []);
+    }
+}
@@ -0,0 +1,154 @@
+//! Lenient parsing, for saves with a top-level property `uesave` can't parse, e.g. one of a shape a
+//! new game version introduced. Such properties are set aside as their raw bytes and written back
+//! where they were, so edits to the rest of the save still round-trip byte-exactly instead of the
+//! whole save failing to parse.
+//!
+//! Properties are only set aside whole, at the top level: an odd property nested in `SeasonSave`
+//! leaves all of `SeasonSave` opaque, so editors working on it fail as if it were missing. Finding
+//! the properties relies on the size every property header carries, so a property of a type whose
+//! header `uesave` doesn't know still fails the save.
+
+use std::io::Cursor;
+use std::path::Path;
+
+use tracing::*;
+use uesave::{PropertyKey, Save};
+
+use crate::error::Result;
+use crate::io::parse_save;
+use crate::raw::{layout, PropertySpan};
+
+/// A top-level property that couldn't be parsed, as its raw bytes.
+#[derive(Debug)]
+pub struct OpaqueProperty {
+    pub name: String,
+    /// The parsed top-level property it came after, if any.
+    pub after: Option<PropertyKey>,
+    /// The whole property, header included.
+    pub bytes: Vec<u8>,
+}
+
+/// Parse the contents of a save file like [`parse_save`], but set aside top-level properties that
+/// can't be parsed (with a warning) instead of failing. Write the save back with
+/// [`splice_opaque`] to keep them.
+pub fn parse_save_lenient(buf: &[u8], path: &Path) -> Result<(Save, Vec<OpaqueProperty>)> {
+    let err = match parse_save(buf, path) {
+        Ok(save) => return Ok((save, vec![])),
+        Err(err) => err,
+    };
+    let Ok(layout) = layout(buf) else {
+        return Err(err);
+    };
+    let start = layout.properties.first().map_or(layout.end, |span| span.range.start);
+    let (prefix, suffix) = (&buf[..start], &buf[layout.end..]);
+
+    let mut parsed = prefix.to_vec();
+    let mut opaque = vec![];
+    let mut after = None;
+    for PropertySpan { key, range, .. } in layout.properties {
+        let bytes = &buf[range.clone()];
+        let alone = [prefix, bytes, suffix].concat();
+        if Save::read(&mut Cursor::new(&alone)).is_ok() {
+            parsed.extend_from_slice(bytes);
+            after = Some(key);
+        } else {
+            warn!(
+                "keeping `{}` ({} bytes at offset {}) as is, it can't be parsed",
+                key.1,
+                bytes.len(),
+                range.start
+            );
+            opaque.push(OpaqueProperty {
+                name: key.1,
+                after: after.as_ref().map(|key: &PropertyKey| PropertyKey(key.0, key.1.clone())),
+                bytes: bytes.to_vec(),
+            });
+        }
+    }
+    if opaque.is_empty() {
+        // Every property parses on its own, so setting some aside wouldn't help.
+        return Err(err);
+    }
+    parsed.extend_from_slice(suffix);
+    Ok((parse_save(&parsed, path)?, opaque))
+}
+
+/// Insert the `opaque` properties into `buf`, a save serialized without them, each after the
+/// property it came after. Those that came after a property `buf` no longer has go last.
+pub fn splice_opaque(buf: &[u8], opaque: &[OpaqueProperty]) -> Result<Vec<u8>> {
+    if opaque.is_empty() {
+        return Ok(buf.to_vec());
+    }
+    let layout = layout(buf)?;
+    let start = layout.properties.first().map_or(layout.end, |span| span.range.start);
+    let mut inserts: Vec<(usize, &[u8])> = opaque
+        .iter()
+        .map(|property| {
+            let at = match &property.after {
+                None => start,
+                Some(after) => layout
+                    .properties
+                    .iter()
+                    .find(|span| span.key == *after)
+                    .map_or(layout.end, |span| span.range.end),
+            };
+            (at, property.bytes.as_slice())
+        })
+        .collect();
+    inserts.sort_by_key(|(at, _)| *at);
+
+    let mut spliced =
+        Vec::with_capacity(buf.len() + inserts.iter().map(|(_, b)| b.len()).sum::<usize>());
+    let mut copied = 0;
+    for (at, bytes) in inserts {
+        spliced.extend_from_slice(&buf[copied..at]);
+        spliced.extend_from_slice(bytes);
+        copied = at;
+    }
+    spliced.extend_from_slice(&buf[copied..]);
+    Ok(spliced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{synthetic_save, synthetic_save_with_unparsable_property};
+    use crate::raw::layout;
+    use crate::util::int_mut;
+
+    #[test]
+    fn sets_aside_and_restores_unparsable_properties() {
+        let (buf, odd) = synthetic_save_with_unparsable_property();
+        let path = Path::new("save.sav");
+        assert!(parse_save(&buf, path).is_err());
+
+        let (mut save, opaque) = parse_save_lenient(&buf, path).unwrap();
+        assert_eq!(opaque.len(), 1);
+        assert_eq!((opaque[0].name.as_str(), &opaque[0].bytes), ("Odd", &odd));
+
+        let mut written = vec![];
+        save.write(&mut written).unwrap();
+        assert_eq!(splice_opaque(&written, &opaque).unwrap(), buf);
+
+        *int_mut(&mut save.root.properties, "Credits").unwrap() = 1;
+        let mut written = vec![];
+        save.write(&mut written).unwrap();
+        let spliced = splice_opaque(&written, &opaque).unwrap();
+        let (mut reparsed, opaque) = parse_save_lenient(&spliced, path).unwrap();
+        assert_eq!(opaque[0].bytes, odd);
+        assert_eq!(*int_mut(&mut reparsed.root.properties, "Credits").unwrap(), 1);
+    }
+
+    #[test]
+    fn parses_saves_without_odd_properties_as_usual() {
+        let mut buf = vec![];
+        synthetic_save().write(&mut buf).unwrap();
+        assert_eq!(
+            layout(&buf).unwrap().properties.len(),
+            synthetic_save().root.properties.0.len()
+        );
+        let (save, opaque) = parse_save_lenient(&buf, Path::new("save.sav")).unwrap();
+        assert!(opaque.is_empty());
+        assert_eq!(save, synthetic_save());
+    }
+}
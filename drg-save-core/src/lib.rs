@@ -0,0 +1,61 @@
+//! Shared save file handling for the DRG save tools.
+
+pub mod anonymize;
+pub mod blue_level;
+pub mod classes;
+pub mod config;
+pub mod cosmetics;
+pub mod counters;
+pub mod describe;
+pub mod diff;
+pub mod editors;
+pub mod error;
+pub mod events;
+pub mod expected_schema;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod guid_array;
+pub mod guid_db;
+pub mod identity;
+pub mod index;
+pub mod integrity;
+pub mod io;
+pub mod journal;
+pub mod legit;
+pub mod lenient;
+pub mod loadout;
+pub mod locate;
+pub mod lock;
+pub mod migrate;
+pub mod missions;
+pub mod patch;
+pub mod pools;
+pub mod raw;
+pub mod rebind;
+pub mod report;
+pub mod resources;
+pub mod restore;
+pub mod rewards;
+pub mod schema;
+pub mod season;
+pub mod session;
+pub mod snapshot;
+pub mod template;
+#[cfg(feature = "track")]
+pub mod track;
+pub mod unlocks;
+mod util;
+pub mod value;
+pub mod version;
+pub mod walk;
+pub mod xp;
+
+pub use diff::find_difference;
+pub use error::DrgSaveError;
+pub use io::{
+    backup_save, backup_save_with, edit_with, edit_with_confirm, parse_save, read_backup,
+    read_save, read_save_mapped, serialize_save_verified, write_save_journaled,
+    write_save_verified, Edited,
+};
+pub use session::{read_for_write_back, write_back, SaveSession, SessionOptions};
+pub use util::{property, property_mut};
@@ -0,0 +1,219 @@
+//! Export and import of a class's loadout (equipped weapons, mods, overclocks and cosmetics).
+//!
+//! A loadout is everything in a class's `CharacterSave` struct except the properties tracking
+//! progression, so it keeps working when new loadout-related properties are added to the save.
+
+use serde::{Deserialize, Serialize};
+use uesave::{Properties, Property, PropertyKey, Save, StructValue};
+use uuid::Uuid;
+
+use crate::classes::{
+    class_saves, class_saves_mut, find_class_save, savegame_id, HIDDEN_CLASS_SAVEGAME_ID,
+};
+use crate::error::{DrgSaveError, Result};
+use crate::util::clone_via_serde;
+use crate::walk::walk_properties_mut;
+
+/// Properties of a `CharacterSave` which track progression rather than the loadout.
+pub const PROGRESSION_PROPERTIES: &[&str] =
+    &["SavegameID", "XP", "TimesRetired", "RetiredCharacterLevels"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Loadout {
+    /// `SavegameID` of the class save slot the loadout was exported from.
+    pub savegame_id: Uuid,
+    pub properties: Properties,
+}
+
+/// Copy the loadout properties out of `class_save`.
+pub fn export_loadout(class_save: &StructValue) -> Result<Loadout> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let Some(savegame_id) = savegame_id(class_save) else {
+        return Err(DrgSaveError::missing("SavegameID"));
+    };
+
+    let mut properties = Properties::default();
+    for (key, prop) in props {
+        if !PROGRESSION_PROPERTIES.contains(&key.1.as_str()) {
+            properties.0.insert(PropertyKey(key.0, key.1.clone()), clone_via_serde(prop)?);
+        }
+    }
+    Ok(Loadout { savegame_id, properties })
+}
+
+/// Replace the loadout properties of `class_save` with the ones from `loadout`, returning the names
+/// of the properties that were written.
+pub fn import_loadout(class_save: &mut StructValue, loadout: Loadout) -> Result<Vec<String>> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+
+    if let Some(key) =
+        loadout.properties.0.keys().find(|key| PROGRESSION_PROPERTIES.contains(&key.1.as_str()))
+    {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "loadout must not contain progression property `{}`",
+            key.1
+        )));
+    }
+
+    let mut written = vec![];
+    for (key, prop) in loadout.properties.0 {
+        written.push(key.1.clone());
+        props.0.insert(key, prop);
+    }
+    Ok(written)
+}
+
+/// Reset the loadout properties of `class_save` to those of a freshly created class: nothing picked
+/// in any slot (a nil GUID, which the game fills with the class's default item, like the empty
+/// slots of the sample save uesave is tested with, `drg-save-test.sav`), the first loadout selected
+/// and every flag cleared. The number of loadouts is kept. Returns the paths of the properties that
+/// changed.
+pub fn reset_loadout(class_save: &mut StructValue) -> Result<Vec<String>> {
+    let StructValue::Struct(props) = class_save else {
+        return Err(DrgSaveError::unexpected_class_save_kind());
+    };
+    let mut changed = vec![];
+    walk_properties_mut(props, &mut |path, prop| {
+        let top_level = path.split(['.', '[']).next().unwrap_or(path);
+        if PROGRESSION_PROPERTIES.contains(&top_level) {
+            return;
+        }
+        let reset = match prop {
+            Property::Struct { value: StructValue::Guid(guid), .. } if !guid.is_nil() => {
+                *guid = Uuid::nil();
+                true
+            }
+            Property::Int { value, .. } if *value != 0 => {
+                *value = 0;
+                true
+            }
+            Property::Bool { value, .. } if *value => {
+                *value = false;
+                true
+            }
+            _ => false,
+        };
+        if reset {
+            changed.push(path.to_string());
+        }
+    });
+    Ok(changed)
+}
+
+/// Copy the loadouts of the active classes in `source` onto the same classes in `target`, leaving
+/// their progression alone. Only `classes` (by `SavegameID`) are synced if given. Returns the
+/// `SavegameID`s of the synced classes.
+pub fn sync_loadouts(
+    target: &mut Save,
+    source: &Save,
+    classes: Option<&[Uuid]>,
+) -> Result<Vec<Uuid>> {
+    let mut loadouts = vec![];
+    for class_save in class_saves(source)? {
+        let id = savegame_id(class_save);
+        if id == Some(HIDDEN_CLASS_SAVEGAME_ID) {
+            continue;
+        }
+        if classes.is_some_and(|classes| !id.is_some_and(|id| classes.contains(&id))) {
+            continue;
+        }
+        loadouts.push(export_loadout(class_save)?);
+    }
+
+    let target_class_saves = class_saves_mut(target)?;
+    let mut synced = vec![];
+    for loadout in loadouts {
+        let id = loadout.savegame_id;
+        let slot = find_class_save(target_class_saves, id)?;
+        import_loadout(&mut target_class_saves[slot], loadout)?;
+        synced.push(id);
+    }
+    Ok(synced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cosmetics::{equip, equipped, CosmeticSlot};
+    use crate::fixtures::{sample_save, synthetic_save, BEARDS, DRILLER, GUNNER, SCOUT};
+    use crate::util::{int_mut, property};
+    use crate::walk::walk_properties;
+
+    /// The loadout properties of `class_save` as JSON, for comparisons.
+    fn loadout_json(class_save: &StructValue) -> serde_json::Value {
+        serde_json::to_value(export_loadout(class_save).unwrap().properties).unwrap()
+    }
+
+    #[test]
+    fn imports_exported_loadouts_onto_other_classes() {
+        let mut save = sample_save();
+        let class_saves = class_saves_mut(&mut save).unwrap();
+        let exported = export_loadout(&class_saves[0]).unwrap();
+        // As written to and read from the file `loadout export` writes.
+        let loadout: Loadout =
+            serde_json::from_str(&serde_json::to_string(&exported).unwrap()).unwrap();
+        let progression = |class_save: &StructValue| {
+            let StructValue::Struct(props) = class_save else { unreachable!() };
+            PROGRESSION_PROPERTIES
+                .iter()
+                .map(|name| serde_json::to_value(props.0.get(&PropertyKey::from(*name))).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let before = progression(&class_saves[2]);
+        assert_ne!(loadout_json(&class_saves[2]), loadout_json(&class_saves[0]));
+
+        let written = import_loadout(&mut class_saves[2], loadout).unwrap();
+        assert!(written.iter().any(|name| name == "Vanity"));
+        assert_eq!(loadout_json(&class_saves[2]), loadout_json(&class_saves[0]));
+        assert_eq!(progression(&class_saves[2]), before);
+    }
+
+    #[test]
+    fn syncs_loadouts_and_cosmetics_keeping_progression() {
+        let mut source = synthetic_save();
+        for class_save in &mut class_saves_mut(&mut source).unwrap()[..4] {
+            equip(class_save, CosmeticSlot::Beard, 1, BEARDS[0]).unwrap();
+            let StructValue::Struct(props) = class_save else { unreachable!() };
+            *int_mut(props, "XP").unwrap() = 1;
+            *int_mut(props, "SelectedLoadout").unwrap() = 1;
+        }
+        let mut target = synthetic_save();
+
+        let synced = sync_loadouts(&mut target, &source, Some(&[DRILLER, SCOUT])).unwrap();
+        assert_eq!(synced, [DRILLER, SCOUT]);
+        let class_saves = class_saves(&target).unwrap();
+        let (driller, gunner) = (&class_saves[0], &class_saves[2]);
+        assert_eq!(equipped(driller, CosmeticSlot::Beard, 1).unwrap(), BEARDS[0]);
+        assert_eq!(crate::cosmetics::selected_loadout(driller).unwrap(), 1);
+        // Progression stays the target's, and classes not asked for are left alone.
+        let StructValue::Struct(props) = driller else { unreachable!() };
+        assert!(matches!(property(props, "XP"), Some(Property::Int { value: 50_000, .. })));
+        assert_eq!(equipped(gunner, CosmeticSlot::Beard, 1).unwrap(), BEARDS[1]);
+        assert_eq!(savegame_id(gunner), Some(GUNNER));
+
+        let synced = sync_loadouts(&mut target, &source, None).unwrap();
+        assert_eq!(synced.len(), 4, "the hidden class isn't synced");
+    }
+
+    #[test]
+    fn reset_loadout_leaves_nothing_picked_and_progression_alone() {
+        let mut save = synthetic_save();
+        let class_save = &mut class_saves_mut(&mut save).unwrap()[0];
+        assert!(!reset_loadout(class_save).unwrap().is_empty());
+        assert!(reset_loadout(class_save).unwrap().is_empty());
+
+        assert_eq!(savegame_id(class_save), Some(DRILLER));
+        let StructValue::Struct(props) = class_save else { unreachable!() };
+        walk_properties(props, &mut |path, prop| match prop {
+            Property::Int { value, .. } if path == "XP" => assert_eq!(*value, 50_000),
+            Property::Struct { value: StructValue::Guid(guid), .. } if path != "SavegameID" => {
+                assert!(guid.is_nil(), "`{path}` is still picked")
+            }
+            _ => {}
+        });
+    }
+}
@@ -0,0 +1,156 @@
+//! Discovery of save files on this machine.
+//!
+//! DRG keeps its saves in `FSD/Saved/SaveGames` in the game's install directory. With Steam there
+//! can be several library folders, each listed in `steamapps/libraryfolders.vdf`, and on Linux the
+//! game runs under Proton, which may also leave saves in the game's Proton prefix. All of them are
+//! searched, and the candidates are ranked by modification time, newest first.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Result;
+use fs_err as fs;
+use serde::Serialize;
+
+/// Steam app ID of Deep Rock Galactic.
+pub const APP_ID: u32 = 548430;
+
+/// Save directory relative to the game's install directory.
+const INSTALL_SAVE_DIR: &str = "steamapps/common/Deep Rock Galactic/FSD/Saved/SaveGames";
+
+/// Save directory inside the Proton prefix, relative to `compatdata/<app id>`.
+const PROTON_SAVE_DIR: &str = "pfx/drive_c/users/steamuser/AppData/Local/FSD/Saved/SaveGames";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub path: PathBuf,
+    /// Seconds since the Unix epoch.
+    pub modified: u64,
+}
+
+/// Steam installations found in their usual locations.
+pub fn steam_roots() -> Vec<PathBuf> {
+    let mut roots = vec![];
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join(".steam/steam"));
+        roots.push(home.join(".local/share/Steam"));
+        // Flatpak
+        roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+    }
+    if cfg!(windows) {
+        roots.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+    }
+    let mut found: Vec<PathBuf> = vec![];
+    for root in roots {
+        // `~/.steam/steam` is usually a symlink to one of the others.
+        let Ok(root) = root.canonicalize() else {
+            continue;
+        };
+        if !found.contains(&root) {
+            found.push(root);
+        }
+    }
+    found
+}
+
+/// The library folders of the Steam installation at `root`, including `root` itself.
+pub fn library_folders(root: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![root.to_path_buf()];
+    let Ok(vdf) = fs::read_to_string(root.join("steamapps/libraryfolders.vdf")) else {
+        return folders;
+    };
+    for folder in parse_library_folders(&vdf) {
+        if !folders.contains(&folder) {
+            folders.push(folder);
+        }
+    }
+    folders
+}
+
+/// The `"path"` values in `libraryfolders.vdf`. Only the lines of interest are parsed, instead of
+/// the whole KeyValues format.
+fn parse_library_folders(vdf: &str) -> Vec<PathBuf> {
+    vdf.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('"').filter(|field| !field.trim().is_empty());
+            match (fields.next(), fields.next()) {
+                (Some("path"), Some(path)) => Some(PathBuf::from(path.replace("\\\\", "\\"))),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Save directories in every Steam library and Proton prefix that exist.
+pub fn save_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    for root in steam_roots() {
+        for folder in library_folders(&root) {
+            dirs.push(folder.join(INSTALL_SAVE_DIR));
+            dirs.push(
+                folder.join("steamapps/compatdata").join(APP_ID.to_string()).join(PROTON_SAVE_DIR),
+            );
+        }
+    }
+    dirs.retain(|dir| dir.is_dir());
+    dirs.dedup();
+    dirs
+}
+
+/// The player saves (`*_Player.sav`) in all [`save_dirs`], most recently modified first.
+pub fn find_saves() -> Result<Vec<Candidate>> {
+    let mut candidates = vec![];
+    for dir in save_dirs() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if !name.to_string_lossy().ends_with("_Player.sav") {
+                continue;
+            }
+            let modified = entry.metadata()?.modified()?;
+            candidates.push(Candidate {
+                path: entry.path(),
+                modified: modified
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |duration| duration.as_secs()),
+            });
+        }
+    }
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.modified));
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn library_folders_from_vdf() {
+        let vdf = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"548430"		"4031628311"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+		"label"		"games"
+	}
+}
+"#;
+        assert_eq!(
+            parse_library_folders(vdf),
+            [
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from("/mnt/games/SteamLibrary")
+            ]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Advisory locking of save files, so concurrent tools (e.g. `drg-save watch` and a manual edit)
+//! don't race on the same save. The snapshot store is locked the same way.
+//!
+//! The lock is taken on a lock file rather than on the save itself, since the save is replaced by
+//! renaming a temporary file over it. Lock files are kept in the cache directory, named after the
+//! save's path, so no stray files end up in `SaveGames` where they could confuse the game. The OS
+//! releases the lock when the process exits, so a crashed tool never leaves a save locked.
+
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fs4::FileExt;
+use sha2::{Digest, Sha256};
+use tracing::*;
+
+use crate::error::{DrgSaveError, Result};
+
+/// How long to wait for another tool to finish with a save before giving up.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Exclusive lock on a save file, released when dropped.
+#[derive(Debug)]
+pub struct SaveLock {
+    file: std::fs::File,
+}
+
+/// Path of the lock file of the save file at `path`: `drg-save/locks/<SHA-256 of the canonical
+/// path>.lock` in the cache directory, or the temporary directory if there is none.
+pub fn lock_path(path: &Path) -> PathBuf {
+    let path = path
+        .canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let name = format!("{:x}.lock", Sha256::digest(path.as_os_str().as_encoded_bytes()));
+    dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join("drg-save").join("locks").join(name)
+}
+
+/// Remove the `.sav.lock` file earlier versions kept next to the save at `path`, unless a tool of
+/// such a version still holds it.
+fn remove_legacy_lock(path: &Path) {
+    let legacy = path.with_extension("sav.lock");
+    let Ok(file) = std::fs::OpenOptions::new().write(true).open(&legacy) else {
+        return;
+    };
+    if file.try_lock_exclusive().is_ok() {
+        match std::fs::remove_file(&legacy) {
+            Ok(()) => debug!("removed legacy lock file `{}`", legacy.display()),
+            Err(e) => warn!("failed to remove legacy lock file `{}`: {e}", legacy.display()),
+        }
+    }
+}
+
+impl SaveLock {
+    /// Lock the save file at `path`, waiting up to `timeout` for another process holding the lock.
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<Self> {
+        let lock_path = lock_path(path);
+        if let Some(dir) = lock_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)?;
+        let start = Instant::now();
+        let mut waiting = false;
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => {
+                    remove_legacy_lock(path);
+                    return Ok(Self { file });
+                }
+                Err(_) if start.elapsed() < timeout => {
+                    if !waiting {
+                        info!("waiting for another tool to finish with `{}`", path.display());
+                        waiting = true;
+                    }
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(_) => return Err(DrgSaveError::Locked { path: path.to_path_buf() }),
+            }
+        }
+    }
+}
+
+impl Drop for SaveLock {
+    fn drop(&mut self) {
+        if let Err(e) = FileExt::unlock(&self.file) {
+            warn!("failed to unlock save: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_without_files_next_to_the_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("save.sav");
+        std::fs::write(&path, b"").unwrap();
+        std::fs::write(path.with_extension("sav.lock"), b"").unwrap();
+
+        let lock = SaveLock::acquire(&path, Duration::ZERO).unwrap();
+        assert!(matches!(
+            SaveLock::acquire(&path, Duration::ZERO),
+            Err(DrgSaveError::Locked { .. })
+        ));
+        drop(lock);
+        SaveLock::acquire(&path, Duration::ZERO).unwrap();
+        let files: Vec<_> =
+            std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(files, [path]);
+    }
+}
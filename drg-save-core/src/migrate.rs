@@ -0,0 +1,132 @@
+//! Upgrades of saves written by older game versions to the layout the editors expect, so old
+//! backups and snapshots can be edited instead of failing on a missing property.
+//!
+//! Each [`Migration`] detects whether a save still has the old layout itself, since game updates
+//! don't reliably change the versions in the header (see [`crate::version`]). Migrations are
+//! applied in the order of [`MIGRATIONS`]; add new ones at the end.
+
+use serde::Serialize;
+use uesave::{Property, Save, ValueArray};
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_array::find_path;
+use crate::util::clone_via_serde;
+use crate::walk::{properties_at_mut, property_at, split_path};
+
+#[derive(Debug, Serialize)]
+pub struct Migration {
+    /// Stable name, e.g. for logs.
+    pub name: &'static str,
+    pub description: &'static str,
+    #[serde(skip)]
+    needed: fn(&Save) -> bool,
+    #[serde(skip)]
+    apply: fn(&mut Save) -> Result<()>,
+}
+
+impl Migration {
+    /// Whether `save` still has the layout this migration upgrades.
+    pub fn needed(&self, save: &Save) -> bool {
+        (self.needed)(save)
+    }
+}
+
+pub static MIGRATIONS: &[Migration] = &[Migration {
+    name: "forged-schematics",
+    description: "add the `ForgedSchematics` array missing from saves from before forging",
+    needed: forged_schematics_needed,
+    apply: add_forged_schematics,
+}];
+
+/// The migrations `save` needs, in the order they would be applied.
+pub fn pending(save: &Save) -> Vec<&'static Migration> {
+    MIGRATIONS.iter().filter(|migration| migration.needed(save)).collect()
+}
+
+/// Apply every migration `save` needs. Returns the applied migrations.
+pub fn migrate(save: &mut Save) -> Result<Vec<&'static Migration>> {
+    let mut applied = vec![];
+    for migration in MIGRATIONS {
+        if migration.needed(save) {
+            (migration.apply)(save)?;
+            applied.push(migration);
+        }
+    }
+    Ok(applied)
+}
+
+fn forged_schematics_needed(save: &Save) -> bool {
+    let props = &save.root.properties;
+    find_path(props, "OwnedSchematics").is_some() && find_path(props, "ForgedSchematics").is_none()
+}
+
+/// Add an empty `ForgedSchematics` next to `OwnedSchematics`, of the same type.
+fn add_forged_schematics(save: &mut Save) -> Result<()> {
+    let props = &mut save.root.properties;
+    let path = find_path(props, "OwnedSchematics")
+        .ok_or_else(|| DrgSaveError::missing("OwnedSchematics"))?;
+    let mut forged = match property_at(props, &path) {
+        Some(prop @ Property::Array { value: ValueArray::Struct { .. }, .. }) => {
+            clone_via_serde(prop)?
+        }
+        Some(prop) => return Err(DrgSaveError::type_mismatch(path, "Guid Array", prop)),
+        None => return Err(DrgSaveError::missing(path)),
+    };
+    if let Property::Array { value: ValueArray::Struct { _type, value, .. }, .. } = &mut forged {
+        *_type = "ForgedSchematics".to_string();
+        value.clear();
+    }
+
+    let parent = split_path(&path).0;
+    let parent_props = properties_at_mut(props, parent)
+        .ok_or_else(|| DrgSaveError::UnexpectedShape(format!("`{parent}` is not a struct")))?;
+    let (index, key) = parent_props
+        .0
+        .keys()
+        .enumerate()
+        .find(|(_, key)| key.1 == "OwnedSchematics")
+        .map(|(index, key)| (index, uesave::PropertyKey(key.0, "ForgedSchematics".to_string())))
+        .ok_or_else(|| DrgSaveError::missing(path.as_str()))?;
+    parent_props.0.shift_insert(index + 1, key, forged);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::PropertyKey;
+
+    use super::*;
+    use crate::fixtures::synthetic_save;
+    use crate::walk::properties_at;
+
+    #[test]
+    fn adds_forged_schematics_next_to_owned_ones() {
+        assert!(pending(&synthetic_save()).is_empty());
+
+        let mut save = synthetic_save();
+        let path = find_path(&save.root.properties, "ForgedSchematics").unwrap();
+        let (parent, _) = split_path(&path);
+        let parent_props = properties_at_mut(&mut save.root.properties, parent).unwrap();
+        parent_props.0.shift_remove(&PropertyKey::from("ForgedSchematics"));
+        let other = Property::Int { id: None, value: 0 };
+        parent_props.0.insert(PropertyKey::from("Other"), other);
+        let names: Vec<_> = parent_props.0.keys().map(|key| key.1.clone()).collect();
+        let pending: Vec<_> = pending(&save).iter().map(|migration| migration.name).collect();
+        assert_eq!(pending, ["forged-schematics"]);
+
+        assert_eq!(migrate(&mut save).unwrap().len(), 1);
+        assert!(migrate(&mut save).unwrap().is_empty());
+        let parent_props = properties_at(&save.root.properties, parent).unwrap();
+        let owned = names.iter().position(|name| name == "OwnedSchematics").unwrap();
+        let (index, _, forged) =
+            parent_props.0.get_full(&PropertyKey::from("ForgedSchematics")).unwrap();
+        assert_eq!(index, owned + 1);
+        assert!(matches!(
+            forged,
+            Property::Array { value: ValueArray::Struct { value, .. }, .. } if value.is_empty()
+        ));
+        let mut buf = vec![];
+        save.write(&mut buf).unwrap();
+        assert_eq!(crate::parse_save(&buf, std::path::Path::new("save.sav")).unwrap(), save);
+    }
+}
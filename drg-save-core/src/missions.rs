@@ -0,0 +1,110 @@
+//! Weekly deep dive completion flags and completed assignments, where the sample save uesave is
+//! tested with (`drg-save-test.sav`) keeps them: `DeepDiveSave.{NormalSave,EliteSave}` hold the
+//! seed of this week's dive and whether it was completed, and `CampaignSave.CompletedCampaigns`
+//! holds the GUIDs of the completed assignments. The save has no progress of assignments in
+//! progress, so those can only be completed or reset as a whole.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use uesave::{Properties, Property};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_array::{guids, insert_guid, remove_guid};
+use crate::guid_db::GuidDb;
+use crate::walk::{property_at, property_at_mut};
+
+/// GUID array of the completed assignments.
+pub const COMPLETED_ASSIGNMENTS: &str = "CampaignSave.CompletedCampaigns";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeepDive {
+    Normal,
+    Elite,
+}
+
+impl DeepDive {
+    pub const ALL: [Self; 2] = [Self::Normal, Self::Elite];
+
+    /// Path of the `Bool` property set once this week's deep dive is completed.
+    pub fn flag_path(self) -> &'static str {
+        match self {
+            Self::Normal => "DeepDiveSave.NormalSave.HasEverCompleted",
+            Self::Elite => "DeepDiveSave.EliteSave.HasEverCompleted",
+        }
+    }
+}
+
+impl FromStr for DeepDive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "elite" => Ok(Self::Elite),
+            _ => Err(format!("expected `normal` or `elite`, got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for DeepDive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "normal"),
+            Self::Elite => write!(f, "elite"),
+        }
+    }
+}
+
+/// Whether this week's `dive` is completed.
+pub fn deep_dive_completed(props: &Properties, dive: DeepDive) -> Result<bool> {
+    let path = dive.flag_path();
+    match property_at(props, path) {
+        Some(Property::Bool { value, .. }) => Ok(*value),
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, "Bool", prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
+
+/// Mark this week's `dive` as completed or not, returning whether it was before.
+pub fn set_deep_dive_completed(
+    props: &mut Properties,
+    dive: DeepDive,
+    completed: bool,
+) -> Result<bool> {
+    let path = dive.flag_path();
+    match property_at_mut(props, path) {
+        Some(Property::Bool { value, .. }) => Ok(std::mem::replace(value, completed)),
+        Some(prop) => Err(DrgSaveError::type_mismatch(path, "Bool", prop)),
+        None => Err(DrgSaveError::missing(path)),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Assignment {
+    pub guid: Uuid,
+    pub name: Option<String>,
+}
+
+/// The completed assignments.
+pub fn completed_assignments(props: &Properties, db: &GuidDb) -> Result<Vec<Assignment>> {
+    Ok(guids(props, COMPLETED_ASSIGNMENTS)?
+        .into_iter()
+        .map(|guid| Assignment { guid, name: db.name(guid).map(str::to_string) })
+        .collect())
+}
+
+/// Mark the assignment `guid` as completed or not. Returns whether that changed anything.
+pub fn set_assignment_completed(
+    props: &mut Properties,
+    guid: Uuid,
+    completed: bool,
+) -> Result<bool> {
+    if completed {
+        insert_guid(props, COMPLETED_ASSIGNMENTS, guid)
+    } else {
+        remove_guid(props, COMPLETED_ASSIGNMENTS, guid)
+    }
+}
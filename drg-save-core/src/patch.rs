@@ -0,0 +1,245 @@
+//! Changes between saves as [RFC 6902] JSON Patch documents.
+//!
+//! Property paths map onto JSON Pointers segment by segment: `CharacterSaves[0].TimesRetired`
+//! becomes `/CharacterSaves/0/TimesRetired`. Values are whole serialized `Property`s, like in the
+//! journal, so a patch holds everything needed to add a property that didn't exist before.
+//!
+//! Properties are ordered in a save but members of a JSON object aren't, so `add` operations carry
+//! the position of the added property among its siblings as an extra `position` member. RFC 6902
+//! has implementations ignore members it doesn't define, so patches stay standard.
+//!
+//! [RFC 6902]: https://www.rfc-editor.org/rfc/rfc6902
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uesave::{Properties, Property, PropertyKey, Save};
+
+use crate::error::DrgSaveError;
+use crate::journal::property_changes;
+use crate::util::clone_via_serde;
+use crate::walk::{properties_at, properties_at_mut, property_at, property_key, split_path};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add {
+        path: String,
+        value: Value,
+        /// Position among its siblings to add the property at, instead of after them.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<usize>,
+    },
+    Remove {
+        path: String,
+    },
+    Replace {
+        path: String,
+        value: Value,
+    },
+    Move {
+        from: String,
+        path: String,
+    },
+    Copy {
+        from: String,
+        path: String,
+    },
+    Test {
+        path: String,
+        value: Value,
+    },
+}
+
+/// The JSON Patch turning `old` into `new`.
+pub fn json_patch(old: &Save, new: &Save) -> Result<Vec<PatchOperation>> {
+    Ok(property_changes(&old.root.properties, &new.root.properties)?
+        .into_iter()
+        .filter_map(|change| {
+            let path = to_pointer(&change.path);
+            match (change.old, change.new) {
+                (None, Some(value)) => {
+                    let (parent, name) = split_path(&change.path);
+                    let position = properties_at(&new.root.properties, parent)
+                        .and_then(|props| props.0.get_index_of(&property_key(name)));
+                    Some(PatchOperation::Add { path, value, position })
+                }
+                (Some(_), None) => Some(PatchOperation::Remove { path }),
+                (Some(_), Some(value)) => Some(PatchOperation::Replace { path, value }),
+                (None, None) => None,
+            }
+        })
+        .collect())
+}
+
+/// Convert a property path (e.g. `CharacterSaves[0].XP`) to a JSON Pointer
+/// (`/CharacterSaves/0/XP`).
+pub fn to_pointer(path: &str) -> String {
+    let mut pointer = String::new();
+    for segment in path.split('.') {
+        let (name, index) = match segment.strip_suffix(']').and_then(|s| s.split_once('[')) {
+            Some((name, index)) => (name, Some(index)),
+            None => (segment, None),
+        };
+        pointer.push('/');
+        pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+        if let Some(index) = index {
+            pointer.push('/');
+            pointer.push_str(index);
+        }
+    }
+    pointer
+}
+
+/// Convert a JSON Pointer (`/CharacterSaves/0/XP`) to a property path (`CharacterSaves[0].XP`).
+/// Numeric segments are indices into the preceding array.
+pub fn from_pointer(pointer: &str) -> Result<String> {
+    let Some(rest) = pointer.strip_prefix('/') else {
+        bail!(DrgSaveError::InvalidInput(format!("JSON Pointer `{pointer}` must start with `/`")));
+    };
+    let mut path = String::new();
+    for segment in rest.split('/') {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.parse::<usize>().is_ok() && !path.is_empty() {
+            path.push_str(&format!("[{segment}]"));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(&segment);
+        }
+    }
+    Ok(path)
+}
+
+/// Apply the operations of a JSON Patch to `save`, in order. Fails on the first operation that
+/// doesn't apply, e.g. removing a property that isn't there or a failing `test`.
+pub fn apply_patch(save: &mut Save, patch: &[PatchOperation]) -> Result<()> {
+    let props = &mut save.root.properties;
+    for operation in patch {
+        match operation {
+            PatchOperation::Add { path, value, position } => {
+                let path = from_pointer(path)?;
+                let prop = serde_json::from_value(value.clone())
+                    .with_context(|| format!("invalid property for `{path}`"))?;
+                add(props, &path, prop, *position)?;
+            }
+            PatchOperation::Remove { path } => {
+                remove(props, &from_pointer(path)?)?;
+            }
+            PatchOperation::Replace { path, value } => {
+                let path = from_pointer(path)?;
+                let (props, key) = parent(props, &path)?;
+                let Some(existing) = props.0.get_mut(&key) else {
+                    return Err(DrgSaveError::missing(path).into());
+                };
+                *existing = serde_json::from_value(value.clone())
+                    .with_context(|| format!("invalid property for `{path}`"))?;
+            }
+            PatchOperation::Move { from, path } => {
+                let prop = remove(props, &from_pointer(from)?)?;
+                add(props, &from_pointer(path)?, prop, None)?;
+            }
+            PatchOperation::Copy { from, path } => {
+                let from = from_pointer(from)?;
+                let prop = property_at(props, &from).ok_or_else(|| DrgSaveError::missing(&from))?;
+                let prop = clone_via_serde(prop)?;
+                add(props, &from_pointer(path)?, prop, None)?;
+            }
+            PatchOperation::Test { path, value } => {
+                let path = from_pointer(path)?;
+                let actual = property_at(props, &path).map(serde_json::to_value).transpose()?;
+                if actual.as_ref() != Some(value) {
+                    bail!(DrgSaveError::InvalidInput(format!(
+                        "test failed, `{path}` doesn't hold the given value"
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The properties of the struct holding the property at `path`, and the property's key.
+fn parent<'a>(props: &'a mut Properties, path: &str) -> Result<(&'a mut Properties, PropertyKey)> {
+    let (parent, name) = split_path(path);
+    let props = properties_at_mut(props, parent)
+        .with_context(|| format!("`{parent}` not found, cannot apply patch to `{path}`"))?;
+    Ok((props, property_key(name)))
+}
+
+/// Add `prop` at `path` at `position` among its siblings, or after them. A property already at
+/// `path` is replaced where it is.
+fn add(props: &mut Properties, path: &str, prop: Property, position: Option<usize>) -> Result<()> {
+    let (props, key) = parent(props, path)?;
+    match position.filter(|_| !props.0.contains_key(&key)) {
+        Some(position) => props.0.shift_insert(position.min(props.0.len()), key, prop),
+        None => props.0.insert(key, prop),
+    };
+    Ok(())
+}
+
+fn remove(props: &mut Properties, path: &str) -> Result<Property> {
+    let (props, key) = parent(props, path)?;
+    props.0.shift_remove(&key).ok_or_else(|| DrgSaveError::missing(path).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::synthetic_save;
+
+    fn names(save: &Save) -> Vec<String> {
+        save.root.properties.0.keys().map(|key| key.1.clone()).collect()
+    }
+
+    #[test]
+    fn applied_patches_keep_property_order() {
+        let new = synthetic_save();
+        let mut old = synthetic_save();
+        let props = &mut old.root.properties.0;
+        props.shift_remove_index(3);
+        props.shift_remove_index(1);
+
+        let patch = json_patch(&old, &new).unwrap();
+        let patch: Vec<PatchOperation> =
+            serde_json::from_str(&serde_json::to_string(&patch).unwrap()).unwrap();
+        apply_patch(&mut old, &patch).unwrap();
+        assert_eq!(names(&old), names(&new));
+    }
+
+    #[test]
+    fn applies_test_move_and_copy() {
+        let mut save = synthetic_save();
+        let credits = serde_json::to_value(property_at(&save.root.properties, "Credits")).unwrap();
+        let patch: Vec<PatchOperation> = serde_json::from_value(serde_json::json!([
+            { "op": "test", "path": "/Credits", "value": credits },
+            { "op": "copy", "from": "/Credits", "path": "/Copied" },
+            { "op": "move", "from": "/Credits", "path": "/Moved" },
+        ]))
+        .unwrap();
+        apply_patch(&mut save, &patch).unwrap();
+        let props = &save.root.properties;
+        assert!(property_at(props, "Credits").is_none());
+        for path in ["Copied", "Moved"] {
+            assert_eq!(serde_json::to_value(property_at(props, path)).unwrap(), credits);
+        }
+
+        let failing = [PatchOperation::Test { path: "/Moved".to_string(), value: Value::Null }];
+        assert!(apply_patch(&mut save, &failing).is_err());
+    }
+
+    #[test]
+    fn pointers_round_trip() {
+        for (path, pointer) in [
+            ("Credits", "/Credits"),
+            ("CharacterSaves[0].XP", "/CharacterSaves/0/XP"),
+            ("Perks[2].Slots[10].Ammo#1", "/Perks/2/Slots/10/Ammo#1"),
+            ("Odd/Name~", "/Odd~1Name~0"),
+        ] {
+            assert_eq!(to_pointer(path), pointer);
+            assert_eq!(from_pointer(pointer).unwrap(), path);
+        }
+        assert!(from_pointer("Credits").is_err());
+    }
+}
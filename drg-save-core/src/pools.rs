@@ -0,0 +1,183 @@
+//! What lost packs and cargo crates can still drop. The save doesn't store their pools: the game
+//! draws from the schematics not obtained yet, i.e. neither in `SchematicSave.OwnedSchematics`
+//! (obtained, not forged yet) nor in `SchematicSave.ForgedSchematics`, as in the sample save uesave
+//! is tested with (`drg-save-test.sav`). A schematic can end up in both, e.g. after merging saves,
+//! which is what pruning the pools removes. Repeats within one array are left alone, since the game
+//! writes those itself: the sample save's `OwnedSchematics` holds 86 entries of 7 schematics.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use uesave::{Properties, StructValue};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_array::{guid_array_mut, guids};
+use crate::guid_db::{GuidDb, GuidKind};
+
+/// GUID array of the schematics obtained but not forged yet.
+const OWNED_SCHEMATICS: &str = "SchematicSave.OwnedSchematics";
+/// GUID array of the forged schematics.
+const FORGED_SCHEMATICS: &str = "SchematicSave.ForgedSchematics";
+
+#[derive(Debug, Serialize)]
+pub struct PoolEntry {
+    pub guid: Uuid,
+    pub name: String,
+}
+
+/// The schematics in `db` not obtained yet, which lost packs and cargo crates can still drop.
+pub fn list_pool(props: &Properties, db: &GuidDb) -> Result<Vec<PoolEntry>> {
+    db.require(GuidKind::Schematic)?;
+    let obtained = obtained_schematics(props)?;
+    Ok(db
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == GuidKind::Schematic && !obtained.contains(&entry.guid))
+        .map(|entry| PoolEntry { guid: entry.guid, name: entry.name.clone() })
+        .collect())
+}
+
+#[derive(Debug, Serialize)]
+pub struct Duplicate {
+    pub guid: Uuid,
+    /// Number of its entries in `OwnedSchematics`, all of which pruning removes.
+    pub count: usize,
+}
+
+/// The schematics pruning removes: forged ones still in `OwnedSchematics`, once each.
+pub fn duplicates(props: &Properties) -> Result<Vec<Duplicate>> {
+    let forged: HashSet<Uuid> = forged_schematics(props)?.into_iter().collect();
+    let mut duplicates: Vec<Duplicate> = vec![];
+    for guid in guids(props, OWNED_SCHEMATICS)?.into_iter().filter(|guid| forged.contains(guid)) {
+        match duplicates.iter_mut().find(|duplicate| duplicate.guid == guid) {
+            Some(duplicate) => duplicate.count += 1,
+            None => duplicates.push(Duplicate { guid, count: 1 }),
+        }
+    }
+    Ok(duplicates)
+}
+
+/// Remove the [`duplicates`] from `OwnedSchematics`, keeping them forged. Returns what was
+/// removed.
+pub fn prune_pools(props: &mut Properties) -> Result<Vec<Duplicate>> {
+    let removed = duplicates(props)?;
+    if !removed.is_empty() {
+        guid_array_mut(props, OWNED_SCHEMATICS)?.retain(|value| {
+            !matches!(value, StructValue::Guid(guid)
+                if removed.iter().any(|duplicate| duplicate.guid == *guid))
+        });
+    }
+    Ok(removed)
+}
+
+/// Saves that predate forging have no `ForgedSchematics`, so a missing array counts as empty. One
+/// of another type is an error like anywhere else.
+fn forged_schematics(props: &Properties) -> Result<Vec<Uuid>> {
+    match guids(props, FORGED_SCHEMATICS) {
+        Err(DrgSaveError::MissingProperty { .. }) => Ok(vec![]),
+        result => result,
+    }
+}
+
+fn obtained_schematics(props: &Properties) -> Result<Vec<Uuid>> {
+    let mut obtained = guids(props, OWNED_SCHEMATICS)?;
+    obtained.extend(forged_schematics(props)?);
+    Ok(obtained)
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::{Property, PropertyType};
+
+    use super::*;
+    use crate::fixtures::{
+        fixture_guid_db, sample_save, synthetic_save, FORGED_SCHEMATIC, UNFORGED_SCHEMATICS,
+    };
+    use crate::walk::property_at_mut;
+
+    #[test]
+    fn pool_is_what_is_not_obtained() {
+        let save = synthetic_save();
+        let props = &save.root.properties;
+        assert!(list_pool(props, &GuidDb::default()).is_err());
+
+        let mut db = fixture_guid_db();
+        let unobtained = Uuid::from_u128(0x601);
+        db.entries.push(crate::guid_db::GuidEntry {
+            guid: unobtained,
+            kind: GuidKind::Schematic,
+            name: "Test Overclock 4".to_string(),
+            dlc: None,
+            weapon: None,
+            class: None,
+            tier: None,
+            slot: None,
+            default: false,
+        });
+        let pool = list_pool(props, &db).unwrap();
+        assert_eq!(pool.iter().map(|entry| entry.guid).collect::<Vec<_>>(), [unobtained]);
+    }
+
+    #[test]
+    fn prunes_forged_entries_still_owned() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        assert!(duplicates(props).unwrap().is_empty());
+
+        // Repeats within an array are how the game writes them.
+        guid_array_mut(props, OWNED_SCHEMATICS)
+            .unwrap()
+            .push(StructValue::Guid(UNFORGED_SCHEMATICS[0]));
+        guid_array_mut(props, FORGED_SCHEMATICS).unwrap().push(StructValue::Guid(FORGED_SCHEMATIC));
+        assert!(duplicates(props).unwrap().is_empty());
+
+        // Forged, but still in the owned array too.
+        for _ in 0..2 {
+            guid_array_mut(props, OWNED_SCHEMATICS)
+                .unwrap()
+                .push(StructValue::Guid(FORGED_SCHEMATIC));
+        }
+        let removed: Vec<_> =
+            prune_pools(props).unwrap().into_iter().map(|dup| (dup.guid, dup.count)).collect();
+        assert_eq!(removed, [(FORGED_SCHEMATIC, 2)]);
+        assert_eq!(
+            guids(props, OWNED_SCHEMATICS).unwrap(),
+            [UNFORGED_SCHEMATICS[0], UNFORGED_SCHEMATICS[1], UNFORGED_SCHEMATICS[0]]
+        );
+        assert_eq!(guids(props, FORGED_SCHEMATICS).unwrap(), [FORGED_SCHEMATIC, FORGED_SCHEMATIC]);
+        assert!(duplicates(props).unwrap().is_empty());
+    }
+
+    #[test]
+    fn keeps_repeats_in_the_sample_save() {
+        let mut save = sample_save();
+        let props = &mut save.root.properties;
+        let owned = guids(props, OWNED_SCHEMATICS).unwrap();
+        let forged = guids(props, FORGED_SCHEMATICS).unwrap();
+        // 86 entries of 7 schematics, one of which is forged as well.
+        assert_eq!((owned.len(), owned.iter().collect::<HashSet<_>>().len()), (86, 7));
+        let both = Uuid::from_u128(0x79cc3bb4_4966_7f09_ea55_65b3512fb36a);
+
+        let removed: Vec<_> =
+            prune_pools(props).unwrap().into_iter().map(|dup| (dup.guid, dup.count)).collect();
+        assert_eq!(removed, [(both, 1)]);
+        let pruned: Vec<_> = owned.into_iter().filter(|guid| *guid != both).collect();
+        assert_eq!(guids(props, OWNED_SCHEMATICS).unwrap(), pruned);
+        assert_eq!(guids(props, FORGED_SCHEMATICS).unwrap(), forged);
+    }
+
+    #[test]
+    fn wrongly_typed_forged_schematics_are_an_error() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        *property_at_mut(props, FORGED_SCHEMATICS).unwrap() = Property::Map {
+            key_type: PropertyType::StructProperty,
+            value_type: PropertyType::IntProperty,
+            id: None,
+            value: vec![],
+        };
+        assert!(duplicates(props).is_err());
+        assert!(prune_pools(props).is_err());
+    }
+}
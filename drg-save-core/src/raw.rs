@@ -0,0 +1,252 @@
+//! Byte-level access to properties, for struct types none of the other tools model.
+//!
+//! A property is addressed by its path like everywhere else, and its bytes are its value as
+//! serialized in the save, without the header with its name, type and size. Replacement bytes have
+//! to be as long as the current ones, so the size in the header stays right.
+
+use std::io::Cursor;
+use std::ops::Range;
+
+use uesave::{Properties, Property, PropertyKey, Root, Save};
+
+use crate::error::{DrgSaveError, Result};
+use crate::util::clone_via_serde;
+use crate::walk::{property_at, property_at_mut, split_path};
+
+/// The serialized value of the property at `path` of `save`.
+pub fn property_bytes(save: &Save, path: &str) -> Result<Vec<u8>> {
+    let (buf, value) = serialize_alone(save, path)?;
+    Ok(buf[value].to_vec())
+}
+
+/// Replace the property at `path` of `save` with the property its serialized value `bytes`
+/// describe. `bytes` has to be as long as the current value. If `expected` is given, the current
+/// value has to be it, to catch patches meant for a different version of the save.
+pub fn set_property_bytes(
+    save: &mut Save,
+    path: &str,
+    bytes: &[u8],
+    expected: Option<&[u8]>,
+) -> Result<()> {
+    let (mut buf, value) = serialize_alone(save, path)?;
+    let current = &buf[value.clone()];
+    if bytes.len() != current.len() {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "`{path}` is {} bytes, not {}",
+            current.len(),
+            bytes.len()
+        )));
+    }
+    if let Some(expected) = expected {
+        if current != expected {
+            return Err(DrgSaveError::InvalidInput(format!(
+                "`{path}` is {}, expected {}",
+                hex::encode(current),
+                hex::encode(expected)
+            )));
+        }
+    }
+    buf[value].copy_from_slice(bytes);
+    let parsed = Save::read(&mut Cursor::new(&buf)).map_err(|e| {
+        DrgSaveError::InvalidInput(format!("`{path}` can't be parsed with these bytes: {e}"))
+    })?;
+    let Some((_, prop)) = parsed.root.properties.0.into_iter().next() else {
+        return Err(DrgSaveError::UnexpectedShape(format!("`{path}` vanished when parsed")));
+    };
+    *property_at_mut(&mut save.root.properties, path)
+        .ok_or_else(|| DrgSaveError::missing(path))? = prop;
+    Ok(())
+}
+
+/// `save` serialized with only the property at `path` at the top level, and where its value is.
+fn serialize_alone(save: &Save, path: &str) -> Result<(Vec<u8>, Range<usize>)> {
+    let prop =
+        property_at(&save.root.properties, path).ok_or_else(|| DrgSaveError::missing(path))?;
+    let (_, name) = split_path(path);
+    let mut properties = Properties::default();
+    properties.0.insert(PropertyKey(0, name.to_string()), clone_via_serde::<Property>(prop)?);
+    let alone = Save {
+        header: clone_via_serde(&save.header)?,
+        root: Root { save_game_type: save.root.save_game_type.clone(), properties },
+        extra: vec![],
+    };
+    let mut buf = vec![];
+    alone
+        .write(&mut buf)
+        .map_err(|e| DrgSaveError::UnexpectedShape(format!("`{path}` can't be serialized: {e}")))?;
+    let value = layout(&buf)?
+        .properties
+        .pop()
+        .ok_or_else(|| DrgSaveError::UnexpectedShape(format!("`{path}` vanished when serialized")))?
+        .value;
+    Ok((buf, value))
+}
+
+/// Where the top-level properties of a serialized save are.
+#[derive(Debug)]
+pub(crate) struct Layout {
+    pub properties: Vec<PropertySpan>,
+    /// Offset of the `None` ending the properties.
+    pub end: usize,
+}
+
+/// Where a serialized property is.
+#[derive(Debug)]
+pub(crate) struct PropertySpan {
+    pub key: PropertyKey,
+    /// The whole property, header included.
+    pub range: Range<usize>,
+    /// The value, as counted by the size in the header.
+    pub value: Range<usize>,
+}
+
+/// Find the top-level properties of the serialized save `buf` from their headers, without parsing
+/// their values.
+pub(crate) fn layout(buf: &[u8]) -> Result<Layout> {
+    let mut reader = Reader { buf, pos: 0 };
+    // Header: magic, save game version, package version(s), engine version, custom versions.
+    reader.skip(4)?;
+    let save_game_version = reader.u32()?;
+    reader.skip(if save_game_version >= 3 { 8 } else { 4 })?;
+    reader.skip(10)?;
+    reader.string()?;
+    reader.skip(4)?;
+    let n_custom_versions = reader.u32()? as usize;
+    reader.skip(n_custom_versions.checked_mul(20).ok_or_else(truncated)?)?;
+    // Root: save game type, then the properties up to `None`.
+    reader.string()?;
+    let mut properties = vec![];
+    loop {
+        let start = reader.pos;
+        let name = reader.string()?;
+        if name == "None" {
+            return Ok(Layout { properties, end: start });
+        }
+        let property_type = reader.string()?;
+        let size = reader.u32()? as usize;
+        let index = reader.u32()?;
+        match property_type.as_str() {
+            "BoolProperty" => reader.skip(1)?,
+            "ByteProperty" | "EnumProperty" | "ArrayProperty" | "SetProperty" => {
+                reader.string()?;
+            }
+            "StructProperty" => {
+                reader.string()?;
+                reader.skip(16)?;
+            }
+            "MapProperty" => {
+                reader.string()?;
+                reader.string()?;
+            }
+            "Int8Property"
+            | "Int16Property"
+            | "IntProperty"
+            | "Int64Property"
+            | "UInt8Property"
+            | "UInt16Property"
+            | "UInt32Property"
+            | "UInt64Property"
+            | "FloatProperty"
+            | "DoubleProperty"
+            | "NameProperty"
+            | "StrProperty"
+            | "FieldPathProperty"
+            | "SoftObjectProperty"
+            | "ObjectProperty"
+            | "TextProperty"
+            | "DelegateProperty"
+            | "MulticastDelegateProperty"
+            | "MulticastInlineDelegateProperty"
+            | "MulticastSparseDelegateProperty" => {}
+            _ => {
+                return Err(DrgSaveError::UnexpectedShape(format!(
+                    "`{name}` is of unknown type `{property_type}`"
+                )))
+            }
+        }
+        if reader.u8()? != 0 {
+            reader.skip(16)?;
+        }
+        let value = reader.pos..reader.pos;
+        reader.skip(size)?;
+        properties.push(PropertySpan {
+            key: PropertyKey(index, name),
+            range: start..reader.pos,
+            value: value.start..reader.pos,
+        });
+    }
+}
+
+fn truncated() -> DrgSaveError {
+    DrgSaveError::UnexpectedShape("save ends in the middle of a property".to_string())
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.buf.len());
+        let bytes = &self.buf[self.pos..end.ok_or_else(truncated)?];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.take(len).map(drop)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// An `FString`: a length, negative for UTF-16, and the NUL-terminated characters.
+    fn string(&mut self) -> Result<String> {
+        let len = self.u32()? as i32;
+        let string = if len < 0 {
+            let bytes = self.take(len.unsigned_abs() as usize * 2)?;
+            let chars: Vec<_> =
+                bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&chars)
+        } else {
+            String::from_utf8_lossy(self.take(len as usize)?).into_owned()
+        };
+        Ok(string.trim_end_matches('\0').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::synthetic_save;
+    use crate::util::int_mut;
+
+    #[test]
+    fn gets_and_sets_the_value_of_a_property() {
+        let mut save = synthetic_save();
+        let credits = property_bytes(&save, "Credits").unwrap();
+        assert_eq!(credits, 123_456i32.to_le_bytes());
+
+        let patched = 42i32.to_le_bytes();
+        set_property_bytes(&mut save, "Credits", &patched, Some(&credits)).unwrap();
+        assert_eq!(*int_mut(&mut save.root.properties, "Credits").unwrap(), 42);
+
+        assert!(set_property_bytes(&mut save, "Credits", &patched, Some(&credits)).is_err());
+        assert!(set_property_bytes(&mut save, "Credits", &[0], None).is_err());
+        assert!(matches!(property_bytes(&save, "Nope"), Err(DrgSaveError::MissingProperty { .. })));
+    }
+
+    #[test]
+    fn round_trips_nested_structured_values() {
+        let mut save = synthetic_save();
+        let seasons = property_bytes(&save, "SeasonSave.Seasons").unwrap();
+        set_property_bytes(&mut save, "SeasonSave.Seasons", &seasons, Some(&seasons)).unwrap();
+        assert_eq!(save, synthetic_save());
+    }
+}
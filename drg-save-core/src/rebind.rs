@@ -0,0 +1,121 @@
+//! Rewriting the account identifiers in a save to another account's, so a save copied to a
+//! different account is accepted by the game.
+//!
+//! Identifiers are found like in [`crate::anonymize`]: any string that looks like a Steam64 ID,
+//! and 64-bit integers in account-related properties holding one. Saves can mention other players'
+//! IDs too, so only the ID of the account the save belongs to is replaced.
+
+use std::path::Path;
+
+use uesave::{Property, Save, ValueArray, ValueVec};
+
+use crate::anonymize::{is_identifying_name, is_steam_id};
+use crate::error::{DrgSaveError, Result};
+use crate::walk::walk_properties_mut;
+
+/// The Steam64 ID a save file is named after, e.g. `76561198000000000` for
+/// `76561198000000000_Player.sav`: the account the save belongs to.
+pub fn steam_id_in_file_name(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    let (id, _) = name.split_once('_')?;
+    is_steam_id(id).then(|| id.parse().ok()).flatten()
+}
+
+/// Replace the Steam ID `from` in `save` with `to`. Returns the paths of the changed properties.
+pub fn rebind(save: &mut Save, to: u64, from: u64) -> Result<Vec<String>> {
+    let to_string = to.to_string();
+    let from_string = from.to_string();
+    for id in [&to_string, &from_string] {
+        if !is_steam_id(id) {
+            return Err(DrgSaveError::InvalidInput(format!("`{id}` is not a Steam64 ID")));
+        }
+    }
+    let matches = |id: &str| id == from_string;
+
+    let mut changed = vec![];
+    walk_properties_mut(&mut save.root.properties, &mut |path, prop| {
+        let name = path.rsplit('.').next().unwrap_or(path);
+        let rebind_string = |s: &mut String| {
+            if matches(s) && *s != to_string {
+                *s = to_string.clone();
+                return true;
+            }
+            false
+        };
+        let rebound = match prop {
+            Property::Str { value, .. } | Property::Name { value, .. } => rebind_string(value),
+            Property::Array {
+                value: ValueArray::Base(ValueVec::Str(values) | ValueVec::Name(values)),
+                ..
+            } => values.iter_mut().fold(false, |rebound, s| rebind_string(s) | rebound),
+            Property::UInt64 { value, .. }
+                if is_identifying_name(name) && matches(&value.to_string()) && *value != to =>
+            {
+                *value = to;
+                true
+            }
+            Property::Int64 { value, .. }
+                if is_identifying_name(name)
+                    && matches(&value.to_string())
+                    && u64::try_from(*value) != Ok(to) =>
+            {
+                // Steam64 IDs fit in an i64, checked by `is_steam_id` above.
+                *value = to as i64;
+                true
+            }
+            _ => false,
+        };
+        if rebound {
+            changed.push(path.to_string());
+        }
+    });
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::PropertyKey;
+
+    use super::*;
+    use crate::fixtures::synthetic_save;
+    use crate::walk::property_at;
+
+    const OWNER: u64 = 76561198000000000;
+    const FRIEND: u64 = 76561198000000001;
+    const NEW_OWNER: u64 = 76561198000000002;
+
+    fn str_property(value: u64) -> Property {
+        Property::Str { id: None, value: value.to_string() }
+    }
+
+    #[test]
+    fn only_the_owner_is_rebound() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties.0;
+        props.insert(PropertyKey::from("OwnerID"), str_property(OWNER));
+        props.insert(PropertyKey::from("LastPartyMember"), str_property(FRIEND));
+
+        let changed = rebind(&mut save, NEW_OWNER, OWNER).unwrap();
+        assert_eq!(changed, ["OwnerID"]);
+        let value = |path| match property_at(&save.root.properties, path) {
+            Some(Property::Str { value, .. }) => value.clone(),
+            prop => panic!("unexpected {prop:?}"),
+        };
+        assert_eq!(value("OwnerID"), NEW_OWNER.to_string());
+        assert_eq!(value("LastPartyMember"), FRIEND.to_string());
+    }
+
+    #[test]
+    fn rejects_non_steam_ids() {
+        assert!(rebind(&mut synthetic_save(), 42, OWNER).is_err());
+        assert!(rebind(&mut synthetic_save(), NEW_OWNER, 42).is_err());
+    }
+
+    #[test]
+    fn steam_id_from_file_name() {
+        let id = |name| steam_id_in_file_name(Path::new(name));
+        assert_eq!(id("saves/76561198000000000_Player.sav"), Some(OWNER));
+        assert_eq!(id("76561198000000000.sav"), None);
+        assert_eq!(id("Player_Backup.sav"), None);
+    }
+}
@@ -0,0 +1,232 @@
+//! Human-readable overview of a save, rendered as Markdown or a standalone HTML page, for sharing
+//! progress or documenting a save before editing it.
+
+use serde::Serialize;
+use uesave::{Property, PropertyValue, Save, StructValue};
+
+use crate::blue_level::{blue_level, red_levels};
+use crate::classes::{class_saves, savegame_id, HIDDEN_CLASS_SAVEGAME_ID};
+use crate::error::Result;
+use crate::guid_array::guids;
+use crate::guid_db::GuidDb;
+use crate::season::challenges;
+use crate::walk::walk_properties;
+use crate::xp::level_for_xp;
+
+#[derive(Debug, Serialize)]
+pub struct SaveReport {
+    pub blue_level: i32,
+    /// The active classes; the hidden class is left out.
+    pub classes: Vec<ClassReport>,
+    pub credits: Option<i32>,
+    pub resources: Vec<ResourceReport>,
+    /// Overclocks (schematics) owned but not forged yet.
+    pub unforged_overclocks: usize,
+    pub forged_overclocks: usize,
+    pub completed_challenges: usize,
+    pub challenges: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassReport {
+    pub name: String,
+    pub level: u8,
+    pub promotions: i32,
+    pub red_levels: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceReport {
+    pub name: String,
+    pub amount: f32,
+}
+
+/// Gather the overview of `save`, naming GUIDs with `db`.
+pub fn report(save: &Save, db: &GuidDb) -> Result<SaveReport> {
+    let class_saves = class_saves(save)?;
+    let mut classes = vec![];
+    for class_save in class_saves {
+        let id = savegame_id(class_save);
+        let StructValue::Struct(props) = class_save else {
+            continue;
+        };
+        if id == Some(HIDDEN_CLASS_SAVEGAME_ID) {
+            continue;
+        }
+        let int = |name| match crate::property(props, name) {
+            Some(Property::Int { value, .. }) => *value,
+            _ => 0,
+        };
+        classes.push(ClassReport {
+            name: id.map_or_else(|| "unknown class".to_string(), |id| db.label(id)),
+            level: level_for_xp(int("XP")).0,
+            promotions: int("TimesRetired"),
+            red_levels: red_levels(class_save)?,
+        });
+    }
+
+    let props = &save.root.properties;
+    let mut credits = None;
+    let mut resources = vec![];
+    walk_properties(props, &mut |path, prop| match prop {
+        Property::Int { value, .. } if path == "Credits" || path.ends_with(".Credits") => {
+            credits = Some(*value)
+        }
+        Property::Map { value, .. } if path.ends_with("OwnedResources") => {
+            for entry in value {
+                if let (
+                    PropertyValue::Struct(StructValue::Guid(guid)),
+                    PropertyValue::Float(amount),
+                ) = (&entry.key, &entry.value)
+                {
+                    resources.push(ResourceReport { name: db.label(*guid), amount: *amount });
+                }
+            }
+        }
+        _ => {}
+    });
+
+    let challenges = challenges(props, db);
+    Ok(SaveReport {
+        blue_level: blue_level(class_saves)?,
+        classes,
+        credits,
+        resources,
+        unforged_overclocks: guids(props, "OwnedSchematics").map_or(0, |guids| guids.len()),
+        forged_overclocks: guids(props, "ForgedSchematics").map_or(0, |guids| guids.len()),
+        completed_challenges: challenges.iter().filter(|challenge| challenge.completed).count(),
+        challenges: challenges.len(),
+    })
+}
+
+impl SaveReport {
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        md.push_str("# Save report\n\n");
+        md.push_str(&format!("Blue level: **{}**\n\n", self.blue_level));
+        md.push_str("## Classes\n\n");
+        md.push_str("| Class | Level | Promotions | Red levels |\n");
+        md.push_str("| --- | ---: | ---: | ---: |\n");
+        for class in &self.classes {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                class.name, class.level, class.promotions, class.red_levels
+            ));
+        }
+        md.push_str("\n## Resources\n\n");
+        if let Some(credits) = self.credits {
+            md.push_str(&format!("- Credits: {credits}\n"));
+        }
+        for resource in &self.resources {
+            md.push_str(&format!("- {}: {}\n", resource.name, resource.amount));
+        }
+        md.push_str("\n## Progress\n\n");
+        md.push_str(&format!(
+            "- Overclocks: {} forged, {} unforged\n",
+            self.forged_overclocks, self.unforged_overclocks
+        ));
+        md.push_str(&format!(
+            "- Season challenges: {} of {} completed\n",
+            self.completed_challenges, self.challenges
+        ));
+        md
+    }
+
+    /// A standalone HTML page with the same content as [`SaveReport::to_markdown`].
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>Save report</title>\n");
+        html.push_str(
+            "<style>body { font-family: sans-serif; } td, th { padding: 0 1em; }</style>\n",
+        );
+        html.push_str("</head>\n<body>\n<h1>Save report</h1>\n");
+        html.push_str(&format!("<p>Blue level: <strong>{}</strong></p>\n", self.blue_level));
+        html.push_str("<h2>Classes</h2>\n<table>\n");
+        html.push_str(
+            "<tr><th>Class</th><th>Level</th><th>Promotions</th><th>Red levels</th></tr>\n",
+        );
+        for class in &self.classes {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape(&class.name),
+                class.level,
+                class.promotions,
+                class.red_levels
+            ));
+        }
+        html.push_str("</table>\n<h2>Resources</h2>\n<ul>\n");
+        if let Some(credits) = self.credits {
+            html.push_str(&format!("<li>Credits: {credits}</li>\n"));
+        }
+        for resource in &self.resources {
+            html.push_str(&format!("<li>{}: {}</li>\n", escape(&resource.name), resource.amount));
+        }
+        html.push_str("</ul>\n<h2>Progress</h2>\n<ul>\n");
+        html.push_str(&format!(
+            "<li>Overclocks: {} forged, {} unforged</li>\n",
+            self.forged_overclocks, self.unforged_overclocks
+        ));
+        html.push_str(&format!(
+            "<li>Season challenges: {} of {} completed</li>\n",
+            self.completed_challenges, self.challenges
+        ));
+        html.push_str("</ul>\n</body>\n</html>\n");
+        html
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fixture_guid_db, synthetic_save, with_guid_db};
+
+    /// The report of [`synthetic_save`], with the classes named by the embedded dataset.
+    fn synthetic_report() -> SaveReport {
+        let db = with_guid_db(fixture_guid_db(), GuidDb::load).unwrap();
+        report(&synthetic_save(), &db).unwrap()
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let report = synthetic_report();
+        let driller = &report.classes[0];
+        assert_eq!((driller.name.as_str(), driller.promotions), ("Driller", 3));
+        assert_eq!(report.classes.len(), 4);
+
+        let md = report.to_markdown();
+        assert!(md.starts_with("# Save report\n\n"));
+        assert!(md.contains(&format!("Blue level: **{}**\n", report.blue_level)));
+        assert!(md.contains(&format!(
+            "| Driller | {} | 3 | {} |\n",
+            level_for_xp(50_000).0,
+            driller.red_levels
+        )));
+        for line in [
+            "- Credits: 123456\n",
+            "- Test Resource A: 100\n",
+            "- Test Resource B: 2.5\n",
+            "- Overclocks: 1 forged, 2 unforged\n",
+        ] {
+            assert!(md.contains(line), "`{line}` is missing from:\n{md}");
+        }
+    }
+
+    #[test]
+    fn renders_escaped_html() {
+        let mut report = synthetic_report();
+        report.classes[0].name = "<b>Driller</b> & co".to_string();
+
+        let html = report.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.ends_with("</html>\n"));
+        assert!(html.contains("<td>&lt;b&gt;Driller&lt;/b&gt; &amp; co</td>"));
+        assert!(!html.contains("<b>Driller"));
+        assert!(html.contains("<li>Credits: 123456</li>"));
+        assert!(html.contains("<li>Overclocks: 1 forged, 2 unforged</li>"));
+    }
+}
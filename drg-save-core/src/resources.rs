@@ -0,0 +1,171 @@
+//! Access to the `OwnedResources` map: minerals, brewing ingredients and the "miscellaneous"
+//! resources (error cubes, data cells, blank matrix cores, phazyonite).
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use uesave::{MapEntry, Properties, Property, PropertyValue, StructValue};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::{GuidDb, GuidKind};
+use crate::walk::{walk_properties, walk_properties_mut};
+
+/// Resources the game only adds to `OwnedResources` once they are first earned, by their name in
+/// the [`GuidDb`]. Their GUIDs come from an installed dataset, the embedded one has no resources.
+pub const SPECIAL_RESOURCES: [&str; 4] =
+    ["Error Cube", "Data Cell", "Blank Matrix Core", "Phazyonite"];
+
+/// A resource, given either by its GUID or by its name in the [`GuidDb`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceSelector {
+    Guid(Uuid),
+    Name(String),
+}
+
+impl FromStr for ResourceSelector {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse() {
+            Ok(guid) => Self::Guid(guid),
+            Err(_) => Self::Name(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ResourceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Guid(guid) => write!(f, "resource {guid}"),
+            Self::Name(name) => write!(f, "resource `{name}`"),
+        }
+    }
+}
+
+/// Find the GUID `selector` refers to.
+pub fn resolve_resource(selector: &ResourceSelector, db: &GuidDb) -> Result<Uuid> {
+    match selector {
+        ResourceSelector::Guid(guid) => Ok(*guid),
+        ResourceSelector::Name(name) => {
+            db.require(GuidKind::Resource)?;
+            db.find(GuidKind::Resource, name).ok_or_else(|| {
+                DrgSaveError::InvalidInput(format!(
+                    "unknown resource `{name}`, give its GUID or install a GUID database that has \
+                     it"
+                ))
+            })
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OwnedResource {
+    pub guid: Uuid,
+    pub name: Option<String>,
+    pub amount: f32,
+}
+
+/// The amounts in the `OwnedResources` map below `props`, followed by the [`SPECIAL_RESOURCES`]
+/// `db` knows but the save doesn't hold yet, at 0.
+pub fn owned_resources(props: &Properties, db: &GuidDb) -> Vec<OwnedResource> {
+    let mut resources = vec![];
+    walk_properties(props, &mut |path, prop| {
+        let Property::Map { value, .. } = prop else {
+            return;
+        };
+        if !path.ends_with("OwnedResources") {
+            return;
+        }
+        for entry in value {
+            if let (PropertyValue::Struct(StructValue::Guid(guid)), PropertyValue::Float(amount)) =
+                (&entry.key, &entry.value)
+            {
+                resources.push(OwnedResource {
+                    guid: *guid,
+                    name: db.name(*guid).map(str::to_string),
+                    amount: *amount,
+                });
+            }
+        }
+    });
+    for name in SPECIAL_RESOURCES {
+        if let Some(guid) = db.find(GuidKind::Resource, name) {
+            if !resources.iter().any(|resource| resource.guid == guid) {
+                resources.push(OwnedResource { guid, name: Some(name.to_string()), amount: 0.0 });
+            }
+        }
+    }
+    resources
+}
+
+/// Set the amount of `guid` in the `OwnedResources` map below `props`, inserting the map entry
+/// if the save has never held that resource. Returns the previous amount, if there was one.
+pub fn set_resource(props: &mut Properties, guid: Uuid, amount: f32) -> Result<Option<f32>> {
+    let mut result = Err(DrgSaveError::missing("OwnedResources"));
+    walk_properties_mut(props, &mut |path, prop| {
+        let Property::Map { value: entries, .. } = prop else {
+            return;
+        };
+        if result.is_ok() || !path.ends_with("OwnedResources") {
+            return;
+        }
+        result = set_entry(entries, guid, amount).map_err(|found| {
+            DrgSaveError::UnexpectedShape(format!(
+                "expected a Float amount for {guid} in `{path}`, found {found}"
+            ))
+        });
+    });
+    result
+}
+
+fn set_entry(
+    entries: &mut Vec<MapEntry>,
+    guid: Uuid,
+    amount: f32,
+) -> std::result::Result<Option<f32>, String> {
+    let existing = entries.iter_mut().find(
+        |entry| matches!(entry.key, PropertyValue::Struct(StructValue::Guid(key)) if key == guid),
+    );
+    match existing {
+        Some(MapEntry { value: PropertyValue::Float(old), .. }) => {
+            let old = std::mem::replace(old, amount);
+            Ok(Some(old))
+        }
+        Some(entry) => Err(format!("{:?}", entry.value)),
+        None => {
+            entries.push(MapEntry {
+                key: PropertyValue::Struct(StructValue::Guid(guid)),
+                value: PropertyValue::Float(amount),
+            });
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fixture_guid_db, synthetic_save, ERROR_CUBE, RESOURCE_A, RESOURCE_B};
+
+    #[test]
+    fn lists_special_resources_not_held_yet() {
+        let save = synthetic_save();
+        let resources = owned_resources(&save.root.properties, &fixture_guid_db());
+        let amounts: Vec<_> =
+            resources.iter().map(|resource| (resource.guid, resource.amount)).collect();
+        assert_eq!(amounts, [(RESOURCE_A, 100.0), (RESOURCE_B, 2.5), (ERROR_CUBE, 0.0)]);
+        assert_eq!(resources[2].name.as_deref(), Some("Error Cube"));
+    }
+
+    #[test]
+    fn names_need_resources_in_the_database() {
+        let error_cube = ResourceSelector::Name("error cube".to_string());
+        assert_eq!(resolve_resource(&error_cube, &fixture_guid_db()).unwrap(), ERROR_CUBE);
+        assert!(matches!(
+            resolve_resource(&error_cube, &GuidDb::default()),
+            Err(DrgSaveError::MissingGuidData { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+//! Copying selected properties back from a backup, so reverting one experiment doesn't throw away
+//! unrelated progress made since the backup.
+
+use uesave::Save;
+
+use crate::error::{DrgSaveError, Result};
+use crate::util::clone_via_serde;
+use crate::walk::{
+    properties_at_mut, property_at, property_at_mut, property_key, split_path, walk_properties,
+};
+
+/// Whether the property path `path` (e.g. `CharacterSaves[2].TimesRetired`) matches `pattern`,
+/// a path in which `[*]` matches any index (e.g. `CharacterSaves[*].TimesRetired`).
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, path): (Vec<_>, Vec<_>) =
+        (pattern.split('.').collect(), path.split('.').collect());
+    pattern.len() == path.len()
+        && pattern.iter().zip(&path).all(|(pattern, segment)| match pattern.strip_suffix("[*]") {
+            Some(name) => segment
+                .strip_prefix(name)
+                .and_then(|index| index.strip_prefix('['))
+                .and_then(|index| index.strip_suffix(']'))
+                .is_some_and(|index| index.parse::<usize>().is_ok()),
+            None => pattern == segment,
+        })
+}
+
+/// Copy the properties of `backup` matching any of `patterns` (see [`path_matches`]) into `save`,
+/// with everything below them. Returns the paths of the restored properties.
+pub fn restore_properties(
+    save: &mut Save,
+    backup: &Save,
+    patterns: &[String],
+) -> Result<Vec<String>> {
+    let mut paths = vec![];
+    for pattern in patterns {
+        let n_paths = paths.len();
+        walk_properties(&backup.root.properties, &mut |path, _| {
+            if path_matches(pattern, path) {
+                paths.push(path.to_string());
+            }
+        });
+        if paths.len() == n_paths {
+            return Err(DrgSaveError::InvalidInput(format!(
+                "`{pattern}` matches no property of the backup"
+            )));
+        }
+    }
+
+    for path in &paths {
+        let prop = property_at(&backup.root.properties, path)
+            .ok_or_else(|| DrgSaveError::missing(path.as_str()))?;
+        let prop = clone_via_serde(prop)?;
+        match property_at_mut(&mut save.root.properties, path) {
+            Some(current) => *current = prop,
+            None => {
+                // Removed since the backup, add it back.
+                let (parent, name) = split_path(path);
+                let props =
+                    properties_at_mut(&mut save.root.properties, parent).ok_or_else(|| {
+                        DrgSaveError::UnexpectedShape(format!(
+                            "`{parent}` is not a struct in the save"
+                        ))
+                    })?;
+                props.0.insert(property_key(name), prop);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::synthetic_save;
+    use crate::util::int_mut;
+
+    #[test]
+    fn matches_paths_with_wildcard_indices() {
+        for (pattern, path, matches) in [
+            ("Credits", "Credits", true),
+            ("Credits", "CreditsSpent", false),
+            ("CharacterSaves[*].XP", "CharacterSaves[2].XP", true),
+            ("CharacterSaves[*].XP", "CharacterSaves[2].XP.Inner", false),
+            ("CharacterSaves[*].XP", "CharacterSaves.XP", false),
+            ("CharacterSaves[*].XP", "CharacterSaves[x].XP", false),
+            ("CharacterSaves[*].XP", "OtherSaves[2].XP", false),
+            ("CharacterSaves[1].XP", "CharacterSaves[2].XP", false),
+        ] {
+            assert_eq!(path_matches(pattern, path), matches, "`{pattern}` against `{path}`");
+        }
+    }
+
+    #[test]
+    fn restores_only_matching_properties() {
+        let backup = synthetic_save();
+        let mut save = synthetic_save();
+        let class_save = properties_at_mut(&mut save.root.properties, "CharacterSaves[0]").unwrap();
+        *int_mut(class_save, "XP").unwrap() += 1;
+        class_save.0.shift_remove(&property_key("TimesRetired"));
+        *int_mut(&mut save.root.properties, "Credits").unwrap() += 1;
+
+        let patterns =
+            ["CharacterSaves[*].XP".to_string(), "CharacterSaves[0].TimesRetired".to_string()];
+        let restored = restore_properties(&mut save, &backup, &patterns).unwrap();
+        assert_eq!(restored.len(), 6);
+        for path in ["CharacterSaves[0].XP", "CharacterSaves[0].TimesRetired", "Credits"] {
+            let (restored, backed_up) = (
+                property_at(&save.root.properties, path),
+                property_at(&backup.root.properties, path),
+            );
+            assert_eq!(restored == backed_up, path != "Credits", "`{path}`");
+        }
+
+        let unmatched = ["Missing".to_string()];
+        assert!(restore_properties(&mut save, &backup, &unmatched).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Flags tracking whether the rewards of performance pass levels were claimed.
+//!
+//! A reward marked as claimed that never arrived can't be claimed again in game. As in the sample
+//! save uesave is tested with (`drg-save-test.sav`), every season in `SeasonSave.Seasons` has a
+//! `RewardsClaimed` struct per level with a `NormalClaimed` and a `SpecialClaimed` flag, and a
+//! `HasClaimedAllRewards` flag. They are found by these [names](CLAIM_FLAGS), in whichever season,
+//! and addressed with the path patterns of [`crate::restore`] (`[*]` matches any index).
+
+use serde::Serialize;
+use uesave::{Properties, Property};
+
+use crate::error::{DrgSaveError, Result};
+use crate::restore::path_matches;
+use crate::walk::{walk_properties, walk_properties_mut};
+
+#[derive(Debug, Serialize)]
+pub struct ClaimState {
+    /// Path of the flag, e.g. `SeasonSave.Seasons[0].RewardsClaimed[3].NormalClaimed`.
+    pub path: String,
+    pub claimed: bool,
+}
+
+/// Names of the bool properties that are claim flags.
+pub const CLAIM_FLAGS: [&str; 3] = ["NormalClaimed", "SpecialClaimed", "HasClaimedAllRewards"];
+
+/// Whether the property called `name` is a claim flag.
+fn is_claim_flag(name: &str) -> bool {
+    CLAIM_FLAGS.contains(&name)
+}
+
+fn name(path: &str) -> &str {
+    path.rsplit('.').next().unwrap_or(path)
+}
+
+/// Every claim flag in the save.
+pub fn claim_states(props: &Properties) -> Vec<ClaimState> {
+    let mut states = vec![];
+    walk_properties(props, &mut |path, prop| {
+        if let Property::Bool { value, .. } = prop {
+            if is_claim_flag(name(path)) {
+                states.push(ClaimState { path: path.to_string(), claimed: *value });
+            }
+        }
+    });
+    states
+}
+
+/// Set the claim flags matching `pattern` to `claimed`. Returns the paths of the flags that
+/// changed.
+pub fn set_claimed(props: &mut Properties, pattern: &str, claimed: bool) -> Result<Vec<String>> {
+    let mut n_matched = 0;
+    let mut changed = vec![];
+    walk_properties_mut(props, &mut |path, prop| {
+        if let Property::Bool { value, .. } = prop {
+            if is_claim_flag(name(path)) && path_matches(pattern, path) {
+                n_matched += 1;
+                if *value != claimed {
+                    *value = claimed;
+                    changed.push(path.to_string());
+                }
+            }
+        }
+    });
+    if n_matched == 0 {
+        return Err(DrgSaveError::InvalidInput(format!("no claim flag matches `{pattern}`")));
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::synthetic_save;
+
+    #[test]
+    fn only_known_flags_are_claim_flags() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        props.0.insert(
+            uesave::PropertyKey::from("HasUnclaimedRewards"),
+            Property::Bool { id: None, value: true },
+        );
+        let states: Vec<_> =
+            claim_states(props).into_iter().map(|state| (state.path, state.claimed)).collect();
+        assert_eq!(
+            states,
+            [
+                ("SeasonSave.Seasons[0].RewardsClaimed[0].NormalClaimed".to_string(), true),
+                ("SeasonSave.Seasons[0].RewardsClaimed[0].SpecialClaimed".to_string(), false),
+                ("SeasonSave.Seasons[0].RewardsClaimed[1].NormalClaimed".to_string(), false),
+                ("SeasonSave.Seasons[0].RewardsClaimed[1].SpecialClaimed".to_string(), false),
+                ("SeasonSave.Seasons[0].HasClaimedAllRewards".to_string(), false),
+            ]
+        );
+        assert!(set_claimed(props, "HasUnclaimedRewards", false).is_err());
+    }
+
+    #[test]
+    fn sets_the_matching_flags() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        assert_eq!(
+            set_claimed(props, "SeasonSave.Seasons[*].RewardsClaimed[*].NormalClaimed", true)
+                .unwrap(),
+            ["SeasonSave.Seasons[0].RewardsClaimed[1].NormalClaimed"]
+        );
+    }
+}
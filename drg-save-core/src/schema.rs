@@ -0,0 +1,62 @@
+//! The shape of a save without its values: property names and types, struct types and container
+//! lengths. Comparing the shapes of saves from two game versions shows structural changes.
+
+use serde::Serialize;
+use uesave::{Properties, Property, PropertyValue, StructValue, ValueArray, ValueSet};
+
+use crate::describe::{property_len, property_type_name};
+
+#[derive(Debug, Serialize)]
+pub struct SchemaNode {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Struct type of a struct, or of the elements of a container of structs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub struct_type: Option<String>,
+    /// Number of elements of an array, set or map.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<usize>,
+    /// Properties of a struct. For containers of structs, the properties of the first element.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SchemaNode>,
+}
+
+/// Describe the shape of `props`.
+pub fn schema(props: &Properties) -> Vec<SchemaNode> {
+    props.0.iter().map(|(key, prop)| schema_node(&key.1, prop)).collect()
+}
+
+fn schema_node(name: &str, prop: &Property) -> SchemaNode {
+    let (struct_type, children) = match prop {
+        Property::Struct { struct_type, value, .. } => {
+            (Some(format!("{struct_type:?}")), struct_children(value))
+        }
+        Property::Array { value: ValueArray::Struct { struct_type, value, .. }, .. } => (
+            Some(format!("{struct_type:?}")),
+            value.first().map(struct_children).unwrap_or_default(),
+        ),
+        Property::Set { value: ValueSet::Struct(value), .. } => {
+            (None, value.first().map(struct_children).unwrap_or_default())
+        }
+        Property::Map { value, .. } => match value.first().map(|entry| &entry.value) {
+            Some(PropertyValue::Struct(value)) => (None, struct_children(value)),
+            _ => (None, vec![]),
+        },
+        _ => (None, vec![]),
+    };
+    SchemaNode {
+        name: name.to_string(),
+        type_name: property_type_name(prop).to_string(),
+        struct_type,
+        len: property_len(prop),
+        children,
+    }
+}
+
+fn struct_children(value: &StructValue) -> Vec<SchemaNode> {
+    match value {
+        StructValue::Struct(props) => schema(props),
+        _ => vec![],
+    }
+}
@@ -0,0 +1,225 @@
+//! Seasonal challenges. As in the sample save uesave is tested with (`drg-save-test.sav`), every
+//! season in the `SeasonSave.Seasons` map (keyed by the season's GUID) keeps the challenges
+//! currently assigned in `ActiveChallenges`: the challenge GUID, the wins so far and the wins
+//! needed to complete it.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Serialize;
+use uesave::{Properties, Property, PropertyValue, StructValue, ValueArray};
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_db::{GuidDb, GuidKind};
+use crate::util::{property, property_mut};
+use crate::walk::{properties_at_mut, property_at};
+
+/// Path of the map of seasons.
+const SEASONS: &str = "SeasonSave.Seasons";
+/// Name of the arrays holding the challenges assigned for a season.
+const ACTIVE_CHALLENGES: &str = "ActiveChallenges";
+const CHALLENGE_GUID: &str = "ChallengeGuid";
+const CURRENT_WINS: &str = "CurrentWins";
+const NUMBER_OF_WINS: &str = "NumberOfWins";
+
+#[derive(Debug, Serialize)]
+pub struct Challenge {
+    /// Position among all challenges in the save, used to address the challenge when editing.
+    pub index: usize,
+    /// Path of the challenge struct, e.g. `SeasonSave.Seasons[0].ActiveChallenges[2]`.
+    pub path: String,
+    /// The season the challenge is assigned for.
+    pub season: Option<Uuid>,
+    pub guid: Option<Uuid>,
+    pub name: Option<String>,
+    /// Wins so far.
+    pub progress: Option<i32>,
+    /// Wins needed to complete the challenge.
+    pub needed: Option<i32>,
+    pub completed: bool,
+}
+
+fn int(props: &Properties, name: &str) -> Option<i32> {
+    match property(props, name) {
+        Some(Property::Int { value, .. }) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Every challenge assigned in the save, over all seasons.
+pub fn challenges(props: &Properties, db: &GuidDb) -> Vec<Challenge> {
+    let mut challenges = vec![];
+    let Some(Property::Map { value: seasons, .. }) = property_at(props, SEASONS) else {
+        return challenges;
+    };
+    for (i, entry) in seasons.iter().enumerate() {
+        let season = match entry.key {
+            PropertyValue::Struct(StructValue::Guid(guid)) => Some(guid),
+            _ => None,
+        };
+        let PropertyValue::Struct(StructValue::Struct(season_props)) = &entry.value else {
+            continue;
+        };
+        let Some(Property::Array { value: ValueArray::Struct { value, .. }, .. }) =
+            property(season_props, ACTIVE_CHALLENGES)
+        else {
+            continue;
+        };
+        for (j, challenge) in value.iter().enumerate() {
+            let StructValue::Struct(challenge) = challenge else {
+                continue;
+            };
+            let guid = match property(challenge, CHALLENGE_GUID) {
+                Some(Property::Struct { value: StructValue::Guid(guid), .. }) => Some(*guid),
+                _ => None,
+            };
+            let progress = int(challenge, CURRENT_WINS);
+            let needed = int(challenge, NUMBER_OF_WINS);
+            challenges.push(Challenge {
+                index: challenges.len(),
+                path: format!("{SEASONS}[{i}].{ACTIVE_CHALLENGES}[{j}]"),
+                season,
+                guid,
+                name: guid.and_then(|guid| db.name(guid)).map(str::to_string),
+                progress,
+                needed,
+                completed: progress
+                    .zip(needed)
+                    .is_some_and(|(progress, needed)| progress >= needed),
+            });
+        }
+    }
+    challenges
+}
+
+/// Mark the challenge at `index` (see [`Challenge::index`]) as completed by giving it all the
+/// wins it needs.
+pub fn complete_challenge(props: &mut Properties, index: usize) -> Result<()> {
+    let challenge = challenge_mut(props, index)?;
+    let needed = match property(challenge, NUMBER_OF_WINS) {
+        Some(Property::Int { value, .. }) => *value,
+        Some(prop) => return Err(DrgSaveError::type_mismatch(NUMBER_OF_WINS, "Int", prop)),
+        None => return Err(DrgSaveError::missing(NUMBER_OF_WINS)),
+    };
+    match property_mut(challenge, CURRENT_WINS) {
+        Some(Property::Int { value, .. }) => *value = needed,
+        Some(prop) => return Err(DrgSaveError::type_mismatch(CURRENT_WINS, "Int", prop)),
+        None => return Err(DrgSaveError::missing(CURRENT_WINS)),
+    }
+    Ok(())
+}
+
+/// Replace the challenge at `index` with a challenge picked with `rng` from those known to `db`
+/// which aren't assigned yet, with its progress reset. Returns the GUID of the new challenge.
+pub fn reroll_challenge(
+    props: &mut Properties,
+    index: usize,
+    db: &GuidDb,
+    rng: &mut impl Rng,
+) -> Result<Uuid> {
+    db.require(GuidKind::SeasonChallenge)?;
+    let assigned: Vec<_> = challenges(props, db).into_iter().filter_map(|c| c.guid).collect();
+    let candidates: Vec<_> = db
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == GuidKind::SeasonChallenge && !assigned.contains(&entry.guid))
+        .map(|entry| entry.guid)
+        .collect();
+    let Some(&new) = candidates.choose(rng) else {
+        return Err(DrgSaveError::InvalidInput(
+            "the GUID database knows no unassigned season challenges to reroll into".to_string(),
+        ));
+    };
+
+    let challenge = challenge_mut(props, index)?;
+    match property_mut(challenge, CHALLENGE_GUID) {
+        Some(Property::Struct { value: StructValue::Guid(guid), .. }) => *guid = new,
+        Some(prop) => return Err(DrgSaveError::type_mismatch(CHALLENGE_GUID, "Guid Struct", prop)),
+        None => return Err(DrgSaveError::missing(CHALLENGE_GUID)),
+    }
+    if let Some(Property::Int { value, .. }) = property_mut(challenge, CURRENT_WINS) {
+        *value = 0;
+    }
+    Ok(new)
+}
+
+fn challenge_mut(props: &mut Properties, index: usize) -> Result<&mut Properties> {
+    let Some(challenge) = challenges(props, &GuidDb::default()).into_iter().nth(index) else {
+        return Err(DrgSaveError::InvalidInput(format!("there is no season challenge {index}")));
+    };
+    properties_at_mut(props, &challenge.path).ok_or_else(|| DrgSaveError::missing(challenge.path))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::fixtures::{fixture_guid_db, synthetic_save, SEASON, SEASON_CHALLENGES};
+
+    #[test]
+    fn lists_the_active_challenges_of_each_season() {
+        let save = synthetic_save();
+        let challenges = challenges(&save.root.properties, &fixture_guid_db());
+        let found: Vec<_> = challenges
+            .iter()
+            .map(|c| (c.path.as_str(), c.season, c.guid, c.progress, c.needed, c.completed))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "SeasonSave.Seasons[0].ActiveChallenges[0]",
+                    Some(SEASON),
+                    Some(SEASON_CHALLENGES[0]),
+                    Some(1),
+                    Some(3),
+                    false
+                ),
+                (
+                    "SeasonSave.Seasons[0].ActiveChallenges[1]",
+                    Some(SEASON),
+                    Some(SEASON_CHALLENGES[1]),
+                    Some(2),
+                    Some(2),
+                    true
+                ),
+            ]
+        );
+        assert_eq!(challenges[0].name.as_deref(), Some("Test Challenge 1"));
+    }
+
+    #[test]
+    fn completing_gives_all_needed_wins() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        complete_challenge(props, 0).unwrap();
+        let challenge = &challenges(props, &GuidDb::default())[0];
+        assert_eq!(challenge.progress, Some(3));
+        assert!(challenge.completed);
+        assert!(complete_challenge(props, 2).is_err());
+    }
+
+    #[test]
+    fn rerolls_into_an_unassigned_challenge() {
+        let mut save = synthetic_save();
+        let props = &mut save.root.properties;
+        let mut rng = StdRng::seed_from_u64(0);
+        let new = reroll_challenge(props, 1, &fixture_guid_db(), &mut rng).unwrap();
+        // The only challenge of the fixture database that isn't assigned yet.
+        assert_eq!(new, SEASON_CHALLENGES[2]);
+        let challenge = &challenges(props, &GuidDb::default())[1];
+        assert_eq!((challenge.guid, challenge.progress), (Some(new), Some(0)));
+    }
+
+    #[test]
+    fn reroll_needs_challenges_in_the_database() {
+        let mut save = synthetic_save();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(matches!(
+            reroll_challenge(&mut save.root.properties, 0, &GuidDb::default(), &mut rng),
+            Err(DrgSaveError::MissingGuidData { .. })
+        ));
+    }
+}
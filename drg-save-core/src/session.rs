@@ -0,0 +1,311 @@
+//! An open save file being edited: the lock on it, the save as read and the working copy, so tools
+//! don't have to hand-roll reading, backing up and writing it back in the right order.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use fs_err as fs;
+use sha2::{Digest as _, Sha256};
+use tracing::*;
+use uesave::Save;
+
+use crate::diff::find_difference;
+use crate::error::DrgSaveError;
+use crate::io::{backup_save_with, parse_save, write_journaled, BackupOptions};
+use crate::lenient::{parse_save_lenient, OpaqueProperty};
+use crate::lock::{SaveLock, LOCK_TIMEOUT};
+use crate::version::check_version;
+
+/// How a [`SaveSession`] backs up the save and which saves it opens.
+#[derive(Debug, Clone, Default)]
+pub struct SessionOptions {
+    pub backup: BackupOptions,
+    /// Open saves from game versions newer than the ones the editing logic was validated against
+    /// (see [`crate::version`]).
+    pub force: bool,
+    /// Set aside top-level properties that can't be parsed and write them back unchanged, instead
+    /// of failing (see [`crate::lenient`]).
+    pub lenient: bool,
+}
+
+/// The save file at a path, locked for as long as the session is open (except while running
+/// [`SaveSession::unlocked`]). Edits go to a working copy,
+/// which [`SaveSession::commit`] backs up the file for and writes, journaled as made by the tool
+/// that opened the session. Dropping the session without committing discards the edits.
+///
+/// The save as it is on disk is kept as the bytes read, and only parsed again when it is compared
+/// with the working copy, so a session holds a single parsed save.
+#[derive(Debug)]
+pub struct SaveSession {
+    path: PathBuf,
+    tool: String,
+    backup: BackupOptions,
+    lenient: bool,
+    lock: Option<SaveLock>,
+    /// The save file as it is on disk.
+    original: Vec<u8>,
+    /// The working copy.
+    save: Save,
+    /// The properties set aside by a lenient parse, written back as they were.
+    opaque: Vec<OpaqueProperty>,
+    /// Whether the working copy was borrowed mutably since it was last committed or rolled back.
+    dirty: bool,
+}
+
+impl SaveSession {
+    /// Lock and read the save file at `path`, refusing saves from newer game versions unless
+    /// `options.force` is set. Commits are journaled as made by `tool` and back up the file
+    /// according to `options.backup`.
+    pub fn open(path: &Path, tool: &str, options: SessionOptions) -> Result<Self> {
+        let lock = SaveLock::acquire(path, LOCK_TIMEOUT)?;
+        let original = fs::read(path)?;
+        let (save, opaque) = if options.lenient {
+            parse_save_lenient(&original, path)?
+        } else {
+            (parse_save(&original, path)?, vec![])
+        };
+        check_version(&save, options.force)
+            .context("refusing to edit, pass `--force` to edit anyway")?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            tool: tool.to_string(),
+            backup: options.backup,
+            lenient: options.lenient,
+            lock: Some(lock),
+            original,
+            save,
+            opaque,
+            dirty: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The save file as it is on disk, i.e. as of opening or the last commit.
+    pub fn original_bytes(&self) -> &[u8] {
+        &self.original
+    }
+
+    /// The save as it is on disk, parsed again from [`SaveSession::original_bytes`].
+    pub fn original(&self) -> Result<Save> {
+        if self.lenient {
+            Ok(parse_save_lenient(&self.original, &self.path)?.0)
+        } else {
+            Ok(parse_save(&self.original, &self.path)?)
+        }
+    }
+
+    /// The top-level properties set aside because they can't be parsed (see [`crate::lenient`]).
+    pub fn opaque(&self) -> &[OpaqueProperty] {
+        &self.opaque
+    }
+
+    /// The working copy.
+    pub fn save(&self) -> &Save {
+        &self.save
+    }
+
+    /// The working copy, to edit it.
+    pub fn save_mut(&mut self) -> &mut Save {
+        self.dirty = true;
+        &mut self.save
+    }
+
+    /// Whether the working copy differs from the save on disk.
+    pub fn is_dirty(&self) -> Result<bool> {
+        Ok(self.dirty && find_difference(&self.original()?, &self.save).is_some())
+    }
+
+    /// Back up the save file and write the working copy over it, if it was changed. Returns
+    /// whether it was written.
+    pub fn commit(&mut self) -> Result<bool> {
+        let original = self.original()?;
+        if !self.dirty || find_difference(&original, &self.save).is_none() {
+            info!("no changes, leaving `{}` untouched", self.path.display());
+            self.dirty = false;
+            return Ok(false);
+        }
+        backup_save_with(&self.path, &self.backup)?;
+        self.original =
+            write_journaled(&self.path, &original, &self.save, &self.opaque, &self.tool)?;
+        self.dirty = false;
+        Ok(true)
+    }
+
+    /// Discard the changes to the working copy since opening or the last commit.
+    pub fn rollback(&mut self) -> Result<()> {
+        if self.dirty {
+            self.save = self.original()?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Run `f` with the save unlocked, e.g. to wait for the user, so other tools don't time out
+    /// meanwhile. The save is locked again afterwards, failing with [`DrgSaveError::Changed`] if
+    /// another tool wrote it in the meantime.
+    pub fn unlocked<T>(&mut self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        self.lock = None;
+        let value = f(self);
+        self.lock = Some(SaveLock::acquire(&self.path, LOCK_TIMEOUT)?);
+        if fs::read(&self.path)? != self.original {
+            bail!(DrgSaveError::Changed { path: self.path.clone() });
+        }
+        value
+    }
+
+    /// Fail with [`DrgSaveError::Changed`] unless the save file on disk has the digest `read`.
+    pub fn ensure_unchanged(&self, read: &Digest) -> Result<()> {
+        if digest(&self.original) != *read {
+            bail!(DrgSaveError::Changed { path: self.path.clone() });
+        }
+        Ok(())
+    }
+
+    /// Close the session without committing, releasing the lock. Returns the save file as it was
+    /// on disk and the working copy.
+    pub fn close(self) -> (Vec<u8>, Save) {
+        (self.original, self.save)
+    }
+}
+
+/// SHA-256 digest of a save file, to tell whether it was written since it was read.
+pub type Digest = [u8; 32];
+
+pub fn digest(buf: &[u8]) -> Digest {
+    Sha256::digest(buf).into()
+}
+
+/// Read and parse the save file at `path` without keeping it locked, e.g. to hand it to a caller
+/// who may edit it for a while, along with the digest to [`write_back`] with.
+pub fn read_for_write_back(path: &Path) -> Result<(Save, Digest)> {
+    let buf = fs::read(path)?;
+    Ok((parse_save(&buf, path)?, digest(&buf)))
+}
+
+/// Write `save` over the save file at `path` in a [`SaveSession`], so it is locked, version
+/// checked, backed up and journaled like any other edit. If `read` is given, the file has to still
+/// have that digest (see [`read_for_write_back`]), rather than losing what another tool wrote to it
+/// meanwhile. `save` is left as it was. Returns the digest of the file afterwards.
+pub fn write_back(
+    path: &Path,
+    tool: &str,
+    options: SessionOptions,
+    read: Option<&Digest>,
+    save: &mut Save,
+) -> Result<Digest> {
+    let mut session = SaveSession::open(path, tool, options)?;
+    if let Some(read) = read {
+        session.ensure_unchanged(read)?;
+    }
+    std::mem::swap(session.save_mut(), save);
+    let committed = session.commit();
+    std::mem::swap(session.save_mut(), save);
+    committed?;
+    Ok(digest(session.original_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{synthetic_save_file, synthetic_save_with_unparsable_property};
+    use crate::journal::read_journal;
+    use crate::util::int_mut;
+
+    fn options() -> SessionOptions {
+        SessionOptions {
+            backup: BackupOptions { disabled: true, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn credits(save: &mut Save) -> &mut i32 {
+        int_mut(&mut save.root.properties, "Credits").unwrap()
+    }
+
+    #[test]
+    fn commit_writes_and_journals_the_working_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = synthetic_save_file(dir.path());
+        let mut session = SaveSession::open(&path, "test", options()).unwrap();
+        assert!(!session.commit().unwrap());
+        assert!(read_journal(&path).unwrap().is_empty());
+
+        *credits(session.save_mut()) = 1;
+        assert!(session.is_dirty().unwrap());
+        assert!(session.commit().unwrap());
+        assert!(!session.is_dirty().unwrap());
+        assert_eq!(session.original_bytes(), fs::read(&path).unwrap());
+        assert_eq!(*credits(&mut session.original().unwrap()), 1);
+        assert_eq!(read_journal(&path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rollback_discards_the_working_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = synthetic_save_file(dir.path());
+        let before = fs::read(&path).unwrap();
+        let mut session = SaveSession::open(&path, "test", options()).unwrap();
+        *credits(session.save_mut()) = 1;
+        session.rollback().unwrap();
+        assert!(!session.is_dirty().unwrap());
+        assert_eq!(*credits(session.save_mut()), 123_456);
+        assert!(!session.commit().unwrap());
+        drop(session);
+        assert_eq!(fs::read(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn write_back_refuses_to_lose_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = synthetic_save_file(dir.path());
+        let (mut save, read) = read_for_write_back(&path).unwrap();
+        *credits(&mut save) = 1;
+        let written = write_back(&path, "test", options(), Some(&read), &mut save).unwrap();
+        assert_eq!(*credits(&mut save), 1);
+        assert_eq!(written, digest(&fs::read(&path).unwrap()));
+
+        *credits(&mut save) = 2;
+        let err = write_back(&path, "test", options(), Some(&read), &mut save).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(DrgSaveError::Changed { .. })));
+        write_back(&path, "test", options(), Some(&written), &mut save).unwrap();
+        assert_eq!(*credits(&mut read_for_write_back(&path).unwrap().0), 2);
+    }
+
+    #[test]
+    fn unlocked_lets_other_tools_in_but_catches_their_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = synthetic_save_file(dir.path());
+        let mut session = SaveSession::open(&path, "test", options()).unwrap();
+        session.unlocked(|_| Ok(())).unwrap();
+
+        let err = session
+            .unlocked(|_| {
+                let mut other = SaveSession::open(&path, "other", options())?;
+                *credits(other.save_mut()) = 1;
+                other.commit()
+            })
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(DrgSaveError::Changed { .. })));
+    }
+
+    #[test]
+    fn lenient_sessions_write_back_what_they_cant_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("save.sav");
+        let (buf, odd) = synthetic_save_with_unparsable_property();
+        fs::write(&path, &buf).unwrap();
+        assert!(SaveSession::open(&path, "test", options()).is_err());
+
+        let options = SessionOptions { lenient: true, ..options() };
+        let mut session = SaveSession::open(&path, "test", options).unwrap();
+        assert_eq!(session.opaque().len(), 1);
+        *credits(session.save_mut()) = 1;
+        assert!(session.commit().unwrap());
+        let written = fs::read(&path).unwrap();
+        assert!(written.windows(odd.len()).any(|window| window == odd));
+        assert_eq!(*credits(&mut session.original().unwrap()), 1);
+    }
+}
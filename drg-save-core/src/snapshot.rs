@@ -0,0 +1,606 @@
+//! Content-addressed store of save file snapshots.
+//!
+//! Every snapshot is a zstd-compressed copy of a save file stored under the SHA-256 hash of its
+//! uncompressed contents, so identical saves are only stored once. Snapshots stored gzip-compressed
+//! by earlier versions are still read, and recompressed by [`SnapshotStore::recompress`]. The
+//! history of snapshots, with their messages, is kept in `log.jsonl` in the store directory, oldest
+//! first. The store is locked while it's written, so a prune can't delete an object another
+//! process stored but hasn't logged yet.
+//!
+//! With the `encryption` feature, snapshots can be encrypted with a passphrase (see
+//! [`SnapshotStore::with_passphrase`]) so a store on a shared disk or in a cloud-synced folder does
+//! not expose the account identifiers in the saves. Snapshots stored before encryption was turned on
+//! are encrypted with the next snapshot taken. The log is not encrypted, so it records a keyed hash
+//! of each save's path instead of the path, which names the Steam ID, and objects are stored under
+//! a keyed hash of their contents instead of the SHA-256 hash, which would tell whether the store
+//! has a given save (e.g. one shared in a bug report).
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::DrgSaveError;
+use crate::lock::{SaveLock, LOCK_TIMEOUT};
+use crate::util::unix_timestamp;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Hex SHA-256 hash of the save file contents, or HMAC-SHA256 keyed with the passphrase if
+    /// [`Self::keyed`].
+    pub hash: String,
+    /// Whether the snapshot was taken with a passphrase, and `hash` is keyed with it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keyed: bool,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub message: String,
+    /// The save file the snapshot was taken of.
+    pub source: PathBuf,
+    /// Size of the uncompressed save file contents, 0 for snapshots taken before it was recorded.
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// Saves compress well and snapshots are written rarely, so compress as much as possible.
+const ZSTD_LEVEL: i32 = 19;
+
+/// Shortest hash prefix that [`SnapshotStore::find`] accepts, like git's abbreviated hashes.
+const MIN_PREFIX_LEN: usize = 4;
+
+/// Start of every gzip-compressed file.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Start of every age-encrypted file.
+const AGE_HEADER: &[u8] = b"age-encryption.org/";
+
+/// Start of the sources logged as keyed hashes of the save's path.
+const HASHED_SOURCE_PREFIX: &str = "hmac-sha256:";
+
+/// Number and total size of stored objects.
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct Usage {
+    pub objects: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+    /// Passphrase new snapshots are encrypted with and encrypted snapshots are decrypted with.
+    #[cfg(feature = "encryption")]
+    passphrase: Option<age::secrecy::SecretString>,
+}
+
+impl SnapshotStore {
+    /// The default store location in the user's data directory.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("drg-save").join("snapshots"))
+    }
+
+    /// Open the store in `dir`, creating it if needed.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join("objects"))?;
+        Ok(Self {
+            dir,
+            #[cfg(feature = "encryption")]
+            passphrase: None,
+        })
+    }
+
+    /// Open the store in [`Self::default_dir`].
+    pub fn open_default() -> Result<Self> {
+        Self::open(Self::default_dir().context("no data directory for this platform")?)
+    }
+
+    /// Encrypt new snapshots with `passphrase`, and decrypt encrypted ones with it.
+    #[cfg(feature = "encryption")]
+    pub fn with_passphrase(mut self, passphrase: String) -> Self {
+        self.passphrase = Some(age::secrecy::SecretString::new(passphrase));
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn log_path(&self) -> PathBuf {
+        self.dir.join("log.jsonl")
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("objects").join(hash)
+    }
+
+    /// What snapshots of the save file at `path` record as their source: its canonical path, or
+    /// a keyed hash of it when encrypting.
+    pub fn source(&self, path: &Path) -> PathBuf {
+        self.hash_source(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()))
+    }
+
+    /// Lock the store against other processes writing it, e.g. `watch` and `gc`.
+    fn lock(&self) -> Result<SaveLock> {
+        Ok(SaveLock::acquire(&self.dir, LOCK_TIMEOUT)?)
+    }
+
+    /// Store the contents of the save file at `path` and record it in the log.
+    pub fn create(&self, path: &Path, message: &str) -> Result<Snapshot> {
+        let _lock = self.lock()?;
+        self.encrypt_existing()?;
+        let buf = fs::read(path)?;
+        let (hash, keyed) = self.content_hash(&buf);
+
+        let object_path = self.object_path(&hash);
+        if !object_path.exists() {
+            self.write_object(
+                &object_path,
+                &self.encrypt(zstd::encode_all(buf.as_slice(), ZSTD_LEVEL)?)?,
+            )?;
+        }
+
+        let snapshot = Snapshot {
+            hash,
+            keyed,
+            timestamp: unix_timestamp(),
+            message: message.to_string(),
+            source: self.source(path),
+            size: buf.len() as u64,
+        };
+        let mut log = fs::OpenOptions::new().create(true).append(true).open(self.log_path())?;
+        writeln!(log, "{}", serde_json::to_string(&snapshot)?)?;
+        Ok(snapshot)
+    }
+
+    fn write_object(&self, object_path: &Path, object: &[u8]) -> Result<()> {
+        let mut tmp = tempfile::NamedTempFile::new_in(&self.dir)?;
+        tmp.write_all(object)?;
+        tmp.persist(object_path)?;
+        Ok(())
+    }
+
+    /// All snapshots, oldest first.
+    pub fn log(&self) -> Result<Vec<Snapshot>> {
+        let log_path = self.log_path();
+        if !log_path.exists() {
+            return Ok(vec![]);
+        }
+        fs::read_to_string(&log_path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("failed to parse `{}`", log_path.display()))
+            })
+            .collect()
+    }
+
+    /// Find the snapshot whose hash starts with `prefix`, which has to be at least
+    /// [`MIN_PREFIX_LEN`] characters long. The most recent one is returned if the same save was
+    /// snapshotted several times.
+    pub fn find(&self, prefix: &str) -> Result<Snapshot> {
+        if prefix.len() < MIN_PREFIX_LEN {
+            bail!(DrgSaveError::InvalidInput(format!(
+                "`{prefix}` is too short, give at least {MIN_PREFIX_LEN} characters of the hash"
+            )));
+        }
+        let log = self.log()?;
+        let mut matches = log.iter().rev().filter(|snapshot| snapshot.hash.starts_with(prefix));
+        let Some(snapshot) = matches.next() else {
+            bail!(DrgSaveError::InvalidInput(format!("no snapshot matches `{prefix}`")));
+        };
+        if let Some(other) = matches.find(|other| other.hash != snapshot.hash) {
+            bail!(DrgSaveError::InvalidInput(format!(
+                "`{prefix}` is ambiguous, matches {} and {}",
+                snapshot.hash, other.hash
+            )));
+        }
+        Ok(snapshot.clone())
+    }
+
+    /// The uncompressed save file contents stored under `hash`.
+    pub fn read(&self, hash: &str) -> Result<Vec<u8>> {
+        let object_path = self.object_path(hash);
+        let object = self
+            .decrypt(fs::read(&object_path)?)
+            .with_context(|| format!("failed to decrypt `{}`", object_path.display()))?;
+        let buf = decompress(&object)
+            .with_context(|| format!("failed to decompress `{}`", object_path.display()))?;
+        let (actual, _) = self.content_hash(&buf);
+        // Snapshots taken before encryption was turned on keep their plain hash until renamed.
+        if actual != hash && format!("{:x}", Sha256::digest(&buf)) != hash {
+            bail!(DrgSaveError::UnexpectedShape(format!(
+                "snapshot `{hash}` is corrupt, its contents hash to `{actual}`"
+            )));
+        }
+        Ok(buf)
+    }
+
+    /// The hash `buf` is stored under, and whether it is keyed with the passphrase.
+    fn content_hash(&self, buf: &[u8]) -> (String, bool) {
+        match self.keyed_hash(buf) {
+            Some(hash) => (hash, true),
+            None => (format!("{:x}", Sha256::digest(buf)), false),
+        }
+    }
+
+    fn write_log(&self, log: &[Snapshot]) -> Result<()> {
+        let mut buf = String::new();
+        for snapshot in log {
+            buf.push_str(&serde_json::to_string(snapshot)?);
+            buf.push('\n');
+        }
+        fs::write(self.log_path(), buf)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "encryption")]
+    fn encrypt(&self, object: Vec<u8>) -> Result<Vec<u8>> {
+        let Some(passphrase) = &self.passphrase else {
+            return Ok(object);
+        };
+        let mut encrypted = vec![];
+        let mut writer =
+            age::Encryptor::with_user_passphrase(passphrase.clone()).wrap_output(&mut encrypted)?;
+        writer.write_all(&object)?;
+        writer.finish()?;
+        Ok(encrypted)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn encrypt(&self, object: Vec<u8>) -> Result<Vec<u8>> {
+        Ok(object)
+    }
+
+    #[cfg(feature = "encryption")]
+    fn decrypt(&self, object: Vec<u8>) -> Result<Vec<u8>> {
+        if !object.starts_with(AGE_HEADER) {
+            return Ok(object);
+        }
+        let Some(passphrase) = &self.passphrase else {
+            bail!(DrgSaveError::InvalidInput(
+                "the snapshot is encrypted and no passphrase was given".to_string()
+            ));
+        };
+        let age::Decryptor::Passphrase(decryptor) = age::Decryptor::new(object.as_slice())? else {
+            bail!(DrgSaveError::InvalidInput(
+                "the snapshot is not encrypted with a passphrase".to_string()
+            ));
+        };
+        let mut decrypted = vec![];
+        decryptor.decrypt(passphrase, None)?.read_to_end(&mut decrypted)?;
+        Ok(decrypted)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn decrypt(&self, object: Vec<u8>) -> Result<Vec<u8>> {
+        if object.starts_with(AGE_HEADER) {
+            bail!(DrgSaveError::InvalidInput(
+                "the snapshot is encrypted, which needs the `encryption` feature".to_string()
+            ));
+        }
+        Ok(object)
+    }
+
+    /// When encrypting, encrypt the objects stored in plain, and move them and replace the paths
+    /// logged as sources by keyed hashes, e.g. for snapshots taken before encryption was turned on.
+    #[cfg(feature = "encryption")]
+    fn encrypt_existing(&self) -> Result<()> {
+        if self.passphrase.is_none() {
+            return Ok(());
+        }
+        let mut log = self.log()?;
+        let mut renamed = std::collections::BTreeMap::new();
+        let mut changed = false;
+        for snapshot in &mut log {
+            if !snapshot.keyed && !renamed.contains_key(&snapshot.hash) {
+                match self.rename_object(&snapshot.hash) {
+                    Ok(hash) => {
+                        renamed.insert(snapshot.hash.clone(), hash);
+                    }
+                    // Left for the next snapshot to retry, and for `verify` to report.
+                    Err(e) => tracing::warn!("failed to rename snapshot {}: {e:#}", snapshot.hash),
+                }
+            }
+            if let Some(hash) = renamed.get(&snapshot.hash).filter(|_| !snapshot.keyed) {
+                (snapshot.hash, snapshot.keyed) = (hash.clone(), true);
+                changed = true;
+            }
+            if !snapshot.source.to_string_lossy().starts_with(HASHED_SOURCE_PREFIX) {
+                snapshot.source = self.hash_source(std::mem::take(&mut snapshot.source));
+                changed = true;
+            }
+        }
+        if changed {
+            self.write_log(&log)?;
+        }
+        // Only once the log no longer references them.
+        for hash in renamed.keys() {
+            fs::remove_file(self.object_path(hash))?;
+        }
+        for object in fs::read_dir(self.dir.join("objects"))? {
+            let object_path = object?.path();
+            let mut header = vec![];
+            fs::File::open(&object_path)?.take(AGE_HEADER.len() as u64).read_to_end(&mut header)?;
+            if header != AGE_HEADER {
+                tracing::info!("encrypting snapshot `{}`", object_path.display());
+                let object = recompressed(fs::read(&object_path)?)?;
+                self.write_object(&object_path, &self.encrypt(object)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store the object stored under the plain hash `hash` encrypted under its keyed hash, which is
+    /// returned. The object under `hash` is left for the caller to delete.
+    #[cfg(feature = "encryption")]
+    fn rename_object(&self, hash: &str) -> Result<String> {
+        let buf = self.read(hash)?;
+        let (keyed_hash, _) = self.content_hash(&buf);
+        let object_path = self.object_path(&keyed_hash);
+        if !object_path.exists() {
+            tracing::info!("encrypting snapshot {hash} as {keyed_hash}");
+            let object = zstd::encode_all(buf.as_slice(), ZSTD_LEVEL)?;
+            self.write_object(&object_path, &self.encrypt(object)?)?;
+        }
+        Ok(keyed_hash)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn encrypt_existing(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// `source` as an HMAC keyed with the passphrase when encrypting, so the log doesn't name the
+    /// Steam ID, but snapshots of the same save can still be told apart.
+    fn hash_source(&self, source: PathBuf) -> PathBuf {
+        match self.keyed_hash(source.as_os_str().as_encoded_bytes()) {
+            Some(hash) => PathBuf::from(format!("{HASHED_SOURCE_PREFIX}{hash}")),
+            None => source,
+        }
+    }
+
+    /// Hex HMAC-SHA256 of `bytes` keyed with the passphrase, when encrypting.
+    #[cfg(feature = "encryption")]
+    fn keyed_hash(&self, bytes: &[u8]) -> Option<String> {
+        use age::secrecy::ExposeSecret;
+        use hmac::{Hmac, Mac};
+
+        let passphrase = self.passphrase.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(passphrase.expose_secret().as_bytes())
+            .expect("HMAC takes keys of any length");
+        mac.update(bytes);
+        Some(format!("{:x}", mac.finalize().into_bytes()))
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn keyed_hash(&self, _bytes: &[u8]) -> Option<String> {
+        None
+    }
+
+    /// Drop all but the `keep` most recent snapshots of the save file at `source`, and delete
+    /// stored objects no longer referenced by any snapshot. Returns the number of dropped
+    /// snapshots.
+    pub fn prune(&self, source: &Path, keep: usize) -> Result<usize> {
+        let _lock = self.lock()?;
+        let source = self.source(source);
+        let mut log = self.log()?;
+        let n_of_source = log.iter().filter(|snapshot| snapshot.source == source).count();
+        let mut n_to_drop = n_of_source.saturating_sub(keep);
+        if n_to_drop == 0 {
+            return Ok(0);
+        }
+
+        let n_dropped = n_to_drop;
+        log.retain(|snapshot| {
+            let drop = n_to_drop > 0 && snapshot.source == source;
+            n_to_drop -= usize::from(drop);
+            !drop
+        });
+        self.write_log(&log)?;
+
+        for (path, _) in self.unreferenced_objects(&log)? {
+            fs::remove_file(path)?;
+        }
+        Ok(n_dropped)
+    }
+
+    /// Number and total size of all stored objects.
+    pub fn usage(&self) -> Result<Usage> {
+        let mut usage = Usage::default();
+        for object in fs::read_dir(self.dir.join("objects"))? {
+            usage.objects += 1;
+            usage.bytes += object?.metadata()?.len();
+        }
+        Ok(usage)
+    }
+
+    /// Delete stored objects no longer referenced by any snapshot, e.g. left behind by an
+    /// interrupted prune, or only list them with `dry_run`. Returns what was (or would be)
+    /// reclaimed.
+    pub fn gc(&self, dry_run: bool) -> Result<Usage> {
+        let _lock = self.lock()?;
+        let mut reclaimed = Usage::default();
+        for (path, bytes) in self.unreferenced_objects(&self.log()?)? {
+            if !dry_run {
+                fs::remove_file(path)?;
+            }
+            reclaimed.objects += 1;
+            reclaimed.bytes += bytes;
+        }
+        Ok(reclaimed)
+    }
+
+    /// Recompress the objects stored gzip-compressed by earlier versions with zstd, or only list
+    /// them with `dry_run`. Returns the objects that were (or would be) recompressed and the bytes
+    /// that saved. Encrypted objects are left alone, since telling how they are compressed takes
+    /// decrypting each; plain ones are recompressed anyway when they are encrypted.
+    pub fn recompress(&self, dry_run: bool) -> Result<Usage> {
+        let _lock = self.lock()?;
+        let mut saved = Usage::default();
+        for object in fs::read_dir(self.dir.join("objects"))? {
+            let object_path = object?.path();
+            let object = fs::read(&object_path)?;
+            if !object.starts_with(GZIP_MAGIC) {
+                continue;
+            }
+            let len = object.len() as u64;
+            let object = recompressed(object)
+                .with_context(|| format!("failed to recompress `{}`", object_path.display()))?;
+            if !dry_run {
+                self.write_object(&object_path, &object)?;
+            }
+            saved.objects += 1;
+            saved.bytes += len.saturating_sub(object.len() as u64);
+        }
+        Ok(saved)
+    }
+
+    /// Paths and sizes of the stored objects not referenced by any snapshot in `log`.
+    fn unreferenced_objects(&self, log: &[Snapshot]) -> Result<Vec<(PathBuf, u64)>> {
+        let mut unreferenced = vec![];
+        for object in fs::read_dir(self.dir.join("objects"))? {
+            let object = object?;
+            let hash = object.file_name();
+            if !log.iter().any(|snapshot| *snapshot.hash == *hash) {
+                unreferenced.push((object.path(), object.metadata()?.len()));
+            }
+        }
+        Ok(unreferenced)
+    }
+}
+
+/// Decompress an unencrypted object, zstd- or gzip-compressed.
+fn decompress(object: &[u8]) -> std::io::Result<Vec<u8>> {
+    if object.starts_with(GZIP_MAGIC) {
+        let mut buf = vec![];
+        GzDecoder::new(object).read_to_end(&mut buf)?;
+        Ok(buf)
+    } else {
+        zstd::decode_all(object)
+    }
+}
+
+/// An unencrypted object zstd-compressed, recompressing it if it is gzip-compressed.
+fn recompressed(object: Vec<u8>) -> Result<Vec<u8>> {
+    if !object.starts_with(GZIP_MAGIC) {
+        return Ok(object);
+    }
+    Ok(zstd::encode_all(decompress(&object)?.as_slice(), ZSTD_LEVEL)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "encryption")]
+    use crate::fixtures::SAMPLE_SAVE;
+    #[cfg(feature = "encryption")]
+    use crate::integrity::{verify_snapshots, Status, Verification};
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypts_what_was_stored_before_encryption() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_dir = dir.path().join("store");
+        let path = dir.path().join("76561198000000000_Player.sav");
+        fs::write(&path, b"plain").unwrap();
+        let plain = SnapshotStore::open(&store_dir).unwrap().create(&path, "plain").unwrap();
+
+        let store = SnapshotStore::open(&store_dir).unwrap().with_passphrase("hunter2".to_string());
+        fs::write(&path, b"encrypted").unwrap();
+        store.create(&path, "encrypted").unwrap();
+
+        for object in fs::read_dir(store_dir.join("objects")).unwrap() {
+            assert!(fs::read(object.unwrap().path()).unwrap().starts_with(AGE_HEADER));
+        }
+        let log = fs::read_to_string(store_dir.join("log.jsonl")).unwrap();
+        assert!(!log.contains("76561198"));
+        // The plain snapshot moved to its keyed hash, so the store doesn't tell which saves it has.
+        assert!(!log.contains(&plain.hash));
+        assert!(!store.object_path(&plain.hash).exists());
+        let renamed = &store.log().unwrap()[0];
+        assert!(renamed.keyed);
+        assert_eq!(store.read(&renamed.hash).unwrap(), b"plain");
+        assert_eq!(store.prune(&path, 1).unwrap(), 1);
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn names_encrypted_objects_by_keyed_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("save.sav");
+        fs::write(&path, SAMPLE_SAVE).unwrap();
+        let plain_hash = format!("{:x}", Sha256::digest(SAMPLE_SAVE));
+        let open = |passphrase: &str| {
+            SnapshotStore::open(dir.path().join(passphrase))
+                .unwrap()
+                .with_passphrase(passphrase.to_string())
+        };
+
+        let store = open("hunter2");
+        let snapshot = store.create(&path, "").unwrap();
+        assert!(snapshot.keyed);
+        assert_ne!(snapshot.hash, plain_hash);
+        assert!(store.object_path(&snapshot.hash).exists());
+        assert_eq!(store.read(&snapshot.hash).unwrap(), SAMPLE_SAVE);
+        let verifications = verify_snapshots(&store).unwrap();
+        assert!(matches!(verifications[..], [Verification { status: Status::Ok, .. }]));
+        // Another passphrase names the same save differently.
+        assert_ne!(open("correct horse").create(&path, "").unwrap().hash, snapshot.hash);
+    }
+
+    #[test]
+    fn finds_snapshots_only_by_long_enough_prefixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::open(dir.path()).unwrap();
+        let path = dir.path().join("save.sav");
+        fs::write(&path, b"save").unwrap();
+        let snapshot = store.create(&path, "").unwrap();
+
+        assert_eq!(store.find(&snapshot.hash[..MIN_PREFIX_LEN]).unwrap().hash, snapshot.hash);
+        assert!(store.find("").is_err());
+        assert!(store.find(&snapshot.hash[..MIN_PREFIX_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn recompresses_gzip_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::open(dir.path()).unwrap();
+        let path = dir.path().join("save.sav");
+        fs::write(&path, vec![7; 4096]).unwrap();
+        let snapshot = store.create(&path, "").unwrap();
+        let object_path = store.object_path(&snapshot.hash);
+        let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::fast());
+        gzip.write_all(&[7; 4096]).unwrap();
+        fs::write(&object_path, gzip.finish().unwrap()).unwrap();
+
+        assert_eq!(store.recompress(true).unwrap().objects, 1);
+        assert!(fs::read(&object_path).unwrap().starts_with(GZIP_MAGIC));
+        let saved = store.recompress(false).unwrap();
+        assert_eq!(saved.objects, 1);
+        assert!(!fs::read(&object_path).unwrap().starts_with(GZIP_MAGIC));
+        assert_eq!(store.read(&snapshot.hash).unwrap(), [7; 4096]);
+        assert_eq!(store.recompress(false).unwrap().objects, 0);
+    }
+
+    #[test]
+    fn gc_deletes_only_unreferenced_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::open(dir.path()).unwrap();
+        let path = dir.path().join("save.sav");
+        fs::write(&path, b"logged").unwrap();
+        let snapshot = store.create(&path, "").unwrap();
+        fs::write(store.object_path("0123"), b"stray").unwrap();
+
+        let reclaimed = store.gc(true).unwrap();
+        assert_eq!((reclaimed.objects, reclaimed.bytes), (1, 5));
+        assert!(store.object_path("0123").exists());
+        assert_eq!(store.gc(false).unwrap().objects, 1);
+        assert!(!store.object_path("0123").exists());
+        assert_eq!(store.read(&snapshot.hash).unwrap(), b"logged");
+    }
+}
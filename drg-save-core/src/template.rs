@@ -0,0 +1,155 @@
+//! Named templates of class saves, kept in `templates` in the user's data directory.
+//!
+//! A template is a complete copy of a `CharacterSave`, progression and loadout included, so a
+//! fresh save can be brought to a known starting state by instantiating a template for every
+//! class. Instantiating keeps the `SavegameID` of the target slot, so a template exported from one
+//! class can be used for another.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use uesave::{Property, StructValue};
+use uuid::Uuid;
+
+use crate::classes::{savegame_id, HIDDEN_CLASS_SAVEGAME_ID};
+use crate::error::DrgSaveError;
+use crate::util::clone_via_serde;
+use crate::walk::walk_properties_mut;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Template {
+    /// `SavegameID` of the class save slot the template was exported from.
+    pub savegame_id: Uuid,
+    pub class_save: StructValue,
+}
+
+pub fn templates_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("drg-save").join("templates"))
+}
+
+fn template_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        bail!(DrgSaveError::InvalidInput(format!("invalid template name `{name}`")));
+    }
+    let dir = templates_dir().context("no data directory for this platform")?;
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// Copy `class_save` into a template.
+pub fn export_template(class_save: &StructValue) -> Result<Template> {
+    let savegame_id = savegame_id(class_save).context("class save has no `SavegameID`")?;
+    Ok(Template { savegame_id, class_save: clone_via_serde(class_save)? })
+}
+
+/// Store `template` as `name`, replacing a template of the same name.
+pub fn save_template(name: &str, template: &Template) -> Result<PathBuf> {
+    let path = template_path(name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(template)?)?;
+    Ok(path)
+}
+
+pub fn load_template(name: &str) -> Result<Template> {
+    let path = template_path(name)?;
+    if !path.exists() {
+        bail!(DrgSaveError::InvalidInput(format!("no template `{name}`")));
+    }
+    serde_json::from_str(&fs::read_to_string(&path)?)
+        .with_context(|| format!("failed to parse template `{}`", path.display()))
+}
+
+/// Delete the template `name`. Returns whether there was one.
+pub fn remove_template(name: &str) -> Result<bool> {
+    let path = template_path(name)?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(path)?;
+    Ok(true)
+}
+
+/// Names of all stored templates, sorted.
+pub fn list_templates() -> Result<Vec<String>> {
+    let Some(dir) = templates_dir().filter(|dir| dir.exists()) else {
+        return Ok(vec![]);
+    };
+    let mut names = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Overwrite `class_save` with a copy of the one in `template`. Every GUID in the copy equal to
+/// the template's `SavegameID` is replaced with the `SavegameID` of `class_save`, so the game
+/// still recognizes the slot as the same class. The hidden class can't be overwritten, as that
+/// would change the blue level.
+pub fn instantiate(class_save: &mut StructValue, template: &Template) -> Result<()> {
+    let target_id = savegame_id(class_save).context("class save has no `SavegameID`")?;
+    if target_id == HIDDEN_CLASS_SAVEGAME_ID {
+        bail!(DrgSaveError::InvalidInput(
+            "cannot apply a template to the hidden class, it would change the blue level"
+                .to_string()
+        ));
+    }
+    let mut instance = clone_via_serde(&template.class_save)?;
+    let StructValue::Struct(props) = &mut instance else {
+        return Err(DrgSaveError::UnexpectedShape(
+            "unexpected `class_save` struct value kind in template".to_string(),
+        )
+        .into());
+    };
+    walk_properties_mut(props, &mut |_, prop| {
+        if let Property::Struct { value: StructValue::Guid(guid), .. } = prop {
+            if *guid == template.savegame_id {
+                *guid = target_id;
+            }
+        }
+    });
+    *class_save = instance;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::{class_saves_mut, find_class_save};
+    use crate::fixtures::{synthetic_save, DRILLER, GUNNER};
+
+    #[test]
+    fn instantiates_templates_in_other_slots() {
+        let mut save = synthetic_save();
+        let class_saves = class_saves_mut(&mut save).unwrap();
+        let driller = find_class_save(class_saves, DRILLER).unwrap();
+        let gunner = find_class_save(class_saves, GUNNER).unwrap();
+        let template = export_template(&class_saves[driller]).unwrap();
+
+        instantiate(&mut class_saves[gunner], &template).unwrap();
+        assert_eq!(savegame_id(&class_saves[gunner]), Some(GUNNER));
+        let mut expected = clone_via_serde(&class_saves[driller]).unwrap();
+        let StructValue::Struct(props) = &mut expected else { unreachable!() };
+        walk_properties_mut(props, &mut |_, prop| {
+            if let Property::Struct { value: StructValue::Guid(guid), .. } = prop {
+                if *guid == DRILLER {
+                    *guid = GUNNER;
+                }
+            }
+        });
+        assert_eq!(class_saves[gunner], expected);
+
+        let hidden = find_class_save(class_saves, HIDDEN_CLASS_SAVEGAME_ID).unwrap();
+        let before = clone_via_serde(&class_saves[hidden]).unwrap();
+        assert!(instantiate(&mut class_saves[hidden], &template).is_err());
+        assert_eq!(class_saves[hidden], before);
+    }
+}
@@ -0,0 +1,189 @@
+//! History of a save's progress (blue level, class levels, credits, resources) in a local SQLite
+//! database, recorded on demand or by the watcher each time the game writes the save.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use fs_err as fs;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::report::SaveReport;
+use crate::util::unix_timestamp;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS records (
+    id INTEGER PRIMARY KEY,
+    source TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    blue_level INTEGER NOT NULL,
+    credits INTEGER
+);
+CREATE TABLE IF NOT EXISTS classes (
+    record_id INTEGER NOT NULL REFERENCES records(id),
+    name TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    promotions INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS resources (
+    record_id INTEGER NOT NULL REFERENCES records(id),
+    name TEXT NOT NULL,
+    amount REAL NOT NULL
+);
+";
+
+/// Metrics of a save at one point in time.
+#[derive(Debug, Serialize)]
+pub struct Record {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub source: PathBuf,
+    pub blue_level: i32,
+    pub credits: Option<i32>,
+    pub classes: Vec<ClassRecord>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassRecord {
+    pub name: String,
+    pub level: u8,
+    pub promotions: i32,
+}
+
+#[derive(Debug)]
+pub struct TrackDb {
+    conn: Connection,
+}
+
+impl TrackDb {
+    /// The default database location in the user's data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("drg-save").join("track.sqlite"))
+    }
+
+    /// Open the database at `path`, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open `{}`", path.display()))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Open the database at [`Self::default_path`].
+    pub fn open_default() -> Result<Self> {
+        Self::open(&Self::default_path().context("no data directory for this platform")?)
+    }
+
+    /// Record the metrics in `report` of the save at `source`.
+    pub fn record(&mut self, source: &Path, report: &SaveReport) -> Result<()> {
+        let source = source.canonicalize().unwrap_or_else(|_| source.to_path_buf());
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO records (source, timestamp, blue_level, credits) VALUES (?1, ?2, ?3, ?4)",
+            params![source.to_string_lossy(), unix_timestamp(), report.blue_level, report.credits],
+        )?;
+        let id = tx.last_insert_rowid();
+        for class in &report.classes {
+            tx.execute(
+                "INSERT INTO classes (record_id, name, level, promotions) VALUES (?1, ?2, ?3, ?4)",
+                params![id, class.name, class.level, class.promotions],
+            )?;
+        }
+        for resource in &report.resources {
+            tx.execute(
+                "INSERT INTO resources (record_id, name, amount) VALUES (?1, ?2, ?3)",
+                params![id, resource.name, resource.amount],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All records, oldest first, only of the save at `source` if given.
+    pub fn history(&self, source: Option<&Path>) -> Result<Vec<Record>> {
+        let source = source.map(|source| {
+            source
+                .canonicalize()
+                .unwrap_or_else(|_| source.to_path_buf())
+                .to_string_lossy()
+                .into_owned()
+        });
+        let mut records = self.conn.prepare(
+            "SELECT id, timestamp, source, blue_level, credits FROM records
+             WHERE ?1 IS NULL OR source = ?1 ORDER BY timestamp, id",
+        )?;
+        let mut classes = self.conn.prepare(
+            "SELECT name, level, promotions FROM classes WHERE record_id = ?1 ORDER BY rowid",
+        )?;
+        let rows = records.query_map(params![source], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Record {
+                    timestamp: row.get(1)?,
+                    source: PathBuf::from(row.get::<_, String>(2)?),
+                    blue_level: row.get(3)?,
+                    credits: row.get(4)?,
+                    classes: vec![],
+                },
+            ))
+        })?;
+        let mut history = vec![];
+        for row in rows {
+            let (id, mut record) = row?;
+            record.classes = classes
+                .query_map(params![id], |row| {
+                    Ok(ClassRecord {
+                        name: row.get(0)?,
+                        level: row.get(1)?,
+                        promotions: row.get(2)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            history.push(record);
+        }
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fixture_guid_db, synthetic_save, with_guid_db};
+    use crate::guid_db::GuidDb;
+
+    #[test]
+    fn reads_back_what_was_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("track.sqlite");
+        let [a, b] = ["a.sav", "b.sav"].map(|name| dir.path().join(name));
+        for path in [&a, &b] {
+            fs::write(path, b"").unwrap();
+        }
+        let guid_db = with_guid_db(fixture_guid_db(), GuidDb::load).unwrap();
+        let mut report = crate::report::report(&synthetic_save(), &guid_db).unwrap();
+
+        let mut db = TrackDb::open(&db_path).unwrap();
+        db.record(&a, &report).unwrap();
+        db.record(&b, &report).unwrap();
+        report.credits = None;
+        report.classes.truncate(1);
+        db.record(&a, &report).unwrap();
+        drop(db);
+
+        let db = TrackDb::open(&db_path).unwrap();
+        assert_eq!(db.history(None).unwrap().len(), 3);
+        let history = db.history(Some(&a)).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|record| record.source == a.canonicalize().unwrap()));
+        let (first, second) = (&history[0], &history[1]);
+        assert_eq!((first.blue_level, first.credits), (report.blue_level, Some(123_456)));
+        let names: Vec<_> = first.classes.iter().map(|class| class.name.as_str()).collect();
+        assert_eq!(names, ["Driller", "Engineer", "Gunner", "Scout"]);
+        let driller = &first.classes[0];
+        assert_eq!((driller.level, driller.promotions), (report.classes[0].level, 3));
+        assert_eq!((second.credits, second.classes.len()), (None, 1));
+    }
+}
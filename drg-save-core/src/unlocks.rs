@@ -0,0 +1,103 @@
+//! Abyss Bar drink recipes and pickaxe customization parts, which the save tracks as sets of
+//! unlocked GUIDs: `Drinks.UnlockedDrinks` and `UnlockedPickaxeParts`, as in the sample save uesave
+//! is tested with (`drg-save-test.sav`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use uesave::Properties;
+use uuid::Uuid;
+
+use crate::error::{DrgSaveError, Result};
+use crate::guid_array::{guids, insert_guid};
+use crate::guid_db::{GuidDb, GuidKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockKind {
+    Drinks,
+    PickaxeParts,
+}
+
+impl UnlockKind {
+    /// Path of the GUID set holding the unlocked GUIDs of this kind.
+    pub fn set_path(self) -> &'static str {
+        match self {
+            Self::Drinks => "Drinks.UnlockedDrinks",
+            Self::PickaxeParts => "UnlockedPickaxeParts",
+        }
+    }
+
+    /// Kind of the [`GuidDb`] entries that can be unlocked.
+    pub fn guid_kind(self) -> GuidKind {
+        match self {
+            Self::Drinks => GuidKind::Drink,
+            Self::PickaxeParts => GuidKind::PickaxePart,
+        }
+    }
+}
+
+impl FromStr for UnlockKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drinks" => Ok(Self::Drinks),
+            "pickaxe-parts" => Ok(Self::PickaxeParts),
+            _ => Err(format!("expected `drinks` or `pickaxe-parts`, got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for UnlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Drinks => write!(f, "drinks"),
+            Self::PickaxeParts => write!(f, "pickaxe-parts"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Unlock {
+    pub guid: Uuid,
+    pub name: Option<String>,
+    pub unlocked: bool,
+}
+
+/// Every `kind` GUID known to `db` or unlocked in the save, and whether it is unlocked. Without
+/// `kind` entries in `db` this is only what is unlocked.
+pub fn list_unlocks(props: &Properties, kind: UnlockKind, db: &GuidDb) -> Result<Vec<Unlock>> {
+    let unlocked = guids(props, kind.set_path())?;
+    let mut unlocks: Vec<_> = db
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == kind.guid_kind())
+        .map(|entry| Unlock {
+            guid: entry.guid,
+            name: Some(entry.name.clone()),
+            unlocked: unlocked.contains(&entry.guid),
+        })
+        .collect();
+    for guid in unlocked {
+        if !unlocks.iter().any(|unlock| unlock.guid == guid) {
+            unlocks.push(Unlock { guid, name: db.name(guid).map(str::to_string), unlocked: true });
+        }
+    }
+    Ok(unlocks)
+}
+
+/// Find the GUID of the `kind` unlock called `name` (or given as a GUID).
+pub fn resolve_unlock(kind: UnlockKind, name: &str, db: &GuidDb) -> Result<Uuid> {
+    if let Ok(guid) = name.parse() {
+        return Ok(guid);
+    }
+    db.require(kind.guid_kind())?;
+    db.find(kind.guid_kind(), name)
+        .ok_or_else(|| DrgSaveError::InvalidInput(format!("unknown {kind} unlock `{name}`")))
+}
+
+/// Unlock `guid`. Returns whether it was locked before.
+pub fn unlock(props: &mut Properties, kind: UnlockKind, guid: Uuid) -> Result<bool> {
+    insert_guid(props, kind.set_path(), guid)
+}
@@ -0,0 +1,44 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uesave::{Properties, Property, PropertyKey};
+
+use crate::error::{DrgSaveError, Result};
+
+/// Deep copy a value through its serde representation. `uesave` types don't implement `Clone`.
+/// This fails for values JSON can't represent, e.g. a `NaN` float read from a corrupt save.
+pub(crate) fn clone_via_serde<T: Serialize + DeserializeOwned>(value: &T) -> Result<T> {
+    serde_json::to_value(value)
+        .and_then(serde_json::from_value)
+        .map_err(|e| DrgSaveError::UnexpectedShape(format!("failed to copy value: {e}")))
+}
+
+/// Look up the property called `name` (with index 0) in `props`.
+pub fn property<'a>(props: &'a Properties, name: &str) -> Option<&'a Property> {
+    props.0.get(&PropertyKey::from(name))
+}
+
+/// Mutable counterpart of [`property`].
+pub fn property_mut<'a>(props: &'a mut Properties, name: &str) -> Option<&'a mut Property> {
+    props.0.get_mut(&PropertyKey::from(name))
+}
+
+/// Look up the `Property::Int` called `name` in `props`.
+pub(crate) fn int_mut<'a>(props: &'a mut Properties, name: &str) -> Result<&'a mut i32> {
+    match property_mut(props, name) {
+        Some(Property::Int { value, .. }) => Ok(value),
+        Some(prop) => Err(DrgSaveError::type_mismatch(name, "Int", prop)),
+        None => Err(DrgSaveError::missing(name)),
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+/// Milliseconds since the Unix epoch.
+pub(crate) fn unix_timestamp_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis() as u64)
+}
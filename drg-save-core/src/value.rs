@@ -0,0 +1,58 @@
+//! Reading and writing the value of scalar properties (ints, floats, strings, ...) without
+//! matching on every `Property` variant, through their serde representation.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use uesave::Property;
+
+use crate::describe::property_type_name;
+use crate::error::DrgSaveError;
+
+/// The value of `prop` if it is a scalar.
+pub fn property_value(prop: &Property) -> Option<Value> {
+    match prop {
+        Property::Struct { .. }
+        | Property::Array { .. }
+        | Property::Set { .. }
+        | Property::Map { .. } => None,
+        _ => serialized_value(prop),
+    }
+}
+
+/// The serialized `value` field of `prop`. Every `Property` variant stores its payload in one.
+pub(crate) fn serialized_value(prop: &Property) -> Option<Value> {
+    match serde_json::to_value(prop).ok()? {
+        Value::Object(variant) => variant.into_iter().next()?.1.get("value").cloned(),
+        _ => None,
+    }
+}
+
+/// Replace the `value` field of the scalar property `prop` with `value`, keeping its type.
+pub fn set_property_value(prop: &mut Property, value: Value) -> Result<()> {
+    let type_name = property_type_name(prop);
+    if property_value(prop).is_none() {
+        bail!(DrgSaveError::InvalidInput(format!(
+            "cannot set the value of a {type_name} property"
+        )));
+    }
+    let mut json = serde_json::to_value(&*prop)?;
+    let Some(fields) = json
+        .as_object_mut()
+        .and_then(|variant| variant.values_mut().next())
+        .and_then(Value::as_object_mut)
+    else {
+        bail!(DrgSaveError::UnexpectedShape(format!(
+            "unexpected serialized form of {type_name} property"
+        )));
+    };
+    fields.insert("value".to_string(), value);
+    *prop = serde_json::from_value(json)
+        .with_context(|| format!("value is not valid for a {type_name} property"))?;
+    Ok(())
+}
+
+/// Parse a value given on the command line: JSON if it is valid JSON (`24`, `1.5`, `true`,
+/// `"quoted"`), a string otherwise.
+pub fn parse_value(s: &str) -> Value {
+    serde_json::from_str(s).unwrap_or_else(|_| Value::String(s.to_string()))
+}
@@ -0,0 +1,267 @@
+//! Which game build a save was written by, judged from the engine version in its header.
+//!
+//! The engine and package versions only change with engine upgrades, which DRG rarely does, but
+//! the changelist of the engine version is that of the game build, so it changes with every
+//! release. The editing logic assumes a certain layout (property names, class save slots, ...)
+//! that was validated against saves of the builds in [`KNOWN_VERSIONS`]. A save from a newer build
+//! may be laid out differently, so editing one is refused unless forced.
+//!
+//! The experimental branch adds properties ahead of live. Saves from a known or newer build with
+//! top-level properties beyond the [`LIVE_PROPERTIES`] of the known builds are recognized as
+//! experimental: the extra properties are warned about and left alone, and the version check
+//! applies as for any other save.
+
+use std::fmt;
+
+use serde::Serialize;
+use tracing::*;
+use uesave::{Header, Save};
+
+use crate::error::{DrgSaveError, Result};
+
+/// Version information from a save header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SaveVersion {
+    pub save_game_version: u32,
+    /// UE4 object version of the package.
+    pub package_version: u32,
+    /// Engine version as `(major, minor, patch)`.
+    pub engine: (u16, u16, u16),
+    /// Changelist the game was built from, without the licensee flag.
+    pub changelist: u32,
+    /// Branch the engine was built from, e.g. `++UE4+Release-4.27` or `main`.
+    pub engine_branch: String,
+}
+
+/// Set in the changelist of engine versions built by licensees (i.e. the game developer) rather
+/// than by Epic.
+const LICENSEE_CHANGELIST_FLAG: u32 = 1 << 31;
+
+impl SaveVersion {
+    pub fn of(save: &Save) -> Self {
+        let header = &save.header;
+        Self {
+            save_game_version: header.save_game_version,
+            package_version: ue4_package_version(header),
+            engine: (
+                header.engine_version_major,
+                header.engine_version_minor,
+                header.engine_version_patch,
+            ),
+            changelist: header.engine_version_build & !LICENSEE_CHANGELIST_FLAG,
+            engine_branch: header.engine_version.clone(),
+        }
+    }
+}
+
+/// The UE4 package version of `header`. `uesave` keeps the fields of [`uesave::PackageVersion`]
+/// private and only exposes them through serde.
+fn ue4_package_version(header: &Header) -> u32 {
+    serde_json::to_value(&header.package_version)
+        .ok()
+        .and_then(|version| version.get("ue4")?.as_u64())
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or_default()
+}
+
+impl fmt::Display for SaveVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (major, minor, patch) = self.engine;
+        write!(
+            f,
+            "engine {major}.{minor}.{patch}-{} ({}), package version {}, save game version {}",
+            self.changelist, self.engine_branch, self.package_version, self.save_game_version
+        )
+    }
+}
+
+/// A game build the editing logic was validated against.
+#[derive(Debug)]
+pub struct KnownVersion {
+    /// Which game release this is.
+    pub game: &'static str,
+    pub changelist: u32,
+}
+
+/// Game builds the editing logic was validated against, oldest first. Add an entry once the
+/// editors were checked against saves of a new build (see `drg-save stats` for the changelist of
+/// a save).
+pub const KNOWN_VERSIONS: &[KnownVersion] = &[
+    // The sample save `uesave` is tested with (`LastShownVersion` 1.36).
+    KnownVersion { game: "Update 36", changelist: 76_700 },
+];
+
+/// Top-level properties saves of the [`KNOWN_VERSIONS`] have, as in the sample save (`drg-save
+/// schema` lists them). Add the properties a live release introduces along with its entry in
+/// [`KNOWN_VERSIONS`].
+pub const LIVE_PROPERTIES: &[&str] = &[
+    "VersionNumber",
+    "SaveSlotTimeStamp",
+    "MissionStatsSave",
+    "Milestones",
+    "OwnedPerks",
+    "EquippedPerkLoadouts",
+    "VanityMasterySave",
+    "OnVanityMasteryChanged",
+    "SchematicSave",
+    "FSDEventRewardsSave",
+    "SeasonSave",
+    "GameDLCSave",
+    "SaveCreatedInPatch",
+    "AnonymousID",
+    "PerkPoints",
+    "HasRecievedDiscordReward",
+    "UnLockedMissions",
+    "UnLockedMissionParameters",
+    "UnLockedPlanetZones",
+    "CampaignSave",
+    "DeepDiveSave",
+    "CharacterSaves",
+    "Credits",
+    "LastBoughtDailyDealSeed",
+    "Forging",
+    "Drinks",
+    "ItemUpgradeLoadouts",
+    "PurchasedItemUpgrades",
+    "UnlockedItems",
+    "OwnedItems",
+    "EnemiesKilled",
+    "UnlockedItemSkins",
+    "UnlockedPickaxeParts",
+    "LastCollectedCommunityRewardPeriodID",
+    "MinersManualKnownObjects",
+    "ItemUINotifications",
+    "HaveSkinsBeenReset",
+    "bHasOpenedDeepDiveTerminal",
+    "Resources",
+    "FirstSession",
+    "WatchedTutorials",
+    "WindowsOptions",
+    "HasCompletedTutorial",
+    "HasPlayedTutorial",
+    "HasPlayedIntroMessage",
+    "IsBoscoAllowed",
+    "AllowAnalyticsTracking",
+    "AllowPersonalAnalyticsTracking",
+    "TotalPlayTimeSeconds",
+    "SaveToDiskCounter",
+    "BackupSaveIndex",
+    "ExternalBackupSaveIndex",
+    "NumberOfGamesPlayed",
+    "LastPlayedCharacter",
+    "ShowInfoScreen",
+    "LastShownVersion",
+];
+
+/// Top-level properties of `save` that no known live build writes, i.e. that the experimental
+/// branch added ahead of live. Any at all means the save is from the experimental branch. Saves
+/// from builds older than the known ones are never taken for experimental.
+pub fn experimental_properties(save: &Save) -> Vec<String> {
+    if matches!(compatibility(&SaveVersion::of(save)), Compatibility::Older) {
+        return vec![];
+    }
+    save.root
+        .properties
+        .0
+        .keys()
+        .map(|key| key.1.as_str())
+        .filter(|name| !LIVE_PROPERTIES.contains(name))
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Compatibility {
+    Known(&'static KnownVersion),
+    /// Older than every known version: old backups are usually still fine to read, but edits may
+    /// fail on properties that didn't exist yet.
+    Older,
+    /// Newer than every known version: the layout may have changed in ways the editors don't
+    /// notice.
+    Newer,
+}
+
+pub fn compatibility(version: &SaveVersion) -> Compatibility {
+    if let Some(known) = KNOWN_VERSIONS.iter().find(|known| known.changelist == version.changelist)
+    {
+        return Compatibility::Known(known);
+    }
+    let newest = KNOWN_VERSIONS.iter().map(|known| known.changelist).max();
+    if newest.is_some_and(|newest| version.changelist > newest) {
+        Compatibility::Newer
+    } else {
+        Compatibility::Older
+    }
+}
+
+/// Check that `save` is from a version the editing logic is known to work with. Saves from newer
+/// versions are refused unless `force` is set, experimental ones included; anything else unknown
+/// is only warned about.
+pub fn check_version(save: &Save, force: bool) -> Result<()> {
+    let version = SaveVersion::of(save);
+    let experimental = experimental_properties(save);
+    if !experimental.is_empty() {
+        warn!(
+            "save is from the experimental branch ({version}), its properties {} are left alone",
+            experimental.join(", ")
+        );
+    }
+    match compatibility(&version) {
+        Compatibility::Known(known) => debug!("save is from {} ({version})", known.game),
+        Compatibility::Older => {
+            warn!(
+                "save is from a game version older than the known ones ({version}), edits may fail"
+            )
+        }
+        Compatibility::Newer if force => {
+            warn!(
+                "save is from a game version newer than the known ones ({version}), editing anyway"
+            )
+        }
+        Compatibility::Newer => {
+            return Err(DrgSaveError::UnsupportedVersion { version: version.to_string() })
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uesave::{Property, PropertyKey};
+
+    use super::*;
+    use crate::fixtures::synthetic_save;
+
+    fn with_experimental_property(mut save: Save) -> Save {
+        save.root.properties.0.insert(
+            PropertyKey::from("FutureFeatureSave"),
+            Property::Bool { id: None, value: true },
+        );
+        save
+    }
+
+    #[test]
+    fn live_saves_are_not_experimental() {
+        let save = synthetic_save();
+        assert!(experimental_properties(&save).is_empty());
+        assert!(check_version(&save, false).is_ok());
+    }
+
+    #[test]
+    fn experimental_saves_are_still_version_checked() {
+        let mut save = with_experimental_property(synthetic_save());
+        assert_eq!(experimental_properties(&save), ["FutureFeatureSave"]);
+        assert!(check_version(&save, false).is_ok());
+
+        save.header.engine_version_build += 1;
+        assert!(matches!(
+            check_version(&save, false),
+            Err(DrgSaveError::UnsupportedVersion { .. })
+        ));
+        assert!(check_version(&save, true).is_ok());
+
+        // Older saves may have properties that were removed since.
+        save.header.engine_version_build -= 2;
+        assert!(experimental_properties(&save).is_empty());
+    }
+}
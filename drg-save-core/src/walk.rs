@@ -0,0 +1,205 @@
+use uesave::{Properties, Property, PropertyKey, PropertyValue, StructValue, ValueArray, ValueSet};
+
+/// Visit every property below `props` depth-first, parents before their children, together with
+/// its path (e.g. `CharacterSaves[0].TimesRetired`). Properties sharing a name (the elements of a
+/// static array) are told apart by their index after the name, e.g. `Ammo#1`, except for the
+/// first.
+pub fn walk_properties<'a>(props: &'a Properties, f: &mut impl FnMut(&str, &'a Property)) {
+    walk_properties_inner("", props, f);
+}
+
+fn walk_properties_inner<'a>(
+    prefix: &str,
+    props: &'a Properties,
+    f: &mut impl FnMut(&str, &'a Property),
+) {
+    for (key, prop) in props {
+        let path = join_path(prefix, key);
+        f(&path, prop);
+        match prop {
+            Property::Struct { value: StructValue::Struct(props), .. } => {
+                walk_properties_inner(&path, props, f)
+            }
+            Property::Array { value: ValueArray::Struct { value, .. }, .. }
+            | Property::Set { value: ValueSet::Struct(value), .. } => {
+                for (i, value) in value.iter().enumerate() {
+                    if let StructValue::Struct(props) = value {
+                        walk_properties_inner(&format!("{path}[{i}]"), props, f);
+                    }
+                }
+            }
+            Property::Map { value, .. } => {
+                for (i, entry) in value.iter().enumerate() {
+                    if let PropertyValue::Struct(StructValue::Struct(props)) = &entry.value {
+                        walk_properties_inner(&format!("{path}[{i}]"), props, f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Mutable counterpart of [`walk_properties`].
+pub fn walk_properties_mut(props: &mut Properties, f: &mut impl FnMut(&str, &mut Property)) {
+    walk_properties_mut_inner("", props, f);
+}
+
+fn walk_properties_mut_inner(
+    prefix: &str,
+    props: &mut Properties,
+    f: &mut impl FnMut(&str, &mut Property),
+) {
+    for (key, prop) in props.0.iter_mut() {
+        let path = join_path(prefix, key);
+        f(&path, prop);
+        match prop {
+            Property::Struct { value: StructValue::Struct(props), .. } => {
+                walk_properties_mut_inner(&path, props, f)
+            }
+            Property::Array { value: ValueArray::Struct { value, .. }, .. }
+            | Property::Set { value: ValueSet::Struct(value), .. } => {
+                for (i, value) in value.iter_mut().enumerate() {
+                    if let StructValue::Struct(props) = value {
+                        walk_properties_mut_inner(&format!("{path}[{i}]"), props, f);
+                    }
+                }
+            }
+            Property::Map { value, .. } => {
+                for (i, entry) in value.iter_mut().enumerate() {
+                    if let PropertyValue::Struct(StructValue::Struct(props)) = &mut entry.value {
+                        walk_properties_mut_inner(&format!("{path}[{i}]"), props, f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The path of the property `key` in the struct at `prefix`.
+pub(crate) fn join_path(prefix: &str, key: &PropertyKey) -> String {
+    let segment = match key.0 {
+        0 => key.1.clone(),
+        index => format!("{}#{index}", key.1),
+    };
+    if prefix.is_empty() {
+        segment
+    } else {
+        format!("{prefix}.{segment}")
+    }
+}
+
+/// The key of the property a path segment without array index refers to, e.g. `Ammo#1` for the
+/// second property called `Ammo`. The inverse of [`join_path`].
+pub fn property_key(segment: &str) -> PropertyKey {
+    match segment.rsplit_once('#').and_then(|(name, index)| Some((name, index.parse().ok()?))) {
+        Some((name, index)) => PropertyKey(index, name.to_string()),
+        None => PropertyKey::from(segment),
+    }
+}
+
+/// Resolve the path of a struct (e.g. `CharacterSaves[0]`, as produced by [`walk_properties`]) to
+/// the properties inside it. The empty path refers to `props` itself.
+pub fn properties_at<'a>(props: &'a Properties, path: &str) -> Option<&'a Properties> {
+    if path.is_empty() {
+        return Some(props);
+    }
+    path.split('.').try_fold(props, |props, segment| {
+        let (name, index) = match segment.strip_suffix(']').and_then(|s| s.split_once('[')) {
+            Some((name, index)) => (name, Some(index.parse::<usize>().ok()?)),
+            None => (segment, None),
+        };
+        let prop = props.0.get(&property_key(name))?;
+        let value = match (prop, index) {
+            (Property::Struct { value, .. }, None) => value,
+            (Property::Array { value: ValueArray::Struct { value, .. }, .. }, Some(i))
+            | (Property::Set { value: ValueSet::Struct(value), .. }, Some(i)) => value.get(i)?,
+            (Property::Map { value, .. }, Some(i)) => match &value.get(i)?.value {
+                PropertyValue::Struct(value) => value,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match value {
+            StructValue::Struct(props) => Some(props),
+            _ => None,
+        }
+    })
+}
+
+/// Mutable counterpart of [`properties_at`].
+pub fn properties_at_mut<'a>(props: &'a mut Properties, path: &str) -> Option<&'a mut Properties> {
+    if path.is_empty() {
+        return Some(props);
+    }
+    path.split('.').try_fold(props, |props, segment| {
+        let (name, index) = match segment.strip_suffix(']').and_then(|s| s.split_once('[')) {
+            Some((name, index)) => (name, Some(index.parse::<usize>().ok()?)),
+            None => (segment, None),
+        };
+        let prop = props.0.get_mut(&property_key(name))?;
+        let value = match (prop, index) {
+            (Property::Struct { value, .. }, None) => value,
+            (Property::Array { value: ValueArray::Struct { value, .. }, .. }, Some(i))
+            | (Property::Set { value: ValueSet::Struct(value), .. }, Some(i)) => {
+                value.get_mut(i)?
+            }
+            (Property::Map { value, .. }, Some(i)) => match &mut value.get_mut(i)?.value {
+                PropertyValue::Struct(value) => value,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match value {
+            StructValue::Struct(props) => Some(props),
+            _ => None,
+        }
+    })
+}
+
+/// Look up the property at `path` (e.g. `CharacterSaves[0].TimesRetired`).
+pub fn property_at<'a>(props: &'a Properties, path: &str) -> Option<&'a Property> {
+    let mut found = None;
+    walk_properties(props, &mut |prop_path, prop| {
+        if found.is_none() && prop_path == path {
+            found = Some(prop);
+        }
+    });
+    found
+}
+
+/// Mutable counterpart of [`property_at`].
+pub fn property_at_mut<'a>(props: &'a mut Properties, path: &str) -> Option<&'a mut Property> {
+    let (parent, name) = split_path(path);
+    properties_at_mut(props, parent)?.0.get_mut(&property_key(name))
+}
+
+/// Split a property path into the path of its parent struct and its name.
+pub fn split_path(path: &str) -> (&str, &str) {
+    path.rsplit_once('.').unwrap_or(("", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_apart_properties_sharing_a_name() {
+        let mut props = Properties::default();
+        for index in 0..2 {
+            let prop = Property::Int { id: None, value: index as i32 };
+            props.0.insert(PropertyKey(index, "Ammo".to_string()), prop);
+        }
+
+        let mut paths = vec![];
+        walk_properties(&props, &mut |path, _| paths.push(path.to_string()));
+        assert_eq!(paths, ["Ammo", "Ammo#1"]);
+        for (index, path) in paths.iter().enumerate() {
+            assert_eq!(property_key(path), PropertyKey(index as u32, "Ammo".to_string()));
+        }
+        *property_at_mut(&mut props, "Ammo#1").unwrap() = Property::Int { id: None, value: 7 };
+        assert_eq!(property_at(&props, "Ammo"), Some(&Property::Int { id: None, value: 0 }));
+        assert_eq!(property_at(&props, "Ammo#1"), Some(&Property::Int { id: None, value: 7 }));
+    }
+}
@@ -0,0 +1,77 @@
+//! Class level math.
+//!
+//! A class save's `XP` is the XP earned since its last promotion. The level shown in-game is the
+//! highest level whose cumulative XP requirement it meets, capped at [`MAX_LEVEL`].
+
+use crate::error::{DrgSaveError, Result};
+
+/// Highest level a class can reach before it has to be promoted.
+pub const MAX_LEVEL: u8 = 25;
+
+/// Cumulative XP needed to reach each level, starting at level 1.
+const LEVEL_XP: [i32; MAX_LEVEL as usize] = [
+    0, 3_000, 7_000, 12_000, 18_000, 25_000, 33_000, 42_000, 52_000, 63_000, 75_000, 88_000,
+    102_000, 117_000, 132_500, 148_500, 165_000, 182_000, 199_500, 217_500, 236_000, 255_000,
+    274_500, 294_500, 315_000,
+];
+
+/// Cumulative XP needed to reach `level`, or `None` if there is no such level.
+pub fn xp_for_level(level: u8) -> Option<i32> {
+    LEVEL_XP.get(usize::from(level).checked_sub(1)?).copied()
+}
+
+/// Level shown in-game for a class with `xp`, and how much XP it has into that level.
+pub fn level_for_xp(xp: i32) -> (u8, i32) {
+    let index = LEVEL_XP.iter().rposition(|required| *required <= xp).unwrap_or(0);
+    ((index + 1) as u8, xp - LEVEL_XP[index])
+}
+
+/// `XP` a class save needs to be shown at `level` with `xp_into_level` towards the next one.
+/// Fails if `xp_into_level` would already reach the next level.
+pub fn xp_for(level: u8, xp_into_level: i32) -> Result<i32> {
+    let Some(base) = xp_for_level(level) else {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "level must be between 1 and {MAX_LEVEL}, got {level}"
+        )));
+    };
+    let needed = xp_for_level(level + 1).map_or(0, |next| next - base);
+    if xp_into_level < 0 || (level < MAX_LEVEL && xp_into_level >= needed) {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "XP into level {level} must be between 0 and {}, got {xp_into_level}",
+            needed - 1
+        )));
+    }
+    if level == MAX_LEVEL && xp_into_level != 0 {
+        return Err(DrgSaveError::InvalidInput(format!(
+            "level {MAX_LEVEL} is the highest level, XP into it must be 0"
+        )));
+    }
+    Ok(base + xp_into_level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_round_trips() {
+        for level in 1..=MAX_LEVEL {
+            assert_eq!(level_for_xp(xp_for(level, 0).unwrap()), (level, 0));
+        }
+        assert_eq!(level_for_xp(xp_for(3, 4_999).unwrap()), (3, 4_999));
+    }
+
+    #[test]
+    fn xp_past_max_level_stays_at_max_level() {
+        assert_eq!(level_for_xp(400_000), (MAX_LEVEL, 85_000));
+    }
+
+    #[test]
+    fn invalid_levels() {
+        assert!(xp_for(0, 0).is_err());
+        assert!(xp_for(MAX_LEVEL + 1, 0).is_err());
+        assert!(xp_for(1, 3_000).is_err());
+        assert!(xp_for(1, -1).is_err());
+        assert!(xp_for(MAX_LEVEL, 1).is_err());
+    }
+}
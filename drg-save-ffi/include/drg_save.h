@@ -0,0 +1,25 @@
+/* C interface to drg-save-core. See drg-save-ffi/src/lib.rs for documentation. */
+
+#ifndef DRG_SAVE_H
+#define DRG_SAVE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct DrgSave DrgSave;
+
+const char *drg_save_last_error(void);
+
+DrgSave *drg_save_open(const char *path);
+char *drg_save_get(const DrgSave *save, const char *path);
+int drg_save_set(DrgSave *save, const char *path, const char *json);
+int drg_save_write(DrgSave *save, const char *path);
+void drg_save_free(DrgSave *save);
+void drg_save_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DRG_SAVE_H */
@@ -0,0 +1,285 @@
+//! C ABI over `drg-save-core` for save editors written in other languages. See
+//! `include/drg_save.h` for the declarations.
+//!
+//! Functions returning a pointer return null on failure, functions returning an `int` return 0
+//! on success and -1 on failure. After a failure, [`drg_save_last_error`] describes what went
+//! wrong, including panics, which are caught instead of unwinding into the caller. Strings
+//! returned by this library must be released with [`drg_save_string_free`].
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+use anyhow::{anyhow, Context, Result};
+use drg_save_core::session::Digest;
+use drg_save_core::walk::{property_at, property_at_mut};
+use uesave::{Property, Save};
+
+/// Opaque handle to a parsed save file.
+pub struct DrgSave {
+    save: Save,
+    path: PathBuf,
+    /// Digest of the save file at `path` as of opening or the last write.
+    read: Digest,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(e: anyhow::Error) {
+    let message = CString::new(format!("{e:#}").replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Run `f`, turning a panic into an error so it doesn't unwind across the C ABI. A handle `f`
+/// panicked while modifying may be left half-modified, but is still safe to use and release.
+fn catch<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        Err(anyhow!("panicked: {message}"))
+    })
+}
+
+/// # Safety
+///
+/// `s` must be null or a valid NUL-terminated string.
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Result<&'a str> {
+    if s.is_null() {
+        return Err(anyhow!("`{name}` is null"));
+    }
+    CStr::from_ptr(s).to_str().with_context(|| format!("`{name}` is not valid UTF-8"))
+}
+
+fn status(result: Result<()>) -> c_int {
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(e);
+            -1
+        }
+    }
+}
+
+/// Message describing the last failure on this thread, or null if nothing failed yet. The
+/// string is owned by the library and valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn drg_save_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Read and parse the save file at `path`. Release the handle with [`drg_save_free`].
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn drg_save_open(path: *const c_char) -> *mut DrgSave {
+    let result = catch(|| {
+        let path = PathBuf::from(str_arg(path, "path")?);
+        let (save, read) = drg_save_core::read_for_write_back(&path)?;
+        Ok(DrgSave { save, path, read })
+    });
+    match result {
+        Ok(save) => Box::into_raw(Box::new(save)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The property at `path` (e.g. `CharacterSaves[0].TimesRetired`) as a JSON string.
+///
+/// # Safety
+///
+/// `save` must be a handle returned by [`drg_save_open`] and `path` a valid NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn drg_save_get(save: *const DrgSave, path: *const c_char) -> *mut c_char {
+    let result = catch(|| {
+        let save = save.as_ref().context("`save` is null")?;
+        let path = str_arg(path, "path")?;
+        let prop = property_at(&save.save.root.properties, path)
+            .with_context(|| format!("`{path}` not found"))?;
+        Ok(CString::new(serde_json::to_string(prop)?)?)
+    });
+    match result {
+        Ok(json) => json.into_raw(),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Replace the property at `path` with the property given as JSON, in the format returned by
+/// [`drg_save_get`].
+///
+/// # Safety
+///
+/// `save` must be a handle returned by [`drg_save_open`], `path` and `json` valid NUL-terminated
+/// strings.
+#[no_mangle]
+pub unsafe extern "C" fn drg_save_set(
+    save: *mut DrgSave,
+    path: *const c_char,
+    json: *const c_char,
+) -> c_int {
+    status(catch(|| {
+        let save = save.as_mut().context("`save` is null")?;
+        let path = str_arg(path, "path")?;
+        let value: Property = serde_json::from_str(str_arg(json, "json")?)?;
+        let prop = property_at_mut(&mut save.save.root.properties, path)
+            .with_context(|| format!("`{path}` not found"))?;
+        *prop = value;
+        Ok(())
+    }))
+}
+
+/// Write the save to `path`, or back to the file it was opened from if `path` is null. The save
+/// is verified to round trip first. Writes over an existing file lock, back up and journal it like
+/// `drg-save` does, so they can be undone with `drg-save undo`, and fail for saves from game
+/// versions newer than this library supports. Writing back fails if another tool wrote the file
+/// since it was opened.
+///
+/// # Safety
+///
+/// `save` must be a handle returned by [`drg_save_open`] and `path` null or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn drg_save_write(save: *mut DrgSave, path: *const c_char) -> c_int {
+    status(catch(|| {
+        let save = save.as_mut().context("`save` is null")?;
+        let path =
+            if path.is_null() { save.path.clone() } else { PathBuf::from(str_arg(path, "path")?) };
+        if !path.exists() {
+            return drg_save_core::write_save_verified(&path, &save.save);
+        }
+        let read = (path == save.path).then_some(&save.read);
+        let written = drg_save_core::write_back(
+            &path,
+            "drg-save-ffi",
+            Default::default(),
+            read,
+            &mut save.save,
+        )?;
+        if path == save.path {
+            save.read = written;
+        }
+        Ok(())
+    }))
+}
+
+/// Release a handle returned by [`drg_save_open`]. Null is ignored.
+///
+/// # Safety
+///
+/// `save` must be null or a handle returned by [`drg_save_open`] that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn drg_save_free(save: *mut DrgSave) {
+    if !save.is_null() {
+        drop(Box::from_raw(save));
+    }
+}
+
+/// Release a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn drg_save_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_SAVE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../drg-save-core/testdata/drg-save-test.sav");
+
+    fn c_string(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn last_error() -> String {
+        let e = drg_save_last_error();
+        assert!(!e.is_null());
+        unsafe { CStr::from_ptr(e) }.to_str().unwrap().to_string()
+    }
+
+    /// The property at `path` of `save`, parsed back from JSON.
+    unsafe fn get(save: *const DrgSave, path: &str) -> Property {
+        let json = drg_save_get(save, c_string(path).as_ptr());
+        assert!(!json.is_null(), "{}", last_error());
+        let prop = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+        drg_save_string_free(json);
+        prop
+    }
+
+    #[test]
+    fn reads_edits_and_writes_the_sample_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let copy = c_string(dir.path().join("copy.sav").to_str().unwrap());
+        unsafe {
+            let save = drg_save_open(c_string(SAMPLE_SAVE).as_ptr());
+            assert!(!save.is_null(), "{}", last_error());
+            let path = c_string("CharacterSaves[0].TimesRetired");
+            assert!(matches!(
+                get(save, "CharacterSaves[0].TimesRetired"),
+                Property::Int { value: 83, .. }
+            ));
+            let json = c_string(r#"{"Int":{"value":84}}"#);
+            assert_eq!(drg_save_set(save, path.as_ptr(), json.as_ptr()), 0, "{}", last_error());
+            assert_eq!(drg_save_write(save, copy.as_ptr()), 0, "{}", last_error());
+            drg_save_free(save);
+
+            let save = drg_save_open(copy.as_ptr());
+            assert!(!save.is_null(), "{}", last_error());
+            assert!(matches!(
+                get(save, "CharacterSaves[0].TimesRetired"),
+                Property::Int { value: 84, .. }
+            ));
+            drg_save_free(save);
+        }
+    }
+
+    #[test]
+    fn reports_null_pointers_and_errors() {
+        let path = c_string("Credits");
+        unsafe {
+            assert!(drg_save_open(ptr::null()).is_null());
+            assert_eq!(last_error(), "`path` is null");
+            assert!(drg_save_open(c_string("missing.sav").as_ptr()).is_null());
+            assert!(last_error().contains("missing.sav"));
+            assert!(drg_save_get(ptr::null(), path.as_ptr()).is_null());
+            assert_eq!(last_error(), "`save` is null");
+            assert_eq!(drg_save_set(ptr::null_mut(), path.as_ptr(), path.as_ptr()), -1);
+            assert_eq!(drg_save_write(ptr::null_mut(), ptr::null()), -1);
+            assert_eq!(last_error(), "`save` is null");
+
+            let save = drg_save_open(c_string(SAMPLE_SAVE).as_ptr());
+            assert!(!save.is_null(), "{}", last_error());
+            assert!(drg_save_get(save, c_string("NoSuchProperty").as_ptr()).is_null());
+            assert_eq!(last_error(), "`NoSuchProperty` not found");
+            assert!(drg_save_get(save, ptr::null()).is_null());
+            assert_eq!(last_error(), "`path` is null");
+            assert_eq!(drg_save_set(save, path.as_ptr(), c_string("not json").as_ptr()), -1);
+            assert!(matches!(get(save, "Credits"), Property::Int { value: 5_837_964, .. }));
+            drg_save_free(save);
+
+            drg_save_free(ptr::null_mut());
+            drg_save_string_free(ptr::null_mut());
+        }
+    }
+}
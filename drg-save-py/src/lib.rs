@@ -0,0 +1,160 @@
+//! Python bindings to `drg-save-core`, built with `maturin`:
+//!
+//! ```text
+//! $ cd drg-save-py && maturin develop
+//! >>> import drg_save
+//! >>> save = drg_save.load("path/to/save.sav")
+//! >>> save.get("CharacterSaves[0].TimesRetired")
+//! >>> save.blue_level()
+//! ```
+//!
+//! Properties are converted to and from Python objects through their serde representation.
+
+use std::path::PathBuf;
+
+use drg_save_core::classes::class_saves;
+use drg_save_core::index::PathIndex;
+use drg_save_core::session::Digest;
+use drg_save_core::SessionOptions;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use uesave::Property;
+
+/// A parsed save file.
+#[pyclass(name = "Save")]
+struct PySave {
+    save: uesave::Save,
+    path: PathBuf,
+    /// Digest of the save file at `path` as of loading or the last write.
+    read: Digest,
+    index: PathIndex,
+}
+
+#[pymethods]
+impl PySave {
+    /// The path the save was loaded from.
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// The property at `path` (e.g. `CharacterSaves[0].TimesRetired`).
+    fn get(&mut self, py: Python<'_>, path: &str) -> PyResult<PyObject> {
+        let Some(prop) = self.index.get(&self.save.root.properties, path) else {
+            return Err(PyKeyError::new_err(path.to_string()));
+        };
+        Ok(pythonize::pythonize(py, prop)?)
+    }
+
+    /// Replace the property at `path` with `value`, in the format returned by `get`.
+    fn set(&mut self, path: &str, value: Bound<'_, PyAny>) -> PyResult<()> {
+        let value: Property = pythonize::depythonize_bound(value)?;
+        if self.index.replace(&mut self.save.root.properties, path, value).is_none() {
+            return Err(PyKeyError::new_err(path.to_string()));
+        }
+        Ok(())
+    }
+
+    /// The blue level of the save.
+    fn blue_level(&self) -> PyResult<i32> {
+        let class_saves = class_saves(&self.save).map_err(value_error)?;
+        drg_save_core::blue_level::blue_level(class_saves).map_err(value_error)
+    }
+
+    /// The whole save as nested Python objects.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(pythonize::pythonize(py, &self.save)?)
+    }
+
+    /// Write the save back to the file it was loaded from, or to `path`. The save is verified to
+    /// round trip first. Writes over an existing file lock, back up and journal it like `drg-save`
+    /// does, so they can be undone, and refuse saves from newer game versions unless `force` is
+    /// set. Writing back fails if another tool wrote the file since it was loaded.
+    #[pyo3(signature = (path = None, force = false))]
+    fn write(&mut self, path: Option<PathBuf>, force: bool) -> PyResult<()> {
+        let path = path.unwrap_or_else(|| self.path.clone());
+        if !path.exists() {
+            return drg_save_core::write_save_verified(&path, &self.save).map_err(value_error);
+        }
+        let options = SessionOptions { force, ..Default::default() };
+        let read = (path == self.path).then_some(&self.read);
+        let written =
+            drg_save_core::write_back(&path, "drg_save python", options, read, &mut self.save)
+                .map_err(value_error)?;
+        if path == self.path {
+            self.read = written;
+        }
+        Ok(())
+    }
+}
+
+/// Load and parse the save file at `path`.
+#[pyfunction]
+fn load(path: PathBuf) -> PyResult<PySave> {
+    let (save, read) = drg_save_core::read_for_write_back(&path).map_err(value_error)?;
+    Ok(PySave { save, path, read, index: PathIndex::new() })
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(format!("{e:#}"))
+}
+
+#[pymodule]
+fn drg_save(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySave>()?;
+    m.add_function(wrap_pyfunction!(load, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    const SAMPLE_SAVE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../drg-save-core/testdata/drg-save-test.sav");
+
+    /// Run `code` with the module imported as `drg_save` and the sample save's path as `path`.
+    fn run(code: &str) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "drg_save")?;
+            drg_save(&module)?;
+            let locals = PyDict::new_bound(py);
+            locals.set_item("drg_save", module)?;
+            locals.set_item("path", SAMPLE_SAVE)?;
+            py.run_bound(code, None, Some(&locals))
+        })
+    }
+
+    #[test]
+    fn reads_the_sample_save() {
+        run(r#"
+save = drg_save.load(path)
+assert save.get("CharacterSaves[0].TimesRetired") == {"Int": {"value": 83}}
+assert save.blue_level() == 4566
+assert "root" in save.to_dict()
+save.set("CharacterSaves[0].TimesRetired", {"Int": {"value": 84}})
+assert save.get("CharacterSaves[0].TimesRetired")["Int"]["value"] == 84
+"#)
+        .unwrap();
+    }
+
+    #[test]
+    fn raises_for_missing_properties_and_files() {
+        run(r#"
+save = drg_save.load(path)
+try:
+    save.get("NoSuchProperty")
+    raise AssertionError("no KeyError")
+except KeyError:
+    pass
+try:
+    drg_save.load("missing.sav")
+    raise AssertionError("no ValueError")
+except ValueError as e:
+    assert "missing.sav" in str(e)
+"#)
+        .unwrap();
+    }
+}
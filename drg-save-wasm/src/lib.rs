@@ -0,0 +1,100 @@
+//! JS-friendly bindings to `drg-save-core` for `wasm32-unknown-unknown`, e.g. for a browser-based
+//! save editor. Saves go in and out as bytes, properties as JSON strings.
+//!
+//! ```text
+//! $ wasm-pack build drg-save-wasm --target web
+//! ```
+
+use std::io::Cursor;
+
+use drg_save_core::blue_level;
+use drg_save_core::classes::class_saves;
+use drg_save_core::walk::{property_at, property_at_mut};
+use uesave::{Property, Save};
+use wasm_bindgen::prelude::*;
+
+/// A parsed save file.
+#[wasm_bindgen]
+pub struct SaveFile {
+    save: Save,
+}
+
+#[wasm_bindgen]
+impl SaveFile {
+    /// Parse the contents of a `.sav` file.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<SaveFile, JsError> {
+        let save = Save::read(&mut Cursor::new(bytes)).map_err(js_error)?;
+        Ok(Self { save })
+    }
+
+    /// Serialize the save, failing if it does not round trip (see
+    /// [`drg_save_core::serialize_save_verified`]).
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsError> {
+        drg_save_core::serialize_save_verified(&self.save).map_err(js_error)
+    }
+
+    /// The whole save as JSON.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        serde_json::to_string(&self.save).map_err(js_error)
+    }
+
+    /// The property at `path` (e.g. `CharacterSaves[0].TimesRetired`) as JSON.
+    pub fn get(&self, path: &str) -> Result<String, JsError> {
+        let Some(prop) = property_at(&self.save.root.properties, path) else {
+            return Err(JsError::new(&format!("`{path}` not found")));
+        };
+        serde_json::to_string(prop).map_err(js_error)
+    }
+
+    /// Replace the property at `path` with the property given as JSON, in the format returned
+    /// by [`SaveFile::get`].
+    pub fn set(&mut self, path: &str, json: &str) -> Result<(), JsError> {
+        let value: Property = serde_json::from_str(json).map_err(js_error)?;
+        let Some(prop) = property_at_mut(&mut self.save.root.properties, path) else {
+            return Err(JsError::new(&format!("`{path}` not found")));
+        };
+        *prop = value;
+        Ok(())
+    }
+
+    /// The blue level of the save.
+    #[wasm_bindgen(js_name = blueLevel)]
+    pub fn blue_level(&self) -> Result<i32, JsError> {
+        blue_level::blue_level(class_saves(&self.save).map_err(js_error)?).map_err(js_error)
+    }
+}
+
+/// Total red levels over all class save slots needed for `blue_level`.
+#[wasm_bindgen(js_name = redLevelsNeededFor)]
+pub fn red_levels_needed_for(blue_level: i32) -> Result<i32, JsError> {
+    blue_level::red_levels_needed_for(blue_level).map_err(js_error)
+}
+
+fn js_error(e: impl std::fmt::Display) -> JsError {
+    JsError::new(&format!("{e:#}"))
+}
+
+// Natively, as `JsError`s can only be created on wasm, failures aren't covered.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_SAVE: &[u8] = include_bytes!("../../drg-save-core/testdata/drg-save-test.sav");
+
+    #[test]
+    fn reads_edits_and_writes_the_sample_save() {
+        let mut save = SaveFile::new(SAMPLE_SAVE).unwrap();
+        let path = "CharacterSaves[0].TimesRetired";
+        assert_eq!(save.get(path).unwrap(), r#"{"Int":{"value":83}}"#);
+        assert_eq!(save.blue_level().unwrap(), 4566);
+        assert_eq!(save.to_bytes().unwrap(), SAMPLE_SAVE);
+
+        save.set(path, r#"{"Int":{"value":84}}"#).unwrap();
+        let edited = SaveFile::new(&save.to_bytes().unwrap()).unwrap();
+        assert_eq!(edited.get(path).unwrap(), r#"{"Int":{"value":84}}"#);
+        assert!(edited.to_json().unwrap().starts_with('{'));
+    }
+}
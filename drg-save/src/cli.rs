@@ -0,0 +1,176 @@
+//! The command line definition.
+
+use std::path::PathBuf;
+
+use clap::{CommandFactory, Parser, Subcommand};
+
+use crate::commands;
+use crate::output::OutputFormat;
+
+#[derive(Debug, Parser)]
+#[command(about = "Collection of DRG save file tools")]
+pub struct Args {
+    /// Format of the results printed on stdout. Defaults to `output` from the config file, or
+    /// text.
+    #[arg(long, value_enum, global = true)]
+    pub output: Option<OutputFormat>,
+    /// Print the changes an edit would make and ask before writing them.
+    #[arg(long, global = true)]
+    pub interactive: bool,
+    /// Edit saves from game versions newer than the ones this tool was validated against.
+    #[arg(long, global = true)]
+    pub force: bool,
+    /// When editing, keep top-level properties that can't be parsed as they are and write them
+    /// back unchanged, instead of failing.
+    #[arg(long, global = true)]
+    pub lenient: bool,
+    /// Don't back up saves before writing them, overriding `backup` from the config file.
+    #[arg(long, global = true)]
+    pub no_backup: bool,
+    /// Write backups to this directory instead of next to the save, overriding `backup-dir` from
+    /// the config file.
+    #[arg(long, global = true)]
+    pub backup_dir: Option<PathBuf>,
+    /// Use the save and backup locations of this profile from the config file.
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    #[command(flatten)]
+    pub log: logging::LogArgs,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Strip or randomize account-identifying data so a save can be shared in bug reports.
+    Anonymize(commands::anonymize::Args),
+    /// Apply a JSON Patch document (see `diff --format json-patch`) to a save.
+    ApplyPatch(commands::apply_patch::Args),
+    /// Replace a save with a snapshot from the snapshot store.
+    Checkout(commands::snapshot::CheckoutArgs),
+    /// Copy one class's levels, promotions and cosmetics onto another class save slot.
+    CloneClass(commands::clone_class::Args),
+    /// Print a shell completion script, e.g. for `~/.local/share/bash-completion/completions`.
+    Completions(commands::completions::CompletionsArgs),
+    /// List the victory move and cosmetics each class has equipped.
+    Cosmetics(commands::cosmetics::Args),
+    /// Inspect or update the database of known GUIDs.
+    #[command(subcommand)]
+    Db(commands::db::Args),
+    /// Print the properties that differ between two saves, as text or as a JSON Patch.
+    Diff(commands::diff::Args),
+    /// Apply one of the registered edit modules (e.g. `blue-number`) to a save.
+    Edit(commands::edit::Args),
+    /// List the progress of the weekly core hunt and of machine events.
+    Events(commands::events::Args),
+    /// Print the path, type and value of every property whose name matches a pattern.
+    Find(commands::find::Args),
+    /// Report the space used by the snapshot store and reclaim unreferenced snapshot objects.
+    Gc(commands::gc::Args),
+    /// Print the value of a single scalar or GUID property, for scripts.
+    Get(commands::get::Args),
+    /// Show the player rank title and class names, and whether the save stores or derives them.
+    Identity(commands::identity::Args),
+    /// Export or import a class's loadout.
+    #[command(subcommand)]
+    Loadout(commands::loadout::Args),
+    /// List the save files found in all Steam libraries and Proton prefixes, newest first.
+    Locate(commands::locate::Args),
+    /// List the snapshots in the snapshot store, most recent first.
+    Log(commands::snapshot::LogArgs),
+    /// Write a man page per command (including each edit module) into a directory, for packagers.
+    #[command(hide = true)]
+    Man(commands::completions::ManArgs),
+    /// Upgrade a save from an older game version (e.g. an old backup) to the current layout.
+    Migrate(commands::migrate::Args),
+    /// Print the milestone counters and lifetime statistics of a save.
+    Milestones(commands::milestones::Args),
+    /// Print the weekly deep dive completion and the completed assignments.
+    Missions(commands::missions::Args),
+    /// List what lost packs and cargo crates can still drop, and schematics recorded twice.
+    Pools(commands::pools::Args),
+    /// Add, list or remove profiles (named save and backup locations) in the config file.
+    #[command(subcommand)]
+    Profile(commands::profile::Args),
+    /// Read or patch the serialized value of a property, for data nothing else understands.
+    #[command(subcommand)]
+    Raw(commands::raw::Args),
+    /// Rewrite the account identifiers of a save copied to another account.
+    Rebind(commands::rebind::Args),
+    /// Explore and edit a save interactively, writing it only on `save`.
+    Repl(commands::repl::Args),
+    /// Render an overview of a save as Markdown or a standalone HTML page.
+    Report(commands::report::Args),
+    /// Reset a single class to level 1 with zero promotions.
+    ResetClass(commands::reset_class::Args),
+    /// Copy selected properties back from a backup, keeping everything else.
+    Restore(commands::restore::Args),
+    /// List the claim flags of rewards, e.g. to find rewards stuck as claimed.
+    Rewards(commands::rewards::Args),
+    /// Print the shape of a save (property names, types and lengths) without its values.
+    Schema(commands::schema::Args),
+    /// Inspect or change the seasonal challenges of a save.
+    #[command(subcommand)]
+    Season(commands::season::Args),
+    /// Serve a web UI on localhost to browse and edit a save.
+    Serve(commands::serve::Args),
+    /// Run `watch` as a background service, or show its status.
+    #[command(subcommand)]
+    Service(commands::service::Args),
+    /// Compute the blue level of hypothetical class promotions and levels, without any save.
+    Simulate(commands::simulate::Args),
+    /// Store a copy of a save in the snapshot store.
+    Snapshot(commands::snapshot::SnapshotArgs),
+    /// Print the blue level, per-class red levels, credits and resources of a save.
+    Stats(commands::stats::Args),
+    /// Copy the loadouts and cosmetics of each class from one save to another, keeping progression.
+    SyncLoadouts(commands::sync_loadouts::Args),
+    /// Export class saves as named templates and apply them to saves.
+    #[command(subcommand)]
+    Template(commands::template::Args),
+    /// Record and show the progress history of a save in a local database.
+    #[command(subcommand)]
+    Track(commands::track::Args),
+    /// Revert the last journaled writes to a save.
+    Undo(commands::undo::Args),
+    /// List which drink recipes or pickaxe parts are unlocked in a save.
+    Unlocks(commands::unlocks::Args),
+    /// Check that all backups and snapshots still match their recorded hashes and parse.
+    Verify(commands::verify::Args),
+    /// Snapshot every save in the game's save directory each time the game writes it.
+    Watch(commands::watch::Args),
+}
+
+/// The full command line, including the arguments of each editor (which `edit` otherwise only
+/// parses at runtime), for completions and man pages.
+pub fn command() -> clap::Command {
+    Args::command().mut_subcommand("edit", |edit| {
+        commands::edit::command().name("edit").about(edit.get_about().cloned().unwrap_or_default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_is_valid() {
+        command().debug_assert();
+    }
+
+    #[test]
+    fn output_is_accepted_after_the_command() {
+        for args in [
+            ["drg-save", "stats", "a.sav", "--output", "json"].as_slice(),
+            &["drg-save", "--output", "json", "stats", "a.sav"],
+            &["drg-save", "anonymize", "a.sav", "--out", "b.sav", "--output", "json"],
+            &[
+                "drg-save", "loadout", "export", "a.sav", "--class", "driller", "-o", "b.json",
+                "--output", "json",
+            ],
+        ] {
+            let args = Args::try_parse_from(args).unwrap();
+            assert!(matches!(args.output, Some(OutputFormat::Json)));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+pub mod anonymize;
+pub mod apply_patch;
+pub mod clone_class;
+pub mod completions;
+pub mod cosmetics;
+pub mod db;
+pub mod diff;
+pub mod edit;
+pub mod events;
+pub mod find;
+pub mod gc;
+pub mod get;
+pub mod identity;
+pub mod loadout;
+pub mod locate;
+pub mod migrate;
+pub mod milestones;
+pub mod missions;
+pub mod pools;
+pub mod profile;
+pub mod raw;
+pub mod rebind;
+pub mod repl;
+pub mod report;
+pub mod reset_class;
+pub mod restore;
+pub mod rewards;
+pub mod schema;
+pub mod season;
+pub mod serve;
+pub mod service;
+pub mod simulate;
+pub mod snapshot;
+pub mod stats;
+pub mod sync_loadouts;
+pub mod template;
+pub mod track;
+pub mod undo;
+pub mod unlocks;
+pub mod verify;
+pub mod watch;
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file that you want to anonymize. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Where to write the anonymized save. Defaults to `anonymized.sav` in the current directory,
+    /// because DRG save file names themselves contain the Steam ID.
+    #[arg(short, long = "out", default_value = "anonymized.sav")]
+    out: PathBuf,
+}
+
+pub fn run(Args { path, out }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    info!("anonymizing save file: `{}`", path.display());
+    let mut save = drg_save_core::read_save(&path)?;
+
+    let changed = drg_save_core::anonymize::anonymize(&mut save);
+    for prop_path in &changed {
+        info!("anonymized `{prop_path}`");
+    }
+    if changed.is_empty() {
+        warn!("no identifying data found, output is identical to the input save");
+    }
+
+    drg_save_core::write_save_verified(&out, &save)?;
+    info!("wrote anonymized save file: `{}`", out.display());
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use drg_save_core::patch::{apply_patch, PatchOperation};
+use fs_err as fs;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// JSON Patch document, e.g. from `drg-save diff --format json-patch`.
+    patch: PathBuf,
+    /// Path to the save file that you want to edit.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+pub fn run(Args { patch, path }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let patch: Vec<PatchOperation> = serde_json::from_str(&fs::read_to_string(&patch)?)
+        .with_context(|| format!("`{}` is not a supported JSON Patch", patch.display()))?;
+
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save apply-patch", |save| {
+        apply_patch(save, &patch)?;
+        info!("applied {} patch operations", patch.len());
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::classes::{class_saves_mut, clone_class_save, resolve_class, ClassSelector};
+use drg_save_core::guid_db::GuidDb;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file that you want to edit.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Class name or index of the class save slot in `CharacterSaves` to copy from.
+    #[arg(long)]
+    from: ClassSelector,
+    /// Class name or index of the class save slot in `CharacterSaves` to overwrite.
+    #[arg(long)]
+    to: ClassSelector,
+}
+
+pub fn run(Args { path, from, to }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save clone-class", |save| {
+        let db = GuidDb::load()?;
+        let class_saves = class_saves_mut(save)?;
+        let from_slot = resolve_class(class_saves, &from, &db)?;
+        let to_slot = resolve_class(class_saves, &to, &db)?;
+        clone_class_save(class_saves, from_slot, to_slot)?;
+        info!("cloned {from} onto {to}");
+        Ok(())
+    })?;
+    Ok(())
+}
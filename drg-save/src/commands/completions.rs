@@ -0,0 +1,45 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap_complete::Shell;
+use fs_err as fs;
+
+#[derive(Debug, clap::Args)]
+pub struct CompletionsArgs {
+    /// Shell to print the completion script for.
+    shell: Shell,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ManArgs {
+    /// Directory to write the man pages to, created if needed.
+    dir: PathBuf,
+}
+
+pub fn completions(CompletionsArgs { shell }: CompletionsArgs) -> Result<()> {
+    let mut cli = crate::cli::command();
+    clap_complete::generate(shell, &mut cli, "drg-save", &mut std::io::stdout());
+    Ok(())
+}
+
+pub fn man(ManArgs { dir }: ManArgs) -> Result<()> {
+    let mut command = crate::cli::command();
+    // Fills in the full names (`drg-save-edit-blue-number`) of the subcommands.
+    command.build();
+    fs::create_dir_all(&dir)?;
+    write_man_pages(&command, &dir)
+}
+
+fn write_man_pages(command: &clap::Command, dir: &Path) -> Result<()> {
+    let name = command.get_display_name().unwrap_or_else(|| command.get_name());
+    let mut page = vec![];
+    clap_mangen::Man::new(command.clone()).render(&mut page)?;
+    fs::write(dir.join(format!("{name}.1")), page)?;
+    // clap's generated `help` subcommands don't need pages of their own, and neither does `man`.
+    let subcommands =
+        command.get_subcommands().filter(|c| !c.is_hide_set() && c.get_name() != "help");
+    for subcommand in subcommands {
+        write_man_pages(subcommand, dir)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::classes::{class_saves, resolve_class, savegame_id, ClassSelector};
+use drg_save_core::cosmetics::{equipped, selected_loadout, CosmeticSlot};
+use drg_save_core::guid_db::GuidDb;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Only list this class, by name or index of the class save slot in `CharacterSaves`.
+    #[arg(long)]
+    class: Option<ClassSelector>,
+}
+
+#[derive(Debug, Serialize)]
+struct ClassCosmetics {
+    slot: usize,
+    name: Option<String>,
+    /// The vanity loadout in use, whose cosmetics are listed.
+    loadout: Option<usize>,
+    equipped: Vec<Equipped>,
+}
+
+#[derive(Debug, Serialize)]
+struct Equipped {
+    slot: String,
+    guid: Option<Uuid>,
+    name: Option<String>,
+    /// The DLC the item comes with, if it needs one.
+    dlc: Option<String>,
+}
+
+pub fn run(Args { path, class }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    let class_saves = class_saves(&save)?;
+
+    let slots = match &class {
+        Some(class) => vec![resolve_class(class_saves, class, &db)?],
+        None => (0..class_saves.len()).collect(),
+    };
+    let mut classes = vec![];
+    for i in slots {
+        let class_save = &class_saves[i];
+        // The hidden class has no cosmetics, so missing slots are not an error.
+        let loadout = selected_loadout(class_save).ok();
+        let equipped = CosmeticSlot::ALL
+            .into_iter()
+            .map(|slot| {
+                let guid = loadout.and_then(|loadout| equipped(class_save, slot, loadout).ok());
+                Equipped {
+                    slot: slot.to_string(),
+                    guid,
+                    name: guid.and_then(|guid| db.name(guid)).map(str::to_string),
+                    dlc: guid.and_then(|guid| db.dlc(guid)).map(str::to_string),
+                }
+            })
+            .collect();
+        classes.push(ClassCosmetics {
+            slot: i,
+            name: savegame_id(class_save).and_then(|id| db.name(id)).map(str::to_string),
+            loadout,
+            equipped,
+        });
+    }
+
+    output.print(&classes, |classes| {
+        for class in classes {
+            let name = class.name.as_deref().unwrap_or("<unknown class>");
+            match class.loadout {
+                Some(loadout) => println!("{}: {name} (loadout {loadout})", class.slot),
+                None => println!("{}: {name}", class.slot),
+            }
+            for equipped in &class.equipped {
+                let item = match (&equipped.name, equipped.guid) {
+                    (Some(name), _) => name.clone(),
+                    (None, Some(guid)) => guid.to_string(),
+                    (None, None) => "<not found>".to_string(),
+                };
+                match &equipped.dlc {
+                    Some(dlc) => println!("  {}: {item} ({dlc})", equipped.slot),
+                    None => println!("  {}: {item}", equipped.slot),
+                }
+            }
+        }
+    })
+}
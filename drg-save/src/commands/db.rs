@@ -0,0 +1,74 @@
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use drg_save_core::guid_db::{GuidDb, GuidKind};
+use fs_err as fs;
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Fetch a community-maintained GUID database and install it next to the embedded one.
+    Update {
+        /// URL of the GUID database JSON file. Defaults to the latest version of the embedded
+        /// dataset.
+        #[arg(long, default_value = drg_save_core::guid_db::DEFAULT_URL)]
+        url: String,
+    },
+    /// List all known GUIDs.
+    List {
+        /// Only list GUIDs of this kind.
+        #[arg(long, value_parser = parse_kind)]
+        kind: Option<GuidKind>,
+    },
+}
+
+fn parse_kind(s: &str) -> Result<GuidKind, serde_json::Error> {
+    serde_json::from_value(serde_json::Value::String(s.to_string()))
+}
+
+pub fn run(args: Args, output: OutputFormat) -> Result<()> {
+    match args {
+        Args::Update { url } => {
+            info!("fetching GUID database from `{url}`");
+            let response = ureq::get(&url).call()?;
+            let len = response.header("Content-Length").and_then(|len| len.parse().ok());
+            let bar = match len {
+                Some(len) => crate::progress::bar(len, "downloading", output),
+                None => crate::progress::spinner("downloading", output),
+            };
+            let mut json = String::new();
+            bar.wrap_read(response.into_reader()).read_to_string(&mut json)?;
+            bar.finish_and_clear();
+            // Refuse to install a file we can't load later.
+            let db = GuidDb::from_json(&json)?;
+
+            let path = GuidDb::user_path().context("no data directory for this platform")?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, json)?;
+            info!(
+                "installed GUID database with {} entries to `{}`",
+                db.entries.len(),
+                path.display()
+            );
+        }
+        Args::List { kind } => {
+            let db = GuidDb::load()?;
+            let entries: Vec<_> = db
+                .entries
+                .iter()
+                .filter(|entry| kind.is_none_or(|kind| entry.kind == kind))
+                .collect();
+            output.print(&entries, |entries| {
+                for entry in entries {
+                    println!("{} {:?} {}", entry.guid, entry.kind, entry.name);
+                }
+            })?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::diff::describe_property_differences;
+use drg_save_core::patch::json_patch;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum DiffFormat {
+    /// One line per changed property with its old and new value.
+    #[default]
+    Text,
+    /// An RFC 6902 JSON Patch document turning the first save into the second.
+    JsonPatch,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The save to compare from. Neither save is modified.
+    a: PathBuf,
+    /// The save to compare to.
+    b: PathBuf,
+    #[arg(long, value_enum, default_value_t)]
+    format: DiffFormat,
+}
+
+pub fn run(Args { a, b, format }: Args, output: OutputFormat) -> Result<()> {
+    let a = drg_save_core::read_save_mapped(&a)?;
+    let b = drg_save_core::read_save_mapped(&b)?;
+    // The JSON Patch is the structured form of the differences.
+    let format = match output {
+        OutputFormat::Text => format,
+        OutputFormat::Json => DiffFormat::JsonPatch,
+    };
+    match format {
+        DiffFormat::Text => {
+            for line in describe_property_differences(&a.root.properties, &b.root.properties) {
+                println!("{line}");
+            }
+        }
+        DiffFormat::JsonPatch => {
+            println!("{}", serde_json::to_string_pretty(&json_patch(&a, &b)?)?)
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use drg_save_core::editors::{editors, Editor};
+use drg_save_core::expected_schema::check_preconditions;
+use drg_save_core::legit;
+use drg_save_core::Edited;
+use rayon::prelude::*;
+use serde::Serialize;
+use tracing::*;
+
+use crate::exit::{BatchFailed, Failure};
+use crate::output::OutputFormat;
+
+/// Arguments are parsed by [`run`] once the editor is known, since each editor brings its own.
+#[derive(Debug, clap::Args)]
+#[command(disable_help_flag = true)]
+pub struct Args {
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+pub fn command() -> Command {
+    let editors = editors();
+    Command::new("drg-save edit")
+        .about("Apply one of the registered edit modules to a save")
+        .subcommand_required(true)
+        .subcommands(editors.iter().map(|editor| {
+            Command::new(editor.name())
+                .about(editor.about())
+                .args(editor.args())
+                .arg(
+                    Arg::new("path")
+                        .help(
+                            "Path to the save file that you want to edit. Several saves are \
+                             edited in parallel. Defaults to `save-path` from the config file",
+                        )
+                        .action(ArgAction::Append)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help(
+                            "Edit saves from game versions newer than the ones this tool was \
+                             validated against",
+                        )
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("legit")
+                        .long("legit")
+                        .help(
+                            "Clamp values an edit leaves where the game couldn't plausibly have \
+                             produced them, e.g. negative levels or implausibly many credits, \
+                             into the plausible range",
+                        )
+                        .action(ArgAction::SetTrue),
+                )
+        }))
+}
+
+/// What happened to one save of a batch.
+#[derive(Debug, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+enum Outcome {
+    Edited,
+    Skipped { reason: String },
+    Failed { error: String, kind: Failure },
+}
+
+#[derive(Debug, Serialize)]
+struct BatchResult {
+    path: PathBuf,
+    #[serde(flatten)]
+    outcome: Outcome,
+}
+
+pub fn run(Args { mut args }: Args, output: OutputFormat) -> Result<()> {
+    // Insert the editor's defaults from the config right after its name.
+    if let Some(name) = args.first().filter(|name| !name.starts_with('-')) {
+        let defaults = crate::settings::config().editor_args(name, &args[1..]);
+        args.splice(1..1, defaults);
+    }
+    let matches = command()
+        .try_get_matches_from(std::iter::once("edit".to_string()).chain(args))
+        .unwrap_or_else(crate::exit::clap_exit);
+    let (name, matches) = matches.subcommand().context("no editor given")?;
+    let editor = editors()
+        .into_iter()
+        .find(|editor| editor.name() == name)
+        .context("editor is registered")?;
+    let mut paths: Vec<PathBuf> =
+        matches.get_many::<PathBuf>("path").unwrap_or_default().cloned().collect();
+    if paths.len() <= 1 {
+        let path = crate::settings::save_path(paths.pop())?;
+        edit(editor.as_ref(), matches, &path)?;
+        return Ok(());
+    }
+
+    if crate::interactive::enabled() {
+        bail!("`--interactive` edits one save at a time");
+    }
+
+    // Batch mode: one failing save doesn't stop the others.
+    let bar = crate::progress::bar(paths.len() as u64, "editing saves", output);
+    let results: Vec<_> = paths
+        .into_par_iter()
+        .map(|path| {
+            let outcome = if !path.is_file() {
+                Outcome::Skipped { reason: "not a file".to_string() }
+            } else {
+                match edit(editor.as_ref(), matches, &path).map(|edited| edited.written) {
+                    Ok(true) => Outcome::Edited,
+                    Ok(false) => Outcome::Skipped { reason: "already at target state".to_string() },
+                    Err(e) => {
+                        error!("failed to edit `{}`: {e:#}", path.display());
+                        Outcome::Failed { error: format!("{e:#}"), kind: Failure::of(&e) }
+                    }
+                }
+            };
+            bar.inc(1);
+            BatchResult { path, outcome }
+        })
+        .collect();
+    bar.finish_and_clear();
+    output.print(&results, |results| print_results(results))?;
+
+    let failures: Vec<_> = results
+        .iter()
+        .filter_map(|result| match result.outcome {
+            Outcome::Failed { kind, .. } => Some(kind),
+            _ => None,
+        })
+        .collect();
+    match BatchFailed::of(&failures, results.len()) {
+        Some(failed) => Err(failed).context("failed to edit saves"),
+        None => Ok(()),
+    }
+}
+
+/// Apply `editor` to the save at `path`, returning the lines of its report.
+pub(crate) fn edit(
+    editor: &dyn Editor,
+    matches: &ArgMatches,
+    path: &Path,
+) -> Result<Edited<Vec<String>>> {
+    info!("editing save file: `{}`", path.display());
+    let tool = format!("drg-save edit {}", editor.name());
+    let mut options = crate::settings::session_options();
+    options.force |= matches.get_flag("force");
+    crate::settings::edit_with_options(path, &tool, &options, |save| {
+        check_preconditions(save, editor.name())?;
+        let before = if matches.get_flag("legit") { Some(legit::violations(save)?) } else { None };
+        let mut report = editor.apply(save, matches)?;
+        if let Some(before) = before {
+            for violation in legit::clamp_new_violations(&before, save)? {
+                report.push(format!(
+                    "{violation}, clamped to {} because of `--legit`",
+                    violation.value.clamp(violation.min, violation.max)
+                ));
+            }
+        }
+        for line in &report.lines {
+            info!("{line}");
+        }
+        Ok(report.lines)
+    })
+}
+
+fn print_results(results: &[BatchResult]) {
+    for result in results {
+        let outcome = match &result.outcome {
+            Outcome::Edited => "edited".to_string(),
+            Outcome::Skipped { reason } => format!("skipped: {reason}"),
+            Outcome::Failed { error, .. } => format!("failed: {error}"),
+        };
+        println!("{}\t{outcome}", result.path.display());
+    }
+    let count =
+        |f: fn(&Outcome) -> bool| results.iter().filter(|result| f(&result.outcome)).count();
+    println!(
+        "{} edited, {} skipped, {} failed",
+        count(|outcome| matches!(outcome, Outcome::Edited)),
+        count(|outcome| matches!(outcome, Outcome::Skipped { .. })),
+        count(|outcome| matches!(outcome, Outcome::Failed { .. })),
+    );
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::events::{event_progress, has_machine_events};
+use drg_save_core::guid_db::GuidDb;
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Only list the progress of events whose name contains this, e.g. `core hunt` or `omen`.
+    #[arg(long)]
+    event: Option<String>,
+}
+
+pub fn run(Args { path, event }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    if !has_machine_events(&db) {
+        warn!("the GUID database has no machine events, so only the core hunt is listed");
+    }
+    let mut progress = event_progress(&save.root.properties, &db);
+    if let Some(event) = event {
+        let event = event.to_lowercase();
+        progress.retain(|progress| progress.event.to_lowercase().contains(&event));
+    }
+    output.print(&progress, |progress| {
+        for progress in progress {
+            println!("{}: {} = {}", progress.event, progress.path, progress.value);
+        }
+    })
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::describe::{property_type_name, summarize_property};
+use drg_save_core::walk::walk_properties;
+use regex::RegexBuilder;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to search. This file is not modified.
+    path: PathBuf,
+    /// Property name or regex to search for, e.g. `Retired` or `^XP$`.
+    pattern: String,
+    /// Match case-sensitively.
+    #[arg(short = 's', long)]
+    case_sensitive: bool,
+    /// Match against the full property path (e.g. `CharacterSaves[0].XP`) instead of the name.
+    #[arg(long)]
+    full_path: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Match {
+    path: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    value: String,
+}
+
+pub fn run(
+    Args { path, pattern, case_sensitive, full_path }: Args,
+    output: OutputFormat,
+) -> Result<()> {
+    let regex = RegexBuilder::new(&pattern).case_insensitive(!case_sensitive).build()?;
+    let save = drg_save_core::read_save_mapped(&path)?;
+
+    let mut matches = vec![];
+    walk_properties(&save.root.properties, &mut |prop_path, prop| {
+        let haystack =
+            if full_path { prop_path } else { prop_path.rsplit('.').next().unwrap_or(prop_path) };
+        if regex.is_match(haystack) {
+            matches.push(Match {
+                path: prop_path.to_string(),
+                type_name: property_type_name(prop).to_string(),
+                value: summarize_property(prop),
+            });
+        }
+    });
+
+    output.print(&matches, |matches| {
+        for m in matches {
+            println!("{} ({}): {}", m.path, m.type_name, m.value);
+        }
+    })
+}
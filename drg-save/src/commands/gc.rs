@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use drg_save_core::snapshot::{SnapshotStore, Usage};
+use serde::Serialize;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Only report what would be reclaimed, without deleting or recompressing anything.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    snapshots: usize,
+    /// Stored objects and their size on disk, after reclaiming.
+    stored: Usage,
+    /// Size of the saves the stored objects hold, as far as it was recorded.
+    uncompressed_bytes: u64,
+    reclaimed: Usage,
+    /// Objects stored gzip-compressed by earlier versions that were recompressed, and the bytes
+    /// that saved.
+    recompressed: Usage,
+    dry_run: bool,
+}
+
+pub fn run(Args { dry_run }: Args, output: OutputFormat) -> Result<()> {
+    let store = SnapshotStore::open_default()?;
+    let reclaimed = store.gc(dry_run)?;
+    let recompressed = store.recompress(dry_run)?;
+    let log = store.log()?;
+    let sizes: BTreeMap<_, _> =
+        log.iter().map(|snapshot| (snapshot.hash.as_str(), snapshot.size)).collect();
+    let mut stored = store.usage()?;
+    if dry_run {
+        stored.objects -= reclaimed.objects;
+        stored.bytes -= reclaimed.bytes + recompressed.bytes;
+    }
+    let report = Report {
+        snapshots: log.len(),
+        stored,
+        uncompressed_bytes: sizes.values().sum(),
+        reclaimed,
+        recompressed,
+        dry_run,
+    };
+    output.print(&report, |report| {
+        println!(
+            "{} snapshots in {} objects, {} bytes ({} bytes uncompressed)",
+            report.snapshots, report.stored.objects, report.stored.bytes, report.uncompressed_bytes
+        );
+        let verb = if report.dry_run { "would reclaim" } else { "reclaimed" };
+        println!(
+            "{verb} {} bytes from {} unreferenced objects",
+            report.reclaimed.bytes, report.reclaimed.objects
+        );
+        println!(
+            "{verb} {} bytes by recompressing {} gzip-compressed objects",
+            report.recompressed.bytes, report.recompressed.objects
+        );
+    })
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use drg_save_core::describe::property_type_name;
+use drg_save_core::index::PathIndex;
+use drg_save_core::value::property_value;
+use drg_save_core::DrgSaveError;
+use serde::Serialize;
+use serde_json::Value;
+use uesave::{Properties, Property, StructValue};
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path of the property, e.g. `CharacterSaves[0].XP` (see `find`).
+    property: String,
+    /// Path to the save file to read. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Got {
+    path: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    value: Value,
+}
+
+pub fn run(Args { property, path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save_mapped(&path)?;
+    let got = get(&mut PathIndex::new(), &save.root.properties, property)?;
+    output.print(&got, |got| match &got.value {
+        Value::String(s) => println!("{s}"),
+        value => println!("{value}"),
+    })
+}
+
+/// The scalar or GUID property at `property` in `props`, looked up through `index` so `serve` can
+/// answer repeated lookups without walking the save each time.
+pub fn get(index: &mut PathIndex, props: &Properties, property: String) -> Result<Got> {
+    let prop = index
+        .get(props, &property)
+        .ok_or_else(|| DrgSaveError::MissingProperty { path: property.clone() })?;
+    let value = match prop {
+        Property::Struct { value: StructValue::Guid(guid), .. } => Value::String(guid.to_string()),
+        prop => match property_value(prop) {
+            Some(value) => value,
+            None => bail!(DrgSaveError::InvalidInput(format!(
+                "`{property}` is a {}, not a scalar",
+                property_type_name(prop)
+            ))),
+        },
+    };
+    Ok(Got { path: property, type_name: property_type_name(prop).to_string(), value })
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::identity::{class_names, rank_titles, ClassName, RankTitle, Source};
+use serde::Serialize;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct Identity {
+    rank_titles: Vec<RankTitle>,
+    class_names: Vec<ClassName>,
+}
+
+pub fn run(Args { path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    let identity =
+        Identity { rank_titles: rank_titles(&save)?, class_names: class_names(&save, &db)? };
+    output.print(&identity, |identity| {
+        for title in &identity.rank_titles {
+            match &title.source {
+                Source::Stored { path } => {
+                    println!("rank title: {} (stored in `{path}`)", title.value)
+                }
+                Source::Derived { from } => {
+                    println!("rank title: derived from the {from} ({}), not stored", title.value)
+                }
+            }
+        }
+        for class in &identity.class_names {
+            let name = class.name.as_deref().unwrap_or("<unknown>");
+            match &class.source {
+                Source::Stored { path } => {
+                    println!("class {}: {name} (stored in `{path}`)", class.slot)
+                }
+                Source::Derived { from } => {
+                    println!("class {}: {name} (derived from its {from}, not stored)", class.slot)
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Subcommand;
+use drg_save_core::classes::{
+    class_saves, class_saves_mut, find_class_save, resolve_class, ClassSelector,
+};
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::loadout::{export_loadout, import_loadout, Loadout};
+use fs_err as fs;
+use tracing::*;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Write a class's equipped weapons, mods, overclocks and cosmetics to a JSON file.
+    Export {
+        /// Path to the save file to export the loadout from.
+        /// Defaults to `save-path` from the config file.
+        path: Option<PathBuf>,
+        /// Class name or index of the class save slot in `CharacterSaves`.
+        #[arg(long)]
+        class: ClassSelector,
+        /// Where to write the loadout.
+        #[arg(short, long)]
+        out: PathBuf,
+    },
+    /// Apply a loadout file onto a save.
+    Import {
+        /// Path to the loadout file.
+        loadout: PathBuf,
+        /// Path to the save file that you want to edit.
+        /// Defaults to `save-path` from the config file.
+        path: Option<PathBuf>,
+        /// Class name or index of the class save slot in `CharacterSaves` to apply the loadout
+        /// to. Defaults to the slot with the same `SavegameID` as the one the loadout was
+        /// exported from.
+        #[arg(long)]
+        class: Option<ClassSelector>,
+    },
+}
+
+pub fn run(args: Args) -> Result<()> {
+    match args {
+        Args::Export { path, class, out } => {
+            let path = crate::settings::save_path(path)?;
+            let save = drg_save_core::read_save(&path)?;
+            let class_saves = class_saves(&save)?;
+            let slot = resolve_class(class_saves, &class, &GuidDb::load()?)?;
+            let loadout = export_loadout(&class_saves[slot])?;
+            fs::write(&out, serde_json::to_string_pretty(&loadout)?)?;
+            info!("wrote loadout of {class} to `{}`", out.display());
+        }
+        Args::Import { loadout, path, class } => {
+            let path = crate::settings::save_path(path)?;
+            let loadout: Loadout = serde_json::from_str(&fs::read_to_string(&loadout)?)?;
+
+            info!("editing save file: `{}`", path.display());
+            crate::settings::edit_with(&path, "drg-save loadout import", |save| {
+                let class_saves = class_saves_mut(save)?;
+                let slot = match class {
+                    Some(class) => resolve_class(class_saves, &class, &GuidDb::load()?)?,
+                    None => find_class_save(class_saves, loadout.savegame_id)?,
+                };
+                for name in import_loadout(&mut class_saves[slot], loadout)? {
+                    debug!("wrote `{name}` of class save slot {slot}");
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(())
+}
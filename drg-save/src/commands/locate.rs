@@ -0,0 +1,16 @@
+use anyhow::Result;
+use drg_save_core::locate::find_saves;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {}
+
+pub fn run(Args {}: Args, output: OutputFormat) -> Result<()> {
+    let candidates = find_saves()?;
+    output.print(&candidates, |candidates| {
+        for candidate in candidates {
+            println!("{} {}", candidate.modified, candidate.path.display());
+        }
+    })
+}
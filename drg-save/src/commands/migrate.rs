@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::migrate::{migrate, pending};
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to upgrade, e.g. an old backup.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Only list the migrations the save needs, without modifying it.
+    #[arg(long)]
+    list: bool,
+}
+
+pub fn run(Args { path, list }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    if list {
+        let save = drg_save_core::read_save(&path)?;
+        let migrations = pending(&save);
+        return output.print(&migrations, |migrations| {
+            for migration in migrations {
+                println!("{}: {}", migration.name, migration.description);
+            }
+        });
+    }
+
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save migrate", |save| {
+        for migration in migrate(save)? {
+            info!("applied migration `{}`: {}", migration.name, migration.description);
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::counters::{counters, Counter, CounterKind};
+use drg_save_core::guid_db::GuidDb;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct Milestones {
+    milestones: Vec<Counter>,
+    statistics: Vec<Counter>,
+}
+
+pub fn run(Args { path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    let props = &save.root.properties;
+
+    let milestones = Milestones {
+        milestones: counters(props, CounterKind::Milestones, &db)?,
+        statistics: counters(props, CounterKind::Statistics, &db)?,
+    };
+    output.print(&milestones, |milestones| {
+        for (title, counters) in
+            [("milestones", &milestones.milestones), ("statistics", &milestones.statistics)]
+        {
+            println!("{title}:");
+            for counter in counters {
+                let label = counter.name.clone().unwrap_or_else(|| counter.guid.to_string());
+                match counter.class {
+                    Some(class) => println!("  {label} ({}): {}", db.label(class), counter.value),
+                    None => println!("  {label}: {}", counter.value),
+                }
+            }
+        }
+    })
+}
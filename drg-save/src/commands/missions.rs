@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::missions::{completed_assignments, deep_dive_completed, Assignment, DeepDive};
+use serde::Serialize;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct Missions {
+    deep_dive_completed: bool,
+    elite_deep_dive_completed: bool,
+    completed_assignments: Vec<Assignment>,
+}
+
+pub fn run(Args { path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    let props = &save.root.properties;
+
+    let missions = Missions {
+        deep_dive_completed: deep_dive_completed(props, DeepDive::Normal)?,
+        elite_deep_dive_completed: deep_dive_completed(props, DeepDive::Elite)?,
+        completed_assignments: completed_assignments(props, &db)?,
+    };
+    output.print(&missions, |missions| {
+        println!("deep dive completed: {}", missions.deep_dive_completed);
+        println!("elite deep dive completed: {}", missions.elite_deep_dive_completed);
+        println!("completed assignments:");
+        for assignment in &missions.completed_assignments {
+            match &assignment.name {
+                Some(name) => println!("  {}: {name}", assignment.guid),
+                None => println!("  {}", assignment.guid),
+            }
+        }
+    })
+}
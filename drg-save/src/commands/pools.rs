@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::pools::{duplicates, list_pool, Duplicate, PoolEntry};
+use drg_save_core::DrgSaveError;
+use serde::Serialize;
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct Pools {
+    /// `None` if the GUID database has no schematics.
+    pool: Option<Vec<PoolEntry>>,
+    duplicates: Vec<Duplicate>,
+}
+
+pub fn run(Args { path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    let props = &save.root.properties;
+    let pool = match list_pool(props, &db) {
+        Ok(pool) => Some(pool),
+        Err(e @ DrgSaveError::MissingGuidData { .. }) => {
+            warn!("can't list what can still drop: {e}");
+            None
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let pools = Pools { pool, duplicates: duplicates(props)? };
+    output.print(&pools, |pools| {
+        if let Some(pool) = &pools.pool {
+            println!("can still drop:");
+            for entry in pool {
+                println!("  {}: {}", entry.guid, entry.name);
+            }
+        }
+        println!("forged but still recorded as unforged, with their number of entries:");
+        for duplicate in &pools.duplicates {
+            println!("  {}: {}", db.label(duplicate.guid), duplicate.count);
+        }
+    })
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use drg_save_core::config::{add_profile, remove_profile, Profile};
+use serde::Serialize;
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Add a profile to the config file, or replace the one of the same name.
+    Add {
+        name: String,
+        /// Save file used when a command isn't given one.
+        #[arg(long)]
+        save_path: PathBuf,
+        /// Directory to write backups of this profile's saves to.
+        #[arg(long)]
+        backup_dir: Option<PathBuf>,
+    },
+    /// List the profiles in the config file.
+    List,
+    /// Remove a profile from the config file.
+    Remove { name: String },
+}
+
+#[derive(Debug, Serialize)]
+struct ProfileEntry<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    profile: &'a Profile,
+}
+
+pub fn run(args: Args, output: OutputFormat) -> Result<()> {
+    match args {
+        Args::Add { name, save_path, backup_dir } => {
+            add_profile(&name, &Profile { save_path: Some(save_path), backup_dir })?;
+            info!("added profile `{name}`");
+        }
+        Args::List => {
+            let profiles: Vec<_> = crate::settings::config()
+                .profiles
+                .iter()
+                .map(|(name, profile)| ProfileEntry { name, profile })
+                .collect();
+            output.print(&profiles, |profiles| {
+                for ProfileEntry { name, profile } in profiles {
+                    let path = |path: &Option<PathBuf>| {
+                        path.as_ref().map_or("-".to_string(), |path| path.display().to_string())
+                    };
+                    println!("{name}\t{}\t{}", path(&profile.save_path), path(&profile.backup_dir));
+                }
+            })?;
+        }
+        Args::Remove { name } => {
+            if !remove_profile(&name)? {
+                bail!("no profile `{name}` in the config file");
+            }
+            info!("removed profile `{name}`");
+        }
+    }
+    Ok(())
+}
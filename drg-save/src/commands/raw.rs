@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use drg_save_core::raw::{property_bytes, set_property_bytes};
+use tracing::*;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Print the serialized value of a property as hex.
+    Get {
+        /// Path to the save file. This file is not modified.
+        path: PathBuf,
+        /// Path of the property, e.g. `SeasonSave.Seasons`.
+        property: String,
+    },
+    /// Replace the serialized value of a property with the given hex, as long as the current one.
+    Set {
+        /// Path to the save file that you want to edit.
+        path: PathBuf,
+        /// Path of the property, e.g. `SeasonSave.Seasons`.
+        property: String,
+        /// Replacement value as hex, e.g. `0a000000`.
+        hex: String,
+        /// Only replace the value if it currently is this (as hex).
+        #[arg(long)]
+        expect: Option<String>,
+    },
+}
+
+pub fn run(args: Args) -> Result<()> {
+    match args {
+        Args::Get { path, property } => {
+            let save = drg_save_core::read_save(&path)?;
+            println!("{}", hex::encode(property_bytes(&save, &property)?));
+        }
+        Args::Set { path, property, hex, expect } => {
+            let bytes = hex::decode(&hex).context("replacement bytes are not valid hex")?;
+            let expect = expect
+                .map(|expect| hex::decode(expect).context("expected bytes are not valid hex"))
+                .transpose()?;
+
+            info!("editing save file: `{}`", path.display());
+            crate::settings::edit_with(&path, "drg-save raw set", |save| {
+                Ok(set_property_bytes(save, &property, &bytes, expect.as_deref())?)
+            })?;
+        }
+    }
+    Ok(())
+}
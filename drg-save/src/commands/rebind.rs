@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use drg_save_core::rebind::{rebind, steam_id_in_file_name};
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file that you want to rebind.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Steam64 ID of the account the save is moved to.
+    #[arg(long)]
+    steam_id: u64,
+    /// Steam64 ID of the account the save is moved from. Defaults to the ID the save file is named
+    /// after; IDs of other players the save mentions are kept.
+    #[arg(long)]
+    from: Option<u64>,
+}
+
+pub fn run(Args { path, steam_id, from }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let from = match from {
+        Some(from) => from,
+        None => steam_id_in_file_name(&path).with_context(|| {
+            format!(
+                "`{}` isn't named after a Steam64 ID, pass the ID to replace with `--from`",
+                path.display()
+            )
+        })?,
+    };
+
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save rebind", |save| {
+        let changed = rebind(save, steam_id, from)?;
+        for prop_path in &changed {
+            info!("rebound `{prop_path}`");
+        }
+        if changed.is_empty() {
+            warn!("no account identifiers found");
+        }
+        Ok(())
+    })?;
+
+    // The game finds saves by their file name, which starts with the Steam ID too.
+    if steam_id_in_file_name(&path).is_some_and(|id| id != steam_id) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if let Some((_, rest)) = name.split_once('_') {
+            warn!("rename the save to `{steam_id}_{rest}` for the game to find it");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use drg_save_core::describe::{property_type_name, summarize_property};
+use drg_save_core::index::PathIndex;
+use drg_save_core::value::{parse_value, property_value, set_property_value};
+use drg_save_core::walk::{properties_at_mut, split_path};
+use drg_save_core::SaveSession;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to explore. It is only written on `save`, and locked until the REPL
+    /// exits.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+const HELP: &str = "\
+commands:
+  ls                 list the properties of the current struct
+  cd <name>          enter a struct, e.g. `cd CharacterSaves[0]`; `cd ..` and `cd /` go up
+  pwd                print the path of the current struct
+  get <name>         print a property
+  set <name> <value> set a scalar property, e.g. `set RetiredCharacterLevels 24`
+  save               write the working copy back to the save file
+  revert             discard the changes since the last `save`
+  exit               quit; refuses to with unsaved changes, `exit!` quits anyway
+  help               print this";
+
+struct Repl {
+    session: SaveSession,
+    /// Path of the current struct, empty for the root.
+    cwd: String,
+    /// Paths looked up so far, so `get` and `set` don't walk the whole save each time.
+    index: PathIndex,
+}
+
+pub fn run(Args { path }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let session = crate::settings::open_session(&path, "drg-save repl")?;
+    let mut repl = Repl { session, cwd: String::new(), index: PathIndex::new() };
+    println!("{HELP}");
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("/{}> ", repl.cwd);
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command {
+            "" => {}
+            "exit" | "quit" if repl.session.is_dirty()? => {
+                println!("there are unsaved changes, `save` them or use `exit!`");
+            }
+            "exit" | "quit" | "exit!" | "quit!" => break,
+            _ => {
+                if let Err(e) = repl.command(command, rest.trim()) {
+                    println!("error: {e:#}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Repl {
+    fn command(&mut self, command: &str, args: &str) -> Result<()> {
+        match command {
+            "help" => println!("{HELP}"),
+            "pwd" => println!("/{}", self.cwd),
+            "ls" => {
+                let props = self.current()?;
+                for (key, prop) in props.0.iter() {
+                    println!(
+                        "{} ({}): {}",
+                        key.1,
+                        property_type_name(prop),
+                        summarize_property(prop)
+                    );
+                }
+            }
+            "cd" => {
+                let cwd = match args {
+                    "" | "/" => String::new(),
+                    ".." => split_path(&self.cwd).0.to_string(),
+                    name => self.join(name),
+                };
+                if properties_at_mut(&mut self.session.save_mut().root.properties, &cwd).is_none() {
+                    bail!("`{cwd}` is not a struct");
+                }
+                self.cwd = cwd;
+            }
+            "get" => {
+                let path = self.join(args);
+                let prop = self
+                    .index
+                    .get(&self.session.save().root.properties, &path)
+                    .with_context(|| format!("`{path}` not found"))?;
+                match property_value(prop) {
+                    Some(value) => println!("{value}"),
+                    None => println!("{}", summarize_property(prop)),
+                }
+            }
+            "set" => {
+                let Some((name, value)) = args.split_once(char::is_whitespace) else {
+                    bail!("usage: set <name> <value>");
+                };
+                let path = self.join(name);
+                let prop = self
+                    .index
+                    .get_mut(&mut self.session.save_mut().root.properties, &path)
+                    .with_context(|| format!("`{path}` not found"))?;
+                set_property_value(prop, parse_value(value.trim()))?;
+                debug!("set `{path}` in working copy");
+            }
+            "save" => {
+                crate::settings::commit(&mut self.session)?;
+            }
+            "revert" => {
+                self.session.rollback()?;
+                self.index.invalidate();
+            }
+            _ => bail!("unknown command `{command}`, see `help`"),
+        }
+        Ok(())
+    }
+
+    fn current(&mut self) -> Result<&mut uesave::Properties> {
+        properties_at_mut(&mut self.session.save_mut().root.properties, &self.cwd)
+            .with_context(|| format!("`{}` not found", self.cwd))
+    }
+
+    fn join(&self, name: &str) -> String {
+        if self.cwd.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{name}", self.cwd)
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::report::report;
+use fs_err as fs;
+use tracing::*;
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    /// A standalone HTML page.
+    Html,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to report on. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t)]
+    format: ReportFormat,
+    /// Write the report to this file instead of stdout.
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+}
+
+pub fn run(Args { path, format, out }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save_mapped(&path)?;
+    let report = report(&save, &GuidDb::load()?)?;
+    let rendered = match format {
+        ReportFormat::Markdown => report.to_markdown(),
+        ReportFormat::Html => report.to_html(),
+    };
+    match out {
+        Some(out) => {
+            fs::write(&out, rendered)?;
+            info!("wrote report of `{}` to `{}`", path.display(), out.display());
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::classes::{class_saves_mut, reset_class_save, resolve_class, ClassSelector};
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::loadout::{import_loadout, reset_loadout, Loadout};
+use fs_err as fs;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file that you want to edit.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Class name or index of the class save slot in `CharacterSaves` to reset.
+    #[arg(long)]
+    class: ClassSelector,
+    /// Loadout file (see `loadout export`) to apply as the default loadout, e.g. one exported
+    /// from a freshly created save. Without it nothing is picked in any loadout slot, so the game
+    /// equips the class's default items.
+    #[arg(long)]
+    loadout: Option<PathBuf>,
+}
+
+pub fn run(Args { path, class, loadout }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let loadout: Option<Loadout> = match loadout {
+        Some(loadout) => Some(serde_json::from_str(&fs::read_to_string(loadout)?)?),
+        None => None,
+    };
+
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save reset-class", |save| {
+        let class_saves = class_saves_mut(save)?;
+        let slot = resolve_class(class_saves, &class, &GuidDb::load()?)?;
+        let class_save = &mut class_saves[slot];
+        reset_class_save(class_save)?;
+        info!("reset {class} to level 1 with zero promotions");
+        match loadout {
+            Some(loadout) => {
+                import_loadout(class_save, loadout)?;
+                info!("applied default loadout to {class}");
+            }
+            None => {
+                let changed = reset_loadout(class_save)?;
+                info!("reset {} loadout properties of {class} to the defaults", changed.len());
+            }
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
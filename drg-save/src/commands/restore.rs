@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::restore::restore_properties;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The backup to restore properties from. It is not modified.
+    backup: PathBuf,
+    /// Path to the save file to restore properties into.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Property to restore with everything below it, e.g. `CharacterSaves[*].TimesRetired`, where
+    /// `[*]` matches any index. Can be given multiple times.
+    #[arg(long, required = true)]
+    only: Vec<String>,
+}
+
+pub fn run(Args { backup, path, only }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let backup = drg_save_core::parse_save(&drg_save_core::read_backup(&backup)?, &backup)?;
+
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save restore", |save| {
+        for restored in restore_properties(save, &backup, &only)? {
+            debug!("restored `{restored}`");
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::rewards::claim_states;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Only list rewards that are not claimed yet.
+    #[arg(long)]
+    unclaimed: bool,
+}
+
+pub fn run(Args { path, unclaimed }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let mut states = claim_states(&save.root.properties);
+    if unclaimed {
+        states.retain(|state| !state.claimed);
+    }
+    output.print(&states, |states| {
+        for state in states {
+            let claimed = if state.claimed { "claimed" } else { "unclaimed" };
+            println!("{claimed} {}", state.path);
+        }
+    })
+}
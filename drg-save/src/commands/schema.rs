@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::expected_schema::ExpectedSchema;
+use drg_save_core::schema::{schema, SchemaNode};
+
+use crate::exit::ValidationFailed;
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to describe. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Instead of describing the save, check it against the layout the editors expect and list
+    /// the differences.
+    #[arg(long)]
+    check: bool,
+}
+
+pub fn run(Args { path, check }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save_mapped(&path)?;
+    if check {
+        let mismatches = ExpectedSchema::embedded().check(&save, None);
+        output.print(&mismatches, |mismatches| {
+            for mismatch in mismatches {
+                println!("{mismatch}");
+            }
+        })?;
+        if !mismatches.is_empty() {
+            return Err(
+                ValidationFailed("save is not laid out as the editors expect".to_string()).into()
+            );
+        }
+        return Ok(());
+    }
+    output.print(&schema(&save.root.properties), |nodes| print_nodes(nodes, 0))
+}
+
+fn print_nodes(nodes: &[SchemaNode], depth: usize) {
+    for node in nodes {
+        let mut line =
+            format!("{:indent$}{}: {}", "", node.name, node.type_name, indent = depth * 2);
+        if let Some(struct_type) = &node.struct_type {
+            line.push_str(&format!(" <{struct_type}>"));
+        }
+        if let Some(len) = node.len {
+            line.push_str(&format!(" [{len}]"));
+        }
+        println!("{line}");
+        print_nodes(&node.children, depth + 1);
+    }
+}
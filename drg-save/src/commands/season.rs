@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Subcommand;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::season::{challenges, complete_challenge, reroll_challenge};
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// List the seasonal challenges assigned in a save, and optionally complete or reroll some.
+    /// The save is only written if `--complete` or `--reroll` is given.
+    Challenges {
+        /// Path to the save file.
+        /// Defaults to `save-path` from the config file.
+        path: Option<PathBuf>,
+        /// Index (as listed) of a challenge to mark as completed. Can be repeated.
+        #[arg(long)]
+        complete: Vec<usize>,
+        /// Index (as listed) of a challenge to replace with an unassigned one from the GUID
+        /// database. Can be repeated.
+        #[arg(long)]
+        reroll: Vec<usize>,
+    },
+}
+
+pub fn run(args: Args, output: OutputFormat) -> Result<()> {
+    let Args::Challenges { path, complete, reroll } = args;
+    let path = crate::settings::save_path(path)?;
+    let db = GuidDb::load()?;
+
+    if complete.is_empty() && reroll.is_empty() {
+        let save = drg_save_core::read_save(&path)?;
+        let challenges = challenges(&save.root.properties, &db);
+        return output.print(&challenges, |challenges| {
+            for challenge in challenges {
+                let name = match (&challenge.name, challenge.guid) {
+                    (Some(name), _) => name.clone(),
+                    (None, Some(guid)) => guid.to_string(),
+                    (None, None) => "<no challenge GUID>".to_string(),
+                };
+                let state = if challenge.completed { "completed" } else { "open" };
+                match (challenge.progress, challenge.needed) {
+                    (Some(progress), Some(needed)) => {
+                        println!("{}: {name}: {state}, {progress}/{needed}", challenge.index)
+                    }
+                    _ => println!("{}: {name}: {state}", challenge.index),
+                }
+            }
+        });
+    }
+
+    info!("editing save file: `{}`", path.display());
+    crate::settings::edit_with(&path, "drg-save season challenges", |save| {
+        let props = &mut save.root.properties;
+        for index in complete {
+            complete_challenge(props, index)?;
+            info!("completed season challenge {index}");
+        }
+        for index in reroll {
+            let guid = reroll_challenge(props, index, &db, &mut rand::thread_rng())?;
+            info!("rerolled season challenge {index} into {}", db.label(guid));
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
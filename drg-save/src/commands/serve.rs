@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use drg_save_core::describe::{property_type_name, summarize_property};
+use drg_save_core::editors::editors;
+use drg_save_core::index::PathIndex;
+use drg_save_core::session::{digest, Digest};
+use drg_save_core::walk::walk_properties;
+use drg_save_core::DrgSaveError;
+use fs_err as fs;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::*;
+use uesave::Save;
+
+use super::get::Got;
+
+const INDEX: &str = include_str!("../../assets/serve.html");
+
+/// Header the page sends the session token in. Browsers don't let other sites set it on requests
+/// to this server without a CORS preflight, which the server never allows.
+const TOKEN_HEADER: &str = "X-Drg-Save-Token";
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to view and edit.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Port to listen on. Only connections from this machine are accepted, and only from the page
+    /// at the URL printed on startup.
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+}
+
+/// What requests have to carry to be served: the session token, and `Host` and `Origin` headers
+/// naming this server, so neither other sites (CSRF) nor other hosts resolving to 127.0.0.1 (DNS
+/// rebinding) can read or edit the save.
+struct Guard {
+    token: String,
+    hosts: [String; 2],
+}
+
+impl Guard {
+    fn new(port: u16) -> Self {
+        Self {
+            token: hex::encode(rand::thread_rng().gen::<[u8; 16]>()),
+            hosts: [format!("127.0.0.1:{port}"), format!("localhost:{port}")],
+        }
+    }
+
+    /// Why `request` is refused, if it is. `needs_token` is whether it reads or edits the save.
+    fn refusal(&self, request: &Request, needs_token: bool) -> Option<&'static str> {
+        let header = |name: &'static str| {
+            request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv(name))
+                .map(|header| header.value.as_str())
+        };
+        if !header("Host").is_some_and(|host| self.hosts.iter().any(|known| known == host)) {
+            return Some("unexpected Host");
+        }
+        if let Some(origin) = header("Origin") {
+            let host = origin.strip_prefix("http://");
+            if !host.is_some_and(|host| self.hosts.iter().any(|known| known == host)) {
+                return Some("unexpected Origin");
+            }
+        }
+        if needs_token && header(TOKEN_HEADER) != Some(self.token.as_str()) {
+            return Some("missing or wrong session token");
+        }
+        None
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PropertyEntry {
+    path: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EditorEntry {
+    name: &'static str,
+    about: &'static str,
+    args: Vec<EditorArg>,
+}
+
+#[derive(Debug, Serialize)]
+struct EditorArg {
+    long: String,
+    help: String,
+    /// Whether the argument takes a value, as opposed to being a flag.
+    takes_value: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct EditRequest {
+    /// Arguments of the editor as on the command line, e.g. `["--target", "-69"]`.
+    args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PropertyRequest {
+    /// Path of the property, e.g. `CharacterSaves[0].XP`.
+    path: String,
+}
+
+/// The save as last read, with an index of its property paths, so looking up single properties
+/// only parses and walks it again once the file changed.
+struct Cached {
+    digest: Digest,
+    save: Save,
+    index: PathIndex,
+}
+
+pub fn run(Args { path, port }: Args) -> Result<()> {
+    if crate::interactive::enabled() {
+        return Err(DrgSaveError::Usage(
+            "`--interactive` can't prompt for edits made through `serve`".to_string(),
+        )
+        .into());
+    }
+    let path = crate::settings::save_path(path)?;
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| anyhow!(e))?;
+    let guard = Guard::new(port);
+    info!("serving `{}`", path.display());
+    // The token is only printed here, so only who started the server can open the page.
+    println!("open http://127.0.0.1:{port}/#token={}", guard.token);
+    let mut cache = None;
+    for request in server.incoming_requests() {
+        debug!("{} {}", request.method(), request.url());
+        if let Err(e) = handle(request, &path, &guard, &mut cache) {
+            error!("failed to respond: {e:#}");
+        }
+    }
+    Ok(())
+}
+
+fn handle(
+    mut request: Request,
+    path: &Path,
+    guard: &Guard,
+    cache: &mut Option<Cached>,
+) -> Result<()> {
+    let url = request.url().to_string();
+    if let Some(refusal) = guard.refusal(&request, url.starts_with("/api/")) {
+        warn!("refused {} {}: {refusal}", request.method(), url);
+        let body = json!({ "error": refusal }).to_string();
+        request.respond(
+            Response::from_string(body)
+                .with_status_code(403)
+                .with_header(header("application/json")),
+        )?;
+        return Ok(());
+    }
+    let response = match (request.method(), url.as_str()) {
+        (Method::Get, "/") => Response::from_string(INDEX).with_header(header("text/html")),
+        (Method::Get, "/api/properties") => json_response(properties(path)),
+        (Method::Get, "/api/editors") => json_response(Ok(editor_entries())),
+        (Method::Post, "/api/property") => {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body)?;
+            json_response(property(&body, path, cache))
+        }
+        (Method::Post, url) if url.starts_with("/api/edit/") => {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body)?;
+            json_response(edit(&url["/api/edit/".len()..], &body, path))
+        }
+        _ => Response::from_string("not found").with_status_code(404),
+    };
+    request.respond(response)?;
+    Ok(())
+}
+
+fn header(content_type: &str) -> Header {
+    Header::from_bytes("Content-Type", content_type).expect("content type is a valid header")
+}
+
+/// `value` as JSON, or the error with status 400.
+fn json_response<T: Serialize>(value: Result<T>) -> Response<std::io::Cursor<Vec<u8>>> {
+    let (status, body) = match value.and_then(|value| Ok(serde_json::to_string(&value)?)) {
+        Ok(body) => (200, body),
+        Err(e) => (400, json!({ "error": format!("{e:#}") }).to_string()),
+    };
+    Response::from_string(body).with_status_code(status).with_header(header("application/json"))
+}
+
+fn properties(path: &Path) -> Result<Vec<PropertyEntry>> {
+    let save = drg_save_core::read_save(path)?;
+    let mut entries = vec![];
+    walk_properties(&save.root.properties, &mut |prop_path, prop| {
+        entries.push(PropertyEntry {
+            path: prop_path.to_string(),
+            type_name: property_type_name(prop).to_string(),
+            value: summarize_property(prop),
+        });
+    });
+    Ok(entries)
+}
+
+/// The property at the path in `body`, like `drg-save get`.
+fn property(body: &str, path: &Path, cache: &mut Option<Cached>) -> Result<Got> {
+    let PropertyRequest { path: property } =
+        serde_json::from_str(body).context("invalid property request")?;
+    let buf = fs::read(path)?;
+    let digest = digest(&buf);
+    if cache.as_ref().is_none_or(|cached| cached.digest != digest) {
+        let save = drg_save_core::parse_save(&buf, path)?;
+        *cache = Some(Cached { digest, save, index: PathIndex::new() });
+    }
+    let cached = cache.as_mut().expect("filled above");
+    super::get::get(&mut cached.index, &cached.save.root.properties, property)
+}
+
+fn editor_entries() -> Vec<EditorEntry> {
+    editors()
+        .iter()
+        .map(|editor| EditorEntry {
+            name: editor.name(),
+            about: editor.about(),
+            args: editor
+                .args()
+                .iter()
+                .filter_map(|arg| {
+                    Some(EditorArg {
+                        long: arg.get_long()?.to_string(),
+                        help: arg.get_help().map(ToString::to_string).unwrap_or_default(),
+                        takes_value: arg.get_action().takes_values(),
+                    })
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Apply the editor `name` with the arguments in `body` like `drg-save edit` does, returning the
+/// lines of its report and whether the save was written.
+fn edit(name: &str, body: &str, path: &Path) -> Result<serde_json::Value> {
+    let EditRequest { args } = serde_json::from_str(body).context("invalid edit request")?;
+    let editor = editors()
+        .into_iter()
+        .find(|editor| editor.name() == name)
+        .with_context(|| format!("no editor `{name}`"))?;
+    let defaults = crate::settings::config().editor_args(name, &args);
+    let matches = super::edit::command()
+        .try_get_matches_from(
+            ["edit", name]
+                .into_iter()
+                .map(str::to_string)
+                .chain(defaults)
+                .chain(args)
+                .chain([path.display().to_string()]),
+        )
+        .map_err(|e| anyhow!("{}", e.render()))?;
+    let (_, matches) = matches.subcommand().context("no editor given")?;
+    let edited = super::edit::edit(editor.as_ref(), matches, path)?;
+    Ok(json!({ "report": edited.value, "written": edited.written }))
+}
@@ -0,0 +1,205 @@
+use std::path::PathBuf;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use drg_save_core::snapshot::SnapshotStore;
+use fs_err as fs;
+use serde::Serialize;
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+/// Name of the systemd user unit on Linux and of the logon task on Windows, which run `watch`.
+const SERVICE_NAME: &str = "drg-save-watch";
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Install and start a service running `watch` on a save directory at login.
+    Install {
+        /// The game's save directory, e.g. `.../FSD/Saved/SaveGames`.
+        dir: PathBuf,
+        /// Arguments passed on to `watch`, e.g. `--keep 100 --track`.
+        #[arg(last = true)]
+        watch_args: Vec<String>,
+    },
+    /// Stop and remove the service.
+    Uninstall,
+    /// Show whether the service runs, the last snapshot and the number of retained snapshots.
+    Status,
+}
+
+#[derive(Debug, Serialize)]
+struct Status {
+    running: Option<bool>,
+    /// Seconds since the Unix epoch.
+    last_snapshot: Option<u64>,
+    snapshots: usize,
+}
+
+pub fn run(args: Args, output: OutputFormat) -> Result<()> {
+    match args {
+        Args::Install { dir, watch_args } => install(dir, watch_args),
+        Args::Uninstall => uninstall(),
+        Args::Status => {
+            let snapshots = SnapshotStore::open_default()?.log()?;
+            let status = Status {
+                running: running(),
+                last_snapshot: snapshots.iter().map(|snapshot| snapshot.timestamp).max(),
+                snapshots: snapshots.len(),
+            };
+            output.print(&status, |status| {
+                let running = match status.running {
+                    Some(true) => "running",
+                    Some(false) => "not running",
+                    None => "unknown",
+                };
+                println!("service: {running}");
+                match status.last_snapshot {
+                    Some(timestamp) => println!("last snapshot: {}", ago(timestamp)),
+                    None => println!("last snapshot: none"),
+                }
+                println!("retained snapshots: {}", status.snapshots);
+            })
+        }
+    }
+}
+
+/// How long before now the unix timestamp `timestamp` was, e.g. `3 hours ago`.
+fn ago(timestamp: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs());
+    let seconds = now.saturating_sub(timestamp);
+    let (n, unit) = match seconds {
+        0..60 => (seconds, "second"),
+        60..3600 => (seconds / 60, "minute"),
+        3600..86400 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+    format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" })
+}
+
+/// Quote `arg` as one argument of a systemd `ExecStart` line, escaping what systemd would
+/// otherwise expand: `%` specifiers, `$` variables, and `"` and `\` inside the quotes.
+fn systemd_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The command line the service runs.
+fn watch_command(dir: PathBuf, watch_args: Vec<String>) -> Result<Vec<String>> {
+    let exe = std::env::current_exe().context("failed to find the `drg-save` executable")?;
+    let dir = dir.canonicalize().with_context(|| format!("`{}` not found", dir.display()))?;
+    let mut command = vec![exe.display().to_string(), "watch".to_string()];
+    command.push(dir.display().to_string());
+    command.extend(watch_args);
+    Ok(command)
+}
+
+fn systemd_unit_path() -> Result<PathBuf> {
+    let dir = dirs::config_dir().context("no config directory for this platform")?;
+    Ok(dir.join("systemd").join("user").join(format!("{SERVICE_NAME}.service")))
+}
+
+fn install(dir: PathBuf, watch_args: Vec<String>) -> Result<()> {
+    let command = watch_command(dir, watch_args)?;
+    if cfg!(target_os = "linux") {
+        let quoted: Vec<_> = command.iter().map(|arg| systemd_quote(arg)).collect();
+        let unit = format!(
+            "[Unit]\nDescription=Snapshot DRG saves each time the game writes them\n\n\
+             [Service]\nExecStart={}\nRestart=on-failure\n\n\
+             [Install]\nWantedBy=default.target\n",
+            quoted.join(" ")
+        );
+        let path = systemd_unit_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, unit)?;
+        info!("wrote systemd unit `{}`", path.display());
+        run_command("systemctl", &["--user", "daemon-reload"])?;
+        run_command("systemctl", &["--user", "enable", "--now", SERVICE_NAME])?;
+    } else if cfg!(windows) {
+        let quoted: Vec<_> = command.iter().map(|arg| format!("\"{arg}\"")).collect();
+        let task = quoted.join(" ");
+        run_command(
+            "schtasks",
+            &["/Create", "/F", "/SC", "ONLOGON", "/TN", SERVICE_NAME, "/TR", &task],
+        )?;
+        run_command("schtasks", &["/Run", "/TN", SERVICE_NAME])?;
+    } else {
+        bail!("services are only supported on Linux (systemd) and Windows");
+    }
+    info!("installed and started `{SERVICE_NAME}`");
+    Ok(())
+}
+
+fn uninstall() -> Result<()> {
+    if cfg!(target_os = "linux") {
+        run_command("systemctl", &["--user", "disable", "--now", SERVICE_NAME])?;
+        fs::remove_file(systemd_unit_path()?)?;
+        run_command("systemctl", &["--user", "daemon-reload"])?;
+    } else if cfg!(windows) {
+        // Ending fails if the task isn't running, which is fine.
+        let _ = run_command("schtasks", &["/End", "/TN", SERVICE_NAME]);
+        run_command("schtasks", &["/Delete", "/F", "/TN", SERVICE_NAME])?;
+    } else {
+        bail!("services are only supported on Linux (systemd) and Windows");
+    }
+    info!("removed `{SERVICE_NAME}`");
+    Ok(())
+}
+
+/// Whether the service runs, or `None` if that can't be told.
+fn running() -> Option<bool> {
+    if cfg!(target_os = "linux") {
+        let status = process::Command::new("systemctl")
+            .args(["--user", "is-active", "--quiet", SERVICE_NAME])
+            .status()
+            .ok()?;
+        Some(status.success())
+    } else if cfg!(windows) {
+        let output = process::Command::new("schtasks")
+            .args(["/Query", "/FO", "CSV", "/NH", "/TN", SERVICE_NAME])
+            .output()
+            .ok()?;
+        Some(output.status.success() && String::from_utf8_lossy(&output.stdout).contains("Running"))
+    } else {
+        None
+    }
+}
+
+fn run_command(program: &str, args: &[&str]) -> Result<()> {
+    debug!("running `{program} {}`", args.join(" "));
+    let status = process::Command::new(program)
+        .args(args)
+        .status()
+        .with_context(|| format!("failed to run `{program}`"))?;
+    if !status.success() {
+        bail!("`{program} {}` failed with {status}", args.join(" "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_exec_start_arguments() {
+        assert_eq!(systemd_quote("/home/me/drg-save"), r#""/home/me/drg-save""#);
+        assert_eq!(systemd_quote(r#"100% "$HOME"\x"#), r#""100%% \"$$HOME\"\\x""#);
+    }
+}
@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use drg_save_core::blue_level::{blue_level_for, red_levels_for};
+use drg_save_core::DrgSaveError;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// `<promotions>:<retired levels>` of a class save slot, e.g. `--class 1:25`. Given once per
+    /// slot, including the hidden class if it should count.
+    #[arg(long)]
+    class: Vec<ClassLevels>,
+    /// JSON file with a list of `{"times_retired": .., "retired_levels": ..}` objects, one per
+    /// class save slot, used before the `--class` ones.
+    #[arg(long)]
+    from: Option<PathBuf>,
+}
+
+/// Hypothetical promotions and retired levels of a class save slot.
+#[derive(Debug, Clone, Deserialize)]
+struct ClassLevels {
+    times_retired: i32,
+    retired_levels: i32,
+}
+
+impl FromStr for ClassLevels {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((times_retired, retired_levels)) = s.split_once(':') else {
+            return Err(format!("expected `<promotions>:<retired levels>`, got `{s}`"));
+        };
+        let parse = |value: &str| {
+            value.parse::<i32>().map_err(|e| format!("invalid number `{value}`: {e}"))
+        };
+        Ok(Self { times_retired: parse(times_retired)?, retired_levels: parse(retired_levels)? })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Simulation {
+    blue_level: i32,
+    classes: Vec<SimulatedClass>,
+}
+
+#[derive(Debug, Serialize)]
+struct SimulatedClass {
+    slot: usize,
+    times_retired: i32,
+    retired_levels: i32,
+    red_levels: i32,
+}
+
+pub fn run(Args { class, from }: Args, output: OutputFormat) -> Result<()> {
+    let mut classes: Vec<ClassLevels> = match from {
+        Some(from) => serde_json::from_str(&fs::read_to_string(&from)?)
+            .with_context(|| format!("`{}` is not a list of class levels", from.display()))?,
+        None => vec![],
+    };
+    classes.extend(class);
+    if classes.is_empty() {
+        bail!("no class save slots given, use `--class` or `--from`");
+    }
+
+    let classes = classes
+        .into_iter()
+        .enumerate()
+        .map(|(slot, ClassLevels { times_retired, retired_levels })| {
+            Ok(SimulatedClass {
+                slot,
+                times_retired,
+                retired_levels,
+                red_levels: red_levels_for(times_retired, retired_levels)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let Some(total_red_levels) =
+        classes.iter().try_fold(0i32, |total, class| total.checked_add(class.red_levels))
+    else {
+        return Err(DrgSaveError::InvalidInput(
+            "the class save slots have too many red levels".to_string(),
+        )
+        .into());
+    };
+    let blue_level = blue_level_for(total_red_levels);
+    output.print(&Simulation { blue_level, classes }, |simulation| {
+        println!("blue level: {}", simulation.blue_level);
+        println!("classes:");
+        for class in &simulation.classes {
+            println!(
+                "  {}: {} red levels ({} promotions, {} retired levels)",
+                class.slot, class.red_levels, class.times_retired, class.retired_levels
+            );
+        }
+    })
+}
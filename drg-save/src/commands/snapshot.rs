@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use drg_save_core::snapshot::SnapshotStore;
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct SnapshotArgs {
+    /// Path to the save file to snapshot.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Description of the snapshot.
+    #[arg(short, long, default_value = "")]
+    message: String,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct LogArgs {
+    /// Only list snapshots of this save file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CheckoutArgs {
+    /// Hash (or a unique prefix of at least 4 characters) of the snapshot to restore.
+    hash: String,
+    /// Path to the save file to replace with the snapshot.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Upgrade the snapshot to the current layout (see `migrate`) before restoring it.
+    #[arg(long)]
+    migrate: bool,
+}
+
+pub fn snapshot(SnapshotArgs { path, message }: SnapshotArgs, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let store = crate::settings::snapshot_store()?;
+    let spinner = crate::progress::spinner("creating snapshot", output);
+    let snapshot = store.create(&path, &message);
+    spinner.finish_and_clear();
+    let snapshot = snapshot?;
+    info!("created snapshot {} of `{}`", snapshot.hash, path.display());
+    Ok(())
+}
+
+pub fn log(LogArgs { path }: LogArgs, output: OutputFormat) -> Result<()> {
+    // Sources are logged as keyed hashes when encrypting, which takes the passphrase to match.
+    let store = match path {
+        Some(_) => crate::settings::snapshot_store()?,
+        None => SnapshotStore::open_default()?,
+    };
+    let source = path.map(|path| store.source(&path));
+    let mut snapshots = store.log()?;
+    snapshots.retain(|snapshot| source.as_ref().is_none_or(|source| *source == snapshot.source));
+    snapshots.reverse();
+    output.print(&snapshots, |snapshots| {
+        for snapshot in snapshots {
+            println!(
+                "{} {} {} {}",
+                snapshot.hash.get(..12).unwrap_or(&snapshot.hash),
+                snapshot.timestamp,
+                snapshot.source.display(),
+                snapshot.message
+            );
+        }
+    })
+}
+
+pub fn checkout(CheckoutArgs { hash, path, migrate }: CheckoutArgs) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let store = crate::settings::snapshot_store()?;
+    let snapshot = store.find(&hash)?;
+    let buf = store.read(&snapshot.hash)?;
+    let mut save = drg_save_core::parse_save(&buf, &snapshot.source)
+        .with_context(|| format!("failed to parse snapshot {}", snapshot.hash))?;
+    if migrate {
+        for migration in drg_save_core::migrate::migrate(&mut save)? {
+            info!("applied migration `{}`: {}", migration.name, migration.description);
+        }
+    }
+
+    info!("editing save file: `{}`", path.display());
+    let mut session = crate::settings::open_session(&path, "drg-save checkout")?;
+    *session.save_mut() = save;
+    if !crate::settings::confirm(&mut session)? {
+        info!("changes not confirmed, leaving `{}` untouched", path.display());
+        return Ok(());
+    }
+    if !session.commit()? {
+        return Ok(());
+    }
+    info!("replaced `{}` with snapshot {}", path.display(), snapshot.hash);
+    Ok(())
+}
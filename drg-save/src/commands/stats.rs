@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use drg_save_core::blue_level::{blue_level, red_levels};
+use drg_save_core::classes::{class_saves, savegame_id};
+use drg_save_core::guid_db::{GuidDb, GuidKind};
+use drg_save_core::resources::{owned_resources, OwnedResource};
+use drg_save_core::version::{compatibility, experimental_properties, Compatibility, SaveVersion};
+use drg_save_core::walk::walk_properties;
+use serde::Serialize;
+use tracing::*;
+use uesave::{Property, StructValue};
+use uuid::Uuid;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    version: SaveVersion,
+    /// The known game version the save is from, if any.
+    game: Option<&'static str>,
+    /// Whether the save is from the experimental branch.
+    experimental: bool,
+    blue_level: i32,
+    classes: Vec<ClassStats>,
+    credits: Option<i32>,
+    resources: Vec<OwnedResource>,
+}
+
+#[derive(Debug, Serialize)]
+struct ClassStats {
+    slot: usize,
+    savegame_id: Option<Uuid>,
+    name: Option<String>,
+    red_levels: i32,
+    times_retired: i32,
+    retired_levels: i32,
+    xp: i32,
+}
+
+pub fn run(Args { path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save_mapped(&path)?;
+    let db = GuidDb::load()?;
+
+    let class_saves = class_saves(&save)?;
+    let mut classes = vec![];
+    for (i, class_save) in class_saves.iter().enumerate() {
+        let StructValue::Struct(props) = class_save else {
+            bail!("unexpected `class_save` struct value kind");
+        };
+        let int = |name: &str| match drg_save_core::property(props, name) {
+            Some(Property::Int { value, .. }) => Ok(*value),
+            _ => bail!("`{name}` not found in class save slot {i}"),
+        };
+        let savegame_id = savegame_id(class_save);
+        classes.push(ClassStats {
+            slot: i,
+            savegame_id,
+            name: savegame_id.and_then(|id| db.name(id)).map(str::to_string),
+            red_levels: red_levels(class_save)?,
+            times_retired: int("TimesRetired")?,
+            retired_levels: int("RetiredCharacterLevels")?,
+            xp: int("XP")?,
+        });
+    }
+
+    let mut credits = None;
+    walk_properties(&save.root.properties, &mut |path, prop| {
+        if let Property::Int { value, .. } = prop {
+            if path == "Credits" || path.ends_with(".Credits") {
+                credits = Some(*value);
+            }
+        }
+    });
+    // The save only holds the special resources once they were earned, they are listed anyway
+    // if the GUID database knows them.
+    if !db.has(GuidKind::Resource) {
+        warn!(
+            "the GUID database has no resources, so resources are listed by GUID and those the \
+             save doesn't hold yet are left out"
+        );
+    }
+    let resources = owned_resources(&save.root.properties, &db);
+
+    let version = SaveVersion::of(&save);
+    let game = match compatibility(&version) {
+        Compatibility::Known(known) => Some(known.game),
+        Compatibility::Older | Compatibility::Newer => None,
+    };
+    let stats = Stats {
+        version,
+        game,
+        experimental: !experimental_properties(&save).is_empty(),
+        blue_level: blue_level(class_saves)?,
+        classes,
+        credits,
+        resources,
+    };
+    output.print(&stats, print_stats)
+}
+
+fn print_stats(stats: &Stats) {
+    println!("version: {} ({})", stats.version, stats.game.unwrap_or("unknown game version"));
+    if stats.experimental {
+        println!("experimental branch");
+    }
+    println!("blue level: {}", stats.blue_level);
+    println!("classes:");
+    for class in &stats.classes {
+        let name = match (&class.name, class.savegame_id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(id)) => id.to_string(),
+            (None, None) => "<no SavegameID>".to_string(),
+        };
+        println!(
+            "  {}: {name}: {} red levels ({} promotions, {} retired levels), {} XP",
+            class.slot, class.red_levels, class.times_retired, class.retired_levels, class.xp
+        );
+    }
+    match stats.credits {
+        Some(credits) => println!("credits: {credits}"),
+        None => println!("credits: <not found>"),
+    }
+    println!("resources:");
+    for resource in &stats.resources {
+        match &resource.name {
+            Some(name) => println!("  {name}: {}", resource.amount),
+            None => println!("  {}: {}", resource.guid, resource.amount),
+        }
+    }
+}
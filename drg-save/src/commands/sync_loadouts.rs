@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use drg_save_core::classes::{class_saves, resolve_class, savegame_id, ClassSelector};
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::loadout::sync_loadouts;
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The save to copy the loadouts from. It is not modified.
+    source: PathBuf,
+    /// Path to the save file to copy the loadouts to.
+    /// Defaults to `save-path` from the config file.
+    target: Option<PathBuf>,
+    /// Only sync this class, by name or class save slot. Can be given multiple times.
+    #[arg(long)]
+    class: Vec<ClassSelector>,
+}
+
+pub fn run(Args { source, target, class }: Args) -> Result<()> {
+    let target = crate::settings::save_path(target)?;
+    let source = drg_save_core::read_save(&source)?;
+    let db = GuidDb::load()?;
+
+    let mut ids = vec![];
+    for class in &class {
+        let class_saves = class_saves(&source)?;
+        let slot = resolve_class(class_saves, class, &db)?;
+        ids.push(savegame_id(&class_saves[slot]).context("class save has no `SavegameID`")?);
+    }
+    let classes = if class.is_empty() { None } else { Some(ids.as_slice()) };
+
+    info!("editing save file: `{}`", target.display());
+    crate::settings::edit_with(&target, "drg-save sync-loadouts", |save| {
+        for id in sync_loadouts(save, &source, classes)? {
+            info!("synced loadout of {}", db.label(id));
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use drg_save_core::classes::{
+    class_saves, class_saves_mut, find_class_save, resolve_class, ClassSelector,
+};
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::template::{
+    export_template, instantiate, list_templates, load_template, remove_template, save_template,
+};
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Store a class save as a named template, replacing a template of the same name.
+    Export {
+        name: String,
+        /// Path to the save file to export from. This file is not modified.
+        /// Defaults to `save-path` from the config file.
+        path: Option<PathBuf>,
+        /// Class name or index of the class save slot in `CharacterSaves` to export.
+        #[arg(long)]
+        class: ClassSelector,
+    },
+    /// Overwrite class saves with templates. Each template goes to the class it was exported
+    /// from, unless `--to` is given.
+    Apply {
+        /// Path to the save file that you want to edit.
+        /// Defaults to `save-path` from the config file.
+        path: Option<PathBuf>,
+        /// Name of a template to apply. Can be given multiple times.
+        #[arg(short, long = "template", required = true)]
+        templates: Vec<String>,
+        /// Class name or index of the class save slot to apply a single template to.
+        #[arg(long)]
+        to: Option<ClassSelector>,
+    },
+    /// List the stored templates.
+    List,
+    /// Delete a template.
+    Remove { name: String },
+}
+
+pub fn run(args: Args, output: OutputFormat) -> Result<()> {
+    match args {
+        Args::Export { name, path, class } => {
+            let path = crate::settings::save_path(path)?;
+            let save = drg_save_core::read_save(&path)?;
+            let db = GuidDb::load()?;
+            let class_saves = class_saves(&save)?;
+            let slot = resolve_class(class_saves, &class, &db)?;
+            let template_path = save_template(&name, &export_template(&class_saves[slot])?)?;
+            info!("exported {class} as template `{name}` to `{}`", template_path.display());
+        }
+        Args::Apply { path, templates, to } => {
+            if to.is_some() && templates.len() > 1 {
+                bail!("`--to` can only be given with a single template");
+            }
+            let templates = templates
+                .iter()
+                .map(|name| Ok((name, load_template(name)?)))
+                .collect::<Result<Vec<_>>>()?;
+            let path = crate::settings::save_path(path)?;
+            info!("editing save file: `{}`", path.display());
+            crate::settings::edit_with(&path, "drg-save template apply", |save| {
+                let db = GuidDb::load()?;
+                let class_saves = class_saves_mut(save)?;
+                for (name, template) in &templates {
+                    let slot = match &to {
+                        Some(class) => resolve_class(class_saves, class, &db)?,
+                        None => find_class_save(class_saves, template.savegame_id)
+                            .with_context(|| format!("cannot apply template `{name}`"))?,
+                    };
+                    instantiate(&mut class_saves[slot], template)?;
+                    info!("applied template `{name}` to class save slot {slot}");
+                }
+                Ok(())
+            })?;
+        }
+        Args::List => {
+            let names = list_templates()?;
+            output.print(&names, |names| {
+                for name in names {
+                    println!("{name}");
+                }
+            })?;
+        }
+        Args::Remove { name } => {
+            if !remove_template(&name)? {
+                bail!("no template `{name}`");
+            }
+            info!("removed template `{name}`");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Subcommand;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::report::report;
+use drg_save_core::track::{Record, TrackDb};
+use tracing::*;
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Subcommand)]
+pub enum Args {
+    /// Record the current blue level, class levels, credits and resources of a save.
+    Record {
+        /// Path to the save file to record. This file is not modified.
+        /// Defaults to `save-path` from the config file.
+        path: Option<PathBuf>,
+    },
+    /// Print the recorded history, oldest first.
+    Show {
+        /// Only show records of this save file.
+        path: Option<PathBuf>,
+        /// Plot this metric over time instead of printing the records.
+        #[arg(long, value_enum)]
+        plot: Option<Metric>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum Metric {
+    BlueLevel,
+    Credits,
+}
+
+pub fn run(args: Args, output: OutputFormat) -> Result<()> {
+    match args {
+        Args::Record { path } => {
+            let path = crate::settings::save_path(path)?;
+            record(&mut TrackDb::open_default()?, &path)?;
+            info!("recorded progress of `{}`", path.display());
+            Ok(())
+        }
+        Args::Show { path, plot } => {
+            let history = TrackDb::open_default()?.history(path.as_deref())?;
+            match plot {
+                Some(metric) => {
+                    plot_metric(&history, metric);
+                    Ok(())
+                }
+                None => output.print(&history, |history| print_history(history)),
+            }
+        }
+    }
+}
+
+/// Record the metrics of the save at `path` in `db`.
+pub fn record(db: &mut TrackDb, path: &Path) -> Result<()> {
+    let save = drg_save_core::read_save_mapped(path)?;
+    db.record(path, &report(&save, &GuidDb::load()?)?)
+}
+
+fn print_history(history: &[Record]) {
+    for record in history {
+        let classes: Vec<_> = record
+            .classes
+            .iter()
+            .map(|class| format!("{} {}/{}", class.name, class.level, class.promotions))
+            .collect();
+        let credits = record.credits.map_or("-".to_string(), |credits| credits.to_string());
+        println!(
+            "{} {}\tblue level {}\tcredits {credits}\t{}",
+            record.timestamp,
+            record.source.display(),
+            record.blue_level,
+            classes.join(", ")
+        );
+    }
+}
+
+/// One line per record with a bar proportional to the metric.
+fn plot_metric(history: &[Record], metric: Metric) {
+    let values: Vec<i64> = history
+        .iter()
+        .map(|record| match metric {
+            Metric::BlueLevel => i64::from(record.blue_level),
+            Metric::Credits => i64::from(record.credits.unwrap_or_default()),
+        })
+        .collect();
+    let min = values.iter().copied().min().unwrap_or_default().min(0);
+    let max = values.iter().copied().max().unwrap_or_default();
+    const WIDTH: i64 = 60;
+    for (record, value) in history.iter().zip(values) {
+        let len = if max > min { (value - min) * WIDTH / (max - min) } else { 0 };
+        println!("{}\t{value:>10} {}", record.timestamp, "#".repeat(len as usize));
+    }
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use anyhow::{ensure, Result};
+use drg_save_core::journal::{read_journal, undo_entry, write_journal};
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Path to the save file that you want to edit.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+    /// Number of journaled writes to undo, most recent first.
+    #[arg(short, default_value_t = 1)]
+    n: usize,
+    /// Undo changes even if the properties were modified since they were journaled.
+    #[arg(long)]
+    force: bool,
+}
+
+pub fn run(Args { path, n, force }: Args) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let mut session = crate::settings::open_session(&path, "drg-save undo")?;
+    let mut journal = read_journal(&path)?;
+    ensure!(
+        n <= journal.len(),
+        "cannot undo {n} writes, the journal only has {} entries",
+        journal.len()
+    );
+
+    info!("editing save file: `{}`", path.display());
+    for entry in journal.drain(journal.len() - n..).rev() {
+        undo_entry(session.save_mut(), &entry, force)?;
+        info!(
+            "undid {} changed properties of `{}` at {}",
+            entry.changes.len(),
+            entry.tool,
+            entry.timestamp
+        );
+    }
+
+    if !crate::settings::confirm(&mut session)? {
+        info!("changes not confirmed, leaving `{}` untouched", path.display());
+        return Ok(());
+    }
+    // The undone entries are dropped from the journal instead of journaling the undo itself.
+    crate::settings::commit(&mut session)?;
+    write_journal(&path, &journal)?;
+    Ok(())
+}
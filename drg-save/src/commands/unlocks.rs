@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use drg_save_core::guid_db::GuidDb;
+use drg_save_core::unlocks::{list_unlocks, UnlockKind};
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// What to list: `drinks` or `pickaxe-parts`.
+    #[arg(long)]
+    kind: UnlockKind,
+    /// Path to the save file to inspect. This file is not modified.
+    /// Defaults to `save-path` from the config file.
+    path: Option<PathBuf>,
+}
+
+pub fn run(Args { kind, path }: Args, output: OutputFormat) -> Result<()> {
+    let path = crate::settings::save_path(path)?;
+    let save = drg_save_core::read_save(&path)?;
+    let db = GuidDb::load()?;
+    let unlocks = list_unlocks(&save.root.properties, kind, &db)?;
+    output.print(&unlocks, |unlocks| {
+        for unlock in unlocks {
+            let state = if unlock.unlocked { "unlocked" } else { "locked" };
+            match &unlock.name {
+                Some(name) => println!("{state} {} {name}", unlock.guid),
+                None => println!("{state} {}", unlock.guid),
+            }
+        }
+    })
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use drg_save_core::integrity::{verify_backups, verify_snapshots, Status, Verification};
+use serde::Serialize;
+use tracing::*;
+
+use crate::exit::ValidationFailed;
+use crate::output::OutputFormat;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    backups: Vec<Verification>,
+    snapshots: Vec<Verification>,
+}
+
+pub fn run(Args {}: Args, output: OutputFormat) -> Result<()> {
+    let report = Report {
+        backups: verify_backups()?,
+        snapshots: verify_snapshots(&crate::settings::snapshot_store()?)?,
+    };
+    let n_corrupt = report
+        .backups
+        .iter()
+        .chain(&report.snapshots)
+        .filter(|verification| matches!(verification.status, Status::Corrupt { .. }))
+        .count();
+    output.print(&report, |report| {
+        for (kind, verifications) in [("backup", &report.backups), ("snapshot", &report.snapshots)]
+        {
+            for Verification { copy, status } in verifications {
+                match status {
+                    Status::Ok => debug!("{kind} `{copy}` is intact"),
+                    Status::Missing => println!("{kind} {copy}: missing"),
+                    Status::Corrupt { reason } => println!("{kind} {copy}: corrupt: {reason}"),
+                }
+            }
+        }
+        println!(
+            "checked {} backups and {} snapshots",
+            report.backups.len(),
+            report.snapshots.len()
+        );
+    })?;
+    if n_corrupt > 0 {
+        return Err(ValidationFailed(format!("{n_corrupt} corrupt backups or snapshots")).into());
+    }
+    Ok(())
+}
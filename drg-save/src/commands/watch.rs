@@ -0,0 +1,86 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use anyhow::Result;
+use drg_save_core::snapshot::SnapshotStore;
+use drg_save_core::track::TrackDb;
+use notify::{EventKind, RecursiveMode, Watcher};
+use tracing::*;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The game's save directory, e.g. `.../FSD/Saved/SaveGames`.
+    dir: PathBuf,
+    /// Seconds without further writes to a save before it is snapshotted. The game writes a save
+    /// in several steps, so snapshotting on the first event would catch it half-written.
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    debounce: u64,
+    /// Number of snapshots to keep per save file. Older ones are pruned.
+    #[arg(long, default_value_t = 50)]
+    keep: usize,
+    /// Also record each save's progress (see `track`).
+    #[arg(long)]
+    track: bool,
+}
+
+pub fn run(Args { dir, debounce, keep, track }: Args) -> Result<()> {
+    let store = crate::settings::snapshot_store()?;
+    let mut track_db = if track { Some(TrackDb::open_default()?) } else { None };
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    info!("watching `{}` for save file writes", dir.display());
+
+    let mut pending = BTreeSet::new();
+    loop {
+        // With nothing pending there's nothing to debounce, so wait for the next write.
+        let event = if pending.is_empty() {
+            rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            rx.recv_timeout(Duration::from_secs(debounce))
+        };
+        match event {
+            Ok(event) => {
+                let event = event?;
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    pending.extend(event.paths.into_iter().filter(|path| is_save(path)));
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                for path in std::mem::take(&mut pending) {
+                    if let Err(e) = snapshot(&store, &path, keep) {
+                        error!("failed to snapshot `{}`: {e:#}", path.display());
+                    }
+                    if let Some(db) = track_db.as_mut().filter(|_| path.exists()) {
+                        if let Err(e) = super::track::record(db, &path) {
+                            error!("failed to record progress of `{}`: {e:#}", path.display());
+                        }
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Ok(())
+}
+
+/// Only `.sav` files, not the `.sav.bak` backups or journals written next to them.
+fn is_save(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "sav")
+}
+
+fn snapshot(store: &SnapshotStore, path: &Path, keep: usize) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let snapshot = store.create(path, "watch")?;
+    info!("created snapshot {} of `{}`", snapshot.hash, path.display());
+    let n_pruned = store.prune(path, keep)?;
+    if n_pruned > 0 {
+        debug!("pruned {n_pruned} old snapshots of `{}`", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Exit codes and the error reported when a command fails. Both are a stable contract for scripts
+//! and launchers wrapping the CLI, so they can react to each kind of failure without matching on
+//! messages: new kinds get new codes, existing codes keep their meaning.
+
+use std::fmt;
+use std::process::ExitCode;
+
+use drg_save_core::DrgSaveError;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
+
+/// Why a command failed. The discriminant is the exit code of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    /// The save or the request didn't pass a check: the save isn't laid out as expected, a class
+    /// or item is unknown, a backup is corrupt, ...
+    Validation = 1,
+    /// A save, backup or snapshot could not be parsed.
+    Parse = 2,
+    /// The save is locked by another tool, or was written by one since it was read.
+    Locked = 3,
+    /// The save is from a game version the tool doesn't support.
+    Version = 4,
+    /// Reading or writing a file failed.
+    Io = 5,
+    /// The command line is invalid.
+    Usage = 6,
+    /// Anything else.
+    Other = 10,
+}
+
+impl Failure {
+    /// Classify `error` by the first cause in its chain that says what went wrong.
+    pub fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<DrgSaveError>() {
+                return match error {
+                    DrgSaveError::MissingProperty { .. }
+                    | DrgSaveError::TypeMismatch { .. }
+                    | DrgSaveError::UnexpectedShape(_)
+                    | DrgSaveError::InvalidInput(_)
+                    | DrgSaveError::MissingGuidData { .. } => Self::Validation,
+                    DrgSaveError::Usage(_) => Self::Usage,
+                    DrgSaveError::Locked { .. } | DrgSaveError::Changed { .. } => Self::Locked,
+                    DrgSaveError::UnsupportedVersion { .. } => Self::Version,
+                    DrgSaveError::Io(_) => Self::Io,
+                    DrgSaveError::Parse { .. } => Self::Parse,
+                };
+            }
+            if cause.is::<ValidationFailed>() {
+                return Self::Validation;
+            }
+            if let Some(BatchFailed { kind, .. }) = cause.downcast_ref() {
+                return *kind;
+            }
+            if cause.is::<uesave::Error>() {
+                return Self::Parse;
+            }
+            if cause.is::<std::io::Error>() {
+                return Self::Io;
+            }
+        }
+        Self::Other
+    }
+
+    pub fn exit_code(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Error of a command whose point is a check, e.g. `verify` or `schema --check`, failing it.
+#[derive(Debug)]
+pub struct ValidationFailed(pub String);
+
+impl fmt::Display for ValidationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValidationFailed {}
+
+/// Error of a batch command some of whose items failed, failing with the worst of their kinds
+/// (the one with the highest exit code).
+#[derive(Debug)]
+pub struct BatchFailed {
+    pub kind: Failure,
+    pub n_failed: usize,
+    pub n_total: usize,
+}
+
+impl BatchFailed {
+    /// The error for a batch of `n_total` items which failed with `kinds`, if any did.
+    pub fn of(kinds: &[Failure], n_total: usize) -> Option<Self> {
+        let kind = kinds.iter().copied().max_by_key(|kind| kind.exit_code())?;
+        Some(Self { kind, n_failed: kinds.len(), n_total })
+    }
+}
+
+impl fmt::Display for BatchFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} failed", self.n_failed, self.n_total)
+    }
+}
+
+impl std::error::Error for BatchFailed {}
+
+/// The error printed on stderr with `--output json`, as the last line.
+#[derive(Debug, Serialize)]
+struct ErrorReport {
+    kind: Failure,
+    exit_code: u8,
+    message: String,
+    /// The chain of causes below `message`, outermost first.
+    causes: Vec<String>,
+}
+
+/// Print `error` on stderr and return the exit code for it.
+pub fn report(error: &anyhow::Error, output: OutputFormat) -> ExitCode {
+    let kind = Failure::of(error);
+    match output {
+        OutputFormat::Text => eprintln!("Error: {error:?}"),
+        OutputFormat::Json => {
+            let report = ErrorReport {
+                kind,
+                exit_code: kind.exit_code(),
+                message: error.to_string(),
+                causes: error.chain().skip(1).map(ToString::to_string).collect(),
+            };
+            match serde_json::to_string(&report) {
+                Ok(json) => eprintln!("{json}"),
+                Err(_) => eprintln!("Error: {error:?}"),
+            }
+        }
+    }
+    ExitCode::from(kind.exit_code())
+}
+
+/// Exit on a command line clap couldn't parse, with [`Failure::Usage`] rather than clap's own
+/// code. `--help` and `--version` still exit successfully. Generic over what it would return, so
+/// it can be passed to `unwrap_or_else` as is.
+pub fn clap_exit<T>(error: clap::Error) -> T {
+    if !error.use_stderr() {
+        error.exit();
+    }
+    let _ = error.print();
+    std::process::exit(Failure::Usage.exit_code().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context};
+    use drg_save_core::guid_db::GuidKind;
+
+    use super::*;
+
+    #[test]
+    fn classifies_core_errors() {
+        let of = |error: DrgSaveError| Failure::of(&error.into());
+        assert_eq!(of(DrgSaveError::InvalidInput("unknown class".into())), Failure::Validation);
+        assert_eq!(of(DrgSaveError::MissingProperty { path: "XP".into() }), Failure::Validation);
+        assert_eq!(
+            of(DrgSaveError::MissingGuidData { kind: GuidKind::Resource }),
+            Failure::Validation
+        );
+        assert_eq!(of(DrgSaveError::Usage("nothing to do".into())), Failure::Usage);
+        assert_eq!(of(DrgSaveError::Locked { path: "a.sav".into() }), Failure::Locked);
+        assert_eq!(of(DrgSaveError::UnsupportedVersion { version: "1".into() }), Failure::Version);
+    }
+
+    #[test]
+    fn classifies_by_the_first_known_cause() {
+        let error = anyhow!(DrgSaveError::Usage("either `--set` or `--add` is required".into()))
+            .context("failed to edit `a.sav`");
+        assert_eq!(Failure::of(&error), Failure::Usage);
+        let error: anyhow::Error = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+        assert_eq!(Failure::of(&error.context("reading")), Failure::Io);
+        assert_eq!(Failure::of(&anyhow!("something else")), Failure::Other);
+    }
+
+    #[test]
+    fn batch_fails_with_the_worst_kind() {
+        assert!(BatchFailed::of(&[], 3).is_none());
+        let failed = BatchFailed::of(&[Failure::Validation, Failure::Io], 3).unwrap();
+        assert_eq!((failed.kind, failed.n_failed), (Failure::Io, 2));
+        let error = Err::<(), _>(failed).context("failed to edit saves").unwrap_err();
+        assert_eq!(Failure::of(&error), Failure::Io);
+    }
+}
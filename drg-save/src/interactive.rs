@@ -0,0 +1,33 @@
+//! `--interactive`: show the changes an edit would make and ask before writing them.
+
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use drg_save_core::diff::describe_property_differences;
+use uesave::Save;
+
+static INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn init(interactive: bool) {
+    INTERACTIVE.store(interactive, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// Print the changes from `old` to `new` and ask whether to write them to `path`. Anything but
+/// `y` or `yes` declines.
+pub fn confirm(path: &Path, old: &Save, new: &Save) -> Result<bool> {
+    println!("changes to `{}`:", path.display());
+    for line in describe_property_differences(&old.root.properties, &new.root.properties) {
+        println!("  {line}");
+    }
+    print!("write these changes? [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,103 @@
+mod cli;
+mod commands;
+mod exit;
+mod interactive;
+mod output;
+mod progress;
+mod settings;
+
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, ValueEnum};
+use cli::{Args, Command};
+use drg_save_core::config::Config;
+use output::OutputFormat;
+
+fn main() -> ExitCode {
+    let args = Args::try_parse().unwrap_or_else(exit::clap_exit);
+    logging::setup_logging(&args.log);
+    // Until the config is loaded, errors are reported in the format given on the command line.
+    let output = match setup(&args) {
+        Ok(output) => output,
+        Err(e) => return exit::report(&e, args.output.unwrap_or_default()),
+    };
+    match run(args.command, output) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => exit::report(&e, output),
+    }
+}
+
+/// Load the config, apply the global arguments to it and pick the output format.
+fn setup(args: &Args) -> Result<OutputFormat> {
+    let Args { output, interactive, force, lenient, no_backup, backup_dir, profile, .. } = args;
+    let mut config = Config::load()?;
+    if let Some(profile) = profile {
+        config.select_profile(profile)?;
+    }
+    if *no_backup {
+        config.backup = Some(false);
+    }
+    if backup_dir.is_some() {
+        config.backup_dir = backup_dir.clone();
+    }
+    settings::init(config, *force, *lenient);
+    interactive::init(*interactive);
+    Ok(match output {
+        Some(output) => *output,
+        None => match &settings::config().output {
+            Some(output) => OutputFormat::from_str(output, true)
+                .map_err(|e| anyhow!("invalid `output` in config file: {e}"))?,
+            None => OutputFormat::default(),
+        },
+    })
+}
+
+fn run(command: Command, output: OutputFormat) -> Result<()> {
+    match command {
+        Command::Anonymize(args) => commands::anonymize::run(args),
+        Command::ApplyPatch(args) => commands::apply_patch::run(args),
+        Command::Checkout(args) => commands::snapshot::checkout(args),
+        Command::CloneClass(args) => commands::clone_class::run(args),
+        Command::Completions(args) => commands::completions::completions(args),
+        Command::Cosmetics(args) => commands::cosmetics::run(args, output),
+        Command::Db(args) => commands::db::run(args, output),
+        Command::Diff(args) => commands::diff::run(args, output),
+        Command::Edit(args) => commands::edit::run(args, output),
+        Command::Events(args) => commands::events::run(args, output),
+        Command::Find(args) => commands::find::run(args, output),
+        Command::Gc(args) => commands::gc::run(args, output),
+        Command::Get(args) => commands::get::run(args, output),
+        Command::Identity(args) => commands::identity::run(args, output),
+        Command::Loadout(args) => commands::loadout::run(args),
+        Command::Locate(args) => commands::locate::run(args, output),
+        Command::Log(args) => commands::snapshot::log(args, output),
+        Command::Man(args) => commands::completions::man(args),
+        Command::Migrate(args) => commands::migrate::run(args, output),
+        Command::Milestones(args) => commands::milestones::run(args, output),
+        Command::Missions(args) => commands::missions::run(args, output),
+        Command::Pools(args) => commands::pools::run(args, output),
+        Command::Profile(args) => commands::profile::run(args, output),
+        Command::Raw(args) => commands::raw::run(args),
+        Command::Rebind(args) => commands::rebind::run(args),
+        Command::Repl(args) => commands::repl::run(args),
+        Command::Report(args) => commands::report::run(args),
+        Command::ResetClass(args) => commands::reset_class::run(args),
+        Command::Restore(args) => commands::restore::run(args),
+        Command::Rewards(args) => commands::rewards::run(args, output),
+        Command::Schema(args) => commands::schema::run(args, output),
+        Command::Season(args) => commands::season::run(args, output),
+        Command::Serve(args) => commands::serve::run(args),
+        Command::Service(args) => commands::service::run(args, output),
+        Command::Simulate(args) => commands::simulate::run(args, output),
+        Command::Snapshot(args) => commands::snapshot::snapshot(args, output),
+        Command::Stats(args) => commands::stats::run(args, output),
+        Command::SyncLoadouts(args) => commands::sync_loadouts::run(args),
+        Command::Template(args) => commands::template::run(args, output),
+        Command::Track(args) => commands::track::run(args, output),
+        Command::Undo(args) => commands::undo::run(args),
+        Command::Unlocks(args) => commands::unlocks::run(args, output),
+        Command::Verify(args) => commands::verify::run(args, output),
+        Command::Watch(args) => commands::watch::run(args),
+    }
+}
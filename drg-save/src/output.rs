@@ -0,0 +1,23 @@
+use anyhow::Result;
+use serde::Serialize;
+
+/// Format of the results printed on stdout. Logs always go to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A single JSON document, for scripts and other programs.
+    Json,
+}
+
+impl OutputFormat {
+    /// Print `value` as JSON in JSON mode, or call `text` to print it as text otherwise.
+    pub fn print<T: Serialize>(self, value: &T, text: impl FnOnce(&T)) -> Result<()> {
+        match self {
+            Self::Text => text(value),
+            Self::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        }
+        Ok(())
+    }
+}
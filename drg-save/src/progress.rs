@@ -0,0 +1,37 @@
+//! Progress bars for long operations. They are drawn on stderr, and hidden when stdout isn't a
+//! terminal or results are printed as JSON, so they never end up in piped output.
+
+use std::io::IsTerminal;
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::output::OutputFormat;
+
+fn enabled(output: OutputFormat) -> bool {
+    output == OutputFormat::Text && std::io::stdout().is_terminal()
+}
+
+/// A bar counting `len` steps, e.g. saves of a batch or bytes of a download.
+pub fn bar(len: u64, message: &str, output: OutputFormat) -> ProgressBar {
+    if !enabled(output) {
+        return ProgressBar::hidden();
+    }
+    ProgressBar::new(len)
+        .with_style(
+            ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len}")
+                .expect("progress template is valid")
+                .progress_chars("=> "),
+        )
+        .with_message(message.to_string())
+}
+
+/// A spinner for operations without a known number of steps.
+pub fn spinner(message: &str, output: OutputFormat) -> ProgressBar {
+    if !enabled(output) {
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner().with_message(message.to_string());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
@@ -0,0 +1,154 @@
+//! The user configuration (see [`drg_save_core::config`]), loaded once at startup.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use drg_save_core::config::Config;
+use drg_save_core::locate::{find_saves, Candidate};
+use drg_save_core::snapshot::SnapshotStore;
+use drg_save_core::{Edited, SaveSession, SessionOptions};
+use tracing::*;
+use uesave::Save;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// `--force`: edit saves from newer game versions.
+static FORCE: AtomicBool = AtomicBool::new(false);
+/// `--lenient`: keep top-level properties that can't be parsed as they are.
+static LENIENT: AtomicBool = AtomicBool::new(false);
+
+pub fn init(config: Config, force: bool, lenient: bool) {
+    CONFIG.set(config).expect("config is only initialized once");
+    FORCE.store(force, Ordering::Relaxed);
+    LENIENT.store(lenient, Ordering::Relaxed);
+}
+
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// The save file given on the command line, falling back to `save-path` from the config, and then
+/// to the saves found on this machine (see [`drg_save_core::locate`]). With several found, the user
+/// picks one if there is a terminal to ask on.
+pub fn save_path(path: Option<PathBuf>) -> Result<PathBuf> {
+    if let Some(path) = path.or_else(|| config().save_path.clone()) {
+        return Ok(path);
+    }
+    let mut candidates = find_saves()?;
+    match candidates.len() {
+        0 => bail!("no save file given, no `save-path` in the config file and no save found"),
+        1 => {
+            let path = candidates.remove(0).path;
+            info!("using save file found at `{}`", path.display());
+            Ok(path)
+        }
+        _ if io::stdin().is_terminal() => pick_save(candidates),
+        _ => bail!(
+            "no save file given and no `save-path` in the config file, found several saves (see \
+             `drg-save locate`)"
+        ),
+    }
+}
+
+fn pick_save(candidates: Vec<Candidate>) -> Result<PathBuf> {
+    // On stderr, so the output of the command stays parseable.
+    eprintln!("found several save files, most recently modified first:");
+    for (i, candidate) in candidates.iter().enumerate() {
+        eprintln!("  {i}: {}", candidate.path.display());
+    }
+    eprint!("use which one? [0] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    let i = if answer.is_empty() { 0 } else { answer.parse().context("expected a number")? };
+    let candidate = candidates.into_iter().nth(i).context("no such save file")?;
+    Ok(candidate.path)
+}
+
+/// Environment variable holding the snapshot passphrase, for running without a terminal to ask
+/// on (e.g. `watch` as a service).
+const PASSPHRASE_VAR: &str = "DRG_SAVE_PASSPHRASE";
+
+/// The default snapshot store, encrypting snapshots if `encrypt-snapshots` is set in the config.
+/// The passphrase is taken from `DRG_SAVE_PASSPHRASE`, or asked for.
+pub fn snapshot_store() -> Result<SnapshotStore> {
+    let store = SnapshotStore::open_default()?;
+    let passphrase = match std::env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) => passphrase,
+        Err(_) if config().encrypt_snapshots == Some(true) => {
+            rpassword::prompt_password("snapshot passphrase: ")
+                .with_context(|| format!("failed to read passphrase, set `{PASSPHRASE_VAR}`"))?
+        }
+        Err(_) => return Ok(store),
+    };
+    Ok(store.with_passphrase(passphrase))
+}
+
+/// The backup settings in the config, and whether `--force` and `--lenient` were passed.
+pub fn session_options() -> SessionOptions {
+    SessionOptions {
+        backup: config().backup_options(),
+        force: FORCE.load(Ordering::Relaxed),
+        lenient: LENIENT.load(Ordering::Relaxed),
+    }
+}
+
+/// Open a [`SaveSession`] on the save file at `path` with the [`session_options`].
+pub fn open_session(path: &Path, tool: &str) -> Result<SaveSession> {
+    SaveSession::open(path, tool, session_options())
+}
+
+/// With `--interactive`, ask whether to write the changes made in `session`, with the save
+/// unlocked meanwhile (see [`SaveSession::unlocked`]). Without it, or without changes, there is
+/// nothing to ask and this returns `true`.
+pub fn confirm(session: &mut SaveSession) -> Result<bool> {
+    if !crate::interactive::enabled() || !session.is_dirty()? {
+        return Ok(true);
+    }
+    let original = session.original()?;
+    session
+        .unlocked(|session| crate::interactive::confirm(session.path(), &original, session.save()))
+}
+
+/// [`drg_save_core::edit_with`] with the [`session_options`], asking before writing with
+/// `--interactive`.
+pub fn edit_with<T>(
+    path: &Path,
+    tool: &str,
+    edit: impl FnOnce(&mut Save) -> Result<T>,
+) -> Result<Edited<T>> {
+    edit_with_options(path, tool, &session_options(), edit)
+}
+
+/// [`edit_with`] with other options than the [`session_options`].
+pub fn edit_with_options<T>(
+    path: &Path,
+    tool: &str,
+    options: &SessionOptions,
+    edit: impl FnOnce(&mut Save) -> Result<T>,
+) -> Result<Edited<T>> {
+    let edited = if crate::interactive::enabled() {
+        drg_save_core::edit_with_confirm(path, tool, options, edit, |old, new| {
+            crate::interactive::confirm(path, old, new)
+        })?
+    } else {
+        drg_save_core::edit_with(path, tool, options, edit)?
+    };
+    if edited.written {
+        info!("replaced `{}` with modified save file", path.display());
+    }
+    Ok(edited)
+}
+
+/// [`SaveSession::commit`], logging the write like [`edit_with`].
+pub fn commit(session: &mut SaveSession) -> Result<bool> {
+    let written = session.commit()?;
+    if written {
+        info!("replaced `{}` with modified save file", session.path().display());
+    }
+    Ok(written)
+}
@@ -1,22 +1,72 @@
+use std::path::PathBuf;
+
 use tracing::*;
 
-pub fn setup_logging() {
+/// Logging flags shared by the binaries.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct LogArgs {
+    /// Log more: `-v` for debug logs, `-vv` for trace logs. `RUST_LOG` takes precedence.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+    /// Format of the logs on stderr and in the log file.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub log_format: LogFormat,
+    /// Also log to this file, with timestamps. A new file (suffixed with the date) is started
+    /// every day.
+    #[arg(long, global = true)]
+    pub log_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+pub fn setup_logging(args: &LogArgs) {
     use tracing::metadata::LevelFilter;
     use tracing_subscriber::prelude::*;
-    use tracing_subscriber::{fmt, EnvFilter};
-
-    let stderr_log = fmt::layer()
-        .with_writer(std::io::stderr)
-        .compact()
-        .with_level(true)
-        .with_target(true)
-        .without_time()
-        .with_filter(
-            EnvFilter::builder().with_default_directive(LevelFilter::INFO.into()).from_env_lossy(),
-        );
-    let subscriber = tracing_subscriber::registry().with(stderr_log);
+    use tracing_subscriber::EnvFilter;
+
+    let level = match args.verbose {
+        0 => LevelFilter::INFO,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let filter = || EnvFilter::builder().with_default_directive(level.into()).from_env_lossy();
+
+    let stderr_log = layer(args.log_format, std::io::stderr, false).with_filter(filter());
+    let file_log = args.log_file.as_ref().map(|path| {
+        let dir = path.parent().unwrap_or(std::path::Path::new("."));
+        let name = path.file_name().unwrap_or("drg-save.log".as_ref());
+        let appender = tracing_appender::rolling::daily(dir, name);
+        layer(args.log_format, appender, true).with_filter(filter())
+    });
+    let subscriber = tracing_subscriber::registry().with(stderr_log).with(file_log);
 
     tracing::subscriber::set_global_default(subscriber).unwrap();
 
     debug!("tracing subscriber setup");
 }
+
+/// A formatting layer writing to `writer`. Logs to a file get timestamps and no colors.
+fn layer<S, W>(
+    format: LogFormat,
+    writer: W,
+    file: bool,
+) -> Box<dyn tracing_subscriber::Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    use tracing_subscriber::{fmt, Layer};
+
+    let layer = fmt::layer().with_writer(writer).with_level(true).with_target(true);
+    match (format, file) {
+        (LogFormat::Json, _) => layer.json().boxed(),
+        (LogFormat::Text, false) => layer.compact().without_time().boxed(),
+        (LogFormat::Text, true) => layer.compact().with_ansi(false).boxed(),
+    }
+}